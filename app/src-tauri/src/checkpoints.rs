@@ -0,0 +1,416 @@
+//! Git-backed checkpoints for applied patches. Each checkpoint is a commit on a private ref,
+//! refs/devassistant/checkpoints/<session>, chained before -> after. Trees are built from an
+//! in-memory copy of the index plus the touched files, so the user's index, HEAD and branch
+//! are never modified; restore writes files straight from the checkpoint tree.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use git2::{Commit, DiffOptions, ObjectType, Signature};
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult, IoResultExt};
+use crate::git::{collect_diff, err, format_time, short, GitDiff, Repo};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::workspace;
+
+const REF_PREFIX: &str = "refs/devassistant/checkpoints/";
+const SUMMARY_PREFIX: &str = "devassistant checkpoint: ";
+const PATH_TRAILER: &str = "Path: ";
+/// Checkpoints followed per session chain.
+const MAX_CHAIN: usize = 100;
+/// Shortest commit id prefix accepted in place of a label.
+const MIN_ID_PREFIX: usize = 4;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub commit: String,
+    pub short_id: String,
+    /// "before", "after", …
+    pub label: String,
+    pub time: String,
+    /// Workspace-relative files captured (empty = whole workspace).
+    pub paths: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCheckpoints {
+    pub session_id: String,
+    pub ref_name: String,
+    /// Newest first.
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub commit: String,
+    /// Files written from the checkpoint.
+    pub restored: Vec<String>,
+    /// Files deleted because the checkpoint does not have them.
+    pub removed: Vec<String>,
+}
+
+fn ref_name(session_id: &str) -> AppResult<String> {
+    let id: String = session_id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if id.is_empty() {
+        return Err(AppError::invalid("session_id is required"));
+    }
+    Ok(format!("{}{}", REF_PREFIX, id))
+}
+
+fn signature(r: &Repo) -> AppResult<Signature<'static>> {
+    r.repo
+        .signature()
+        .or_else(|_| Signature::now("DevAssistant", "devassistant@localhost"))
+        .map_err(err)
+}
+
+/// (label, workspace paths) from a checkpoint commit; None for other commits.
+fn parse_message(commit: &Commit) -> Option<(String, Vec<String>)> {
+    let message = commit.message()?;
+    let mut lines = message.lines();
+    let label = lines
+        .next()?
+        .strip_prefix(SUMMARY_PREFIX)?
+        .trim()
+        .to_string();
+    let paths = lines
+        .filter_map(|l| l.strip_prefix(PATH_TRAILER))
+        .map(|p| p.trim().to_string())
+        .collect();
+    Some((label, paths))
+}
+
+/// The session's checkpoints, newest first.
+fn chain<'r>(r: &'r Repo, ref_name: &str) -> AppResult<Vec<(Commit<'r>, String, Vec<String>)>> {
+    let Ok(reference) = r.repo.find_reference(ref_name) else {
+        return Ok(Vec::new());
+    };
+    let mut out = Vec::new();
+    let mut next = Some(reference.peel_to_commit().map_err(err)?);
+    while let Some(commit) = next.take() {
+        let Some((label, paths)) = parse_message(&commit) else {
+            break;
+        };
+        next = commit.parent(0).ok();
+        out.push((commit, label, paths));
+        if out.len() >= MAX_CHAIN {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+fn to_checkpoint(commit: &Commit, label: &str, paths: &[String]) -> Checkpoint {
+    Checkpoint {
+        commit: commit.id().to_string(),
+        short_id: short(commit.id()),
+        label: label.to_string(),
+        time: format_time(commit.time()),
+        paths: paths.to_vec(),
+    }
+}
+
+/// A checkpoint of the session by label (newest with that label) or commit id prefix of at
+/// least MIN_ID_PREFIX hex digits.
+fn find<'r>(
+    chain: &'r [(Commit<'r>, String, Vec<String>)],
+    which: &str,
+) -> AppResult<&'r Commit<'r>> {
+    let which = which.trim();
+    if which.is_empty() {
+        return Err(AppError::invalid("checkpoint is required"));
+    }
+    let id_prefix = (which.len() >= MIN_ID_PREFIX && which.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| which.to_ascii_lowercase());
+    chain
+        .iter()
+        .find(|(c, label, _)| {
+            label == which
+                || id_prefix
+                    .as_deref()
+                    .is_some_and(|p| c.id().to_string().starts_with(p))
+        })
+        .map(|(c, _, _)| c)
+        .ok_or_else(|| AppError::not_found(format!("checkpoint not found: {}", which)))
+}
+
+/// Record the current content of `paths` (workspace-relative; all of the workspace when
+/// empty) as a checkpoint commit labelled e.g. "before"/"after" on the session's ref. Only
+/// reads the workspace (objects and the private ref go into the repository), so it also
+/// works in read-only workspaces.
+#[tauri::command]
+pub fn git_checkpoint_create(
+    workspace_root: String,
+    session_id: String,
+    label: String,
+    paths: Option<Vec<String>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Checkpoint> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let ref_name = ref_name(&session_id)?;
+    let label = label.trim().to_string();
+    if label.is_empty() || label.contains('\n') {
+        return Err(AppError::invalid("label is required"));
+    }
+    let workdir = r
+        .repo
+        .workdir()
+        .ok_or_else(|| AppError::not_found("not a git repository"))?
+        .to_path_buf();
+
+    // In-memory index: starts as the user's index, is never written back.
+    let mut index = r.repo.index().map_err(err)?;
+    let mut ws_paths: Vec<String> = Vec::new();
+    match paths.filter(|p| !p.is_empty()) {
+        Some(paths) => {
+            for p in paths {
+                let repo_path = r.to_repo(&p)?;
+                let rel = Path::new(&repo_path);
+                if workdir.join(rel).is_file() {
+                    index.add_path(rel).map_err(err)?;
+                } else {
+                    let _ = index.remove_path(rel);
+                }
+                ws_paths.extend(r.to_ws(rel));
+            }
+        }
+        None => {
+            let spec = r.pathspec(None)?.unwrap_or_else(|| "*".to_string());
+            index
+                .add_all([spec.as_str()], git2::IndexAddOption::DEFAULT, None)
+                .map_err(err)?;
+            index.update_all([spec.as_str()], None).map_err(err)?;
+        }
+    }
+    let tree = r
+        .repo
+        .find_tree(index.write_tree().map_err(err)?)
+        .map_err(err)?;
+
+    // Chain onto the session's last checkpoint, or start from HEAD.
+    let parent = match r.repo.find_reference(&ref_name) {
+        Ok(reference) => Some(reference.peel_to_commit().map_err(err)?),
+        Err(_) => r.repo.head().ok().and_then(|h| h.peel_to_commit().ok()),
+    };
+    let mut message = format!(
+        "{}{}\n\nSession: {}\n",
+        SUMMARY_PREFIX,
+        label,
+        session_id.trim()
+    );
+    for p in &ws_paths {
+        message.push_str(PATH_TRAILER);
+        message.push_str(p);
+        message.push('\n');
+    }
+    let sig = signature(&r)?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let oid = r
+        .repo
+        .commit(Some(&ref_name), &sig, &sig, &message, &tree, &parents)
+        .map_err(err)?;
+    let commit = r.repo.find_commit(oid).map_err(err)?;
+    Ok(to_checkpoint(&commit, &label, &ws_paths))
+}
+
+/// Every session with checkpoints, most recent first.
+#[tauri::command]
+pub fn git_checkpoint_list(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<SessionCheckpoints>> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let mut names = Vec::new();
+    for reference in r
+        .repo
+        .references_glob(&format!("{}*", REF_PREFIX))
+        .map_err(err)?
+        .flatten()
+    {
+        names.extend(reference.name().map(str::to_string));
+    }
+    let mut out = Vec::new();
+    for name in names {
+        let checkpoints: Vec<Checkpoint> = chain(&r, &name)?
+            .iter()
+            .map(|(c, label, paths)| to_checkpoint(c, label, paths))
+            .collect();
+        if checkpoints.is_empty() {
+            continue;
+        }
+        out.push(SessionCheckpoints {
+            session_id: name.trim_start_matches(REF_PREFIX).to_string(),
+            ref_name: name,
+            checkpoints,
+        });
+    }
+    out.sort_by(|a, b| b.checkpoints[0].time.cmp(&a.checkpoints[0].time));
+    Ok(out)
+}
+
+/// Diff between two checkpoints of a session (label or commit id; `to` may be "worktree").
+/// Defaults: from the oldest checkpoint to the newest.
+#[tauri::command]
+pub fn git_checkpoint_diff(
+    workspace_root: String,
+    session_id: String,
+    from: Option<String>,
+    to: Option<String>,
+    context_lines: Option<u32>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<GitDiff> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let chain = chain(&r, &ref_name(&session_id)?)?;
+    let (Some(newest), Some(oldest)) = (chain.first(), chain.last()) else {
+        return Err(AppError::not_found(format!(
+            "no checkpoints for session {}",
+            session_id
+        )));
+    };
+    let from = match from.as_deref() {
+        Some(w) => find(&chain, w)?,
+        None => &oldest.0,
+    };
+    let from_tree = from.tree().map_err(err)?;
+    let mut opts = DiffOptions::new();
+    opts.context_lines(context_lines.unwrap_or(3));
+    if let Some(spec) = r.pathspec(None)? {
+        opts.pathspec(spec);
+    }
+    let mut diff = match to.as_deref() {
+        Some("worktree") => {
+            opts.include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            r.repo
+                .diff_tree_to_workdir(Some(&from_tree), Some(&mut opts))
+                .map_err(err)?
+        }
+        to => {
+            let to = match to {
+                Some(w) => find(&chain, w)?,
+                None => &newest.0,
+            };
+            let to_tree = to.tree().map_err(err)?;
+            r.repo
+                .diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut opts))
+                .map_err(err)?
+        }
+    };
+    collect_diff(&r, &mut diff, false)
+}
+
+/// Write files back to their content at a checkpoint (label or commit id). Restores `paths`
+/// when given, otherwise every file the session's checkpoints touched. Files missing from
+/// the checkpoint are deleted. Every path goes through workspace::resolve and the workspace
+/// policy first. The index and HEAD are left alone.
+#[tauri::command]
+pub fn git_checkpoint_restore(
+    workspace_root: String,
+    session_id: String,
+    checkpoint: String,
+    paths: Option<Vec<String>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<RestoreResult> {
+    restore(&registry, &workspace_root, &session_id, &checkpoint, paths)
+}
+
+fn restore(
+    registry: &Mutex<WorkspaceRegistry>,
+    workspace_root: &str,
+    session_id: &str,
+    checkpoint: &str,
+    paths: Option<Vec<String>>,
+) -> AppResult<RestoreResult> {
+    registry::check_read(registry, workspace_root)?;
+    let r = Repo::require(workspace_root)?;
+    let chain = chain(&r, &ref_name(session_id)?)?;
+    let target = find(&chain, checkpoint)?;
+    let tree = target.tree().map_err(err)?;
+
+    let mut files: BTreeSet<String> = BTreeSet::new();
+    match paths.filter(|p| !p.is_empty()) {
+        Some(paths) => files.extend(paths),
+        None => {
+            for (_, _, recorded) in &chain {
+                files.extend(recorded.iter().cloned());
+            }
+            // Whole-workspace checkpoints record no paths; use what changed across the chain.
+            if let (Some(newest), Some(oldest)) = (chain.first(), chain.last()) {
+                let old_tree = oldest.0.tree().map_err(err)?;
+                let new_tree = newest.0.tree().map_err(err)?;
+                let diff = r
+                    .repo
+                    .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+                    .map_err(err)?;
+                for d in diff.deltas() {
+                    for f in [d.old_file(), d.new_file()] {
+                        files.extend(f.path().and_then(|p| r.to_ws(p)));
+                    }
+                }
+            }
+        }
+    }
+
+    let (mut restored, mut removed) = (Vec::new(), Vec::new());
+    // Check every path before touching any, so a refused one leaves the worktree as it was.
+    let mut targets = Vec::new();
+    let external = workspace::allow_external_symlinks();
+    for ws_path in files {
+        let repo_path = r.to_repo(&ws_path)?;
+        let full = workspace::resolve(workspace_root, &ws_path, external)?;
+        registry::check_write(registry, workspace_root, &full)?;
+        targets.push((ws_path, repo_path, full));
+    }
+    for (ws_path, repo_path, full) in targets {
+        match tree.get_path(Path::new(&repo_path)) {
+            Ok(entry) if entry.kind() == Some(ObjectType::Blob) => {
+                let blob = r.repo.find_blob(entry.id()).map_err(err)?;
+                if let Some(parent) = full.parent() {
+                    std::fs::create_dir_all(parent).at(parent)?;
+                }
+                std::fs::write(&full, blob.content()).at(&full)?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    let mode = if entry.filemode() == 0o100755 {
+                        0o755
+                    } else {
+                        0o644
+                    };
+                    let _ = std::fs::set_permissions(&full, std::fs::Permissions::from_mode(mode));
+                }
+                restored.push(ws_path);
+            }
+            _ => {
+                if full.is_file() {
+                    std::fs::remove_file(&full).at(&full)?;
+                    removed.push(ws_path);
+                }
+            }
+        }
+    }
+    Ok(RestoreResult {
+        commit: target.id().to_string(),
+        restored,
+        removed,
+    })
+}
@@ -0,0 +1,479 @@
+//! Check runner for build/test/lint. Only checks detected from the project's manifests can
+//! run (program + args, no shell); output streams as events and into a log under
+//! .devassistant/checks/. The finished event carries the session id so the frontend's
+//! MemoryStore, the only writer of sessions.json, can record the result.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::diagnostics::{self, Diagnostic, DiagnosticFormat, Severity};
+use crate::error::AppResult;
+use crate::project::{self, CommandKind, ProjectCommand};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::workspace::canonical_root;
+
+pub const OUTPUT_EVENT: &str = "checks://output";
+pub const FINISHED_EVENT: &str = "checks://finished";
+
+const CHECK_LOGS_DIR: &str = ".devassistant/checks";
+/// Check logs kept per workspace; older ones are pruned when a check starts.
+const CHECK_LOGS_KEEP: usize = 50;
+const DEFAULT_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+const MAX_TIMEOUT_MS: u64 = 60 * 60 * 1000;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long output readers may keep draining after the check exits before the rest of its
+/// process group is killed; a background child holding the pipes would otherwise block forever.
+const DRAIN_GRACE: Duration = Duration::from_secs(2);
+/// Longer output lines are cut in events (the log keeps them whole).
+const EVENT_LINE_MAX_CHARS: usize = 4000;
+
+static RUN_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckSpec {
+    /// Stable id, e.g. "cargo:test" or "app/npm:lint". Pass to checks_run.
+    pub id: String,
+    /// "build", "test", "lint" or "typecheck".
+    pub step: String,
+    /// Root-relative working directory ("" for the root).
+    pub cwd: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Command line for display, e.g. "npm run lint".
+    pub command: String,
+    /// Parser for the output's diagnostics.
+    pub format: DiagnosticFormat,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckRecord {
+    pub run_id: String,
+    pub check_id: String,
+    pub step: String,
+    pub command: String,
+    /// -1 when the process was killed or ended by a signal.
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    pub timed_out: bool,
+    pub cancelled: bool,
+    /// Diagnostics parsed from the output, by severity.
+    pub errors: usize,
+    pub warnings: usize,
+    /// Workspace-relative log with the full stdout and stderr.
+    pub output_path_ref: String,
+    pub finished_at: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckRunStarted {
+    pub run_id: String,
+    pub check: CheckSpec,
+    pub output_path_ref: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckOutputEvent {
+    run_id: String,
+    /// "stdout" or "stderr".
+    stream: &'static str,
+    line: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckFinishedEvent {
+    workspace_root: String,
+    session_id: Option<String>,
+    record: CheckRecord,
+    diagnostics: Vec<Diagnostic>,
+}
+
+struct RunHandle {
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+pub struct CheckRunnerState {
+    runs: HashMap<String, RunHandle>,
+}
+
+/// Step name and output parser for a detected project command, or None when it is not a
+/// check (dev servers, formatters, run targets) or needs a shell.
+fn check_kind(cmd: &ProjectCommand) -> Option<(&'static str, DiagnosticFormat)> {
+    let step = match cmd.kind {
+        CommandKind::Build => "build",
+        CommandKind::Test => "test",
+        CommandKind::Lint => "lint",
+        CommandKind::Typecheck => "typecheck",
+        _ => return None,
+    };
+    if cmd.command.contains(['&', '|', ';', '>', '<']) {
+        return None;
+    }
+    let mut words = cmd.command.split_whitespace();
+    let format = match (words.next()?, words.next()) {
+        ("cargo", _) => DiagnosticFormat::Cargo,
+        ("npx", Some("tsc")) | ("tsc", _) => DiagnosticFormat::Tsc,
+        ("pytest", _) => DiagnosticFormat::Pytest,
+        ("ruff", _) => DiagnosticFormat::Ruff,
+        _ => DiagnosticFormat::Auto,
+    };
+    Some((step, format))
+}
+
+/// The allowlist: the build, test, lint and typecheck commands project detection found in
+/// the manifests, so both report the same set.
+pub(crate) fn detect(root: &Path) -> Vec<CheckSpec> {
+    let mut out = Vec::new();
+    for cmd in project::detect(root).commands {
+        let Some((step, format)) = check_kind(&cmd) else {
+            continue;
+        };
+        let mut words = cmd.command.split_whitespace().map(str::to_string);
+        let Some(program) = words.next() else {
+            continue;
+        };
+        let mut args: Vec<String> = words.collect();
+        if format == DiagnosticFormat::Cargo {
+            // JSON messages for the parser; the stream shows their rendered text.
+            args.push("--message-format=json".into());
+        }
+        let id = if cmd.name == program {
+            program.clone()
+        } else {
+            format!("{}:{}", program, cmd.name)
+        };
+        out.push(CheckSpec {
+            id: if cmd.cwd.is_empty() {
+                id
+            } else {
+                format!("{}/{}", cmd.cwd, id)
+            },
+            step: step.to_string(),
+            cwd: cmd.cwd,
+            program,
+            args,
+            command: cmd.command,
+            format,
+        });
+    }
+    out
+}
+
+/// Node's launchers are batch files on Windows and are not found without the extension.
+fn program_path(program: &str) -> String {
+    if cfg!(windows) && matches!(program, "npm" | "npx" | "pnpm" | "yarn") {
+        format!("{}.cmd", program)
+    } else {
+        program.to_string()
+    }
+}
+
+/// Kill the check and anything it spawned (npm -> node -> tsc ...).
+fn kill_tree(child: &mut Child) {
+    let pid = child.id().to_string();
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", &format!("-{}", pid)])
+        .status();
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid])
+        .status();
+    let _ = child.kill();
+}
+
+/// Wait for the output readers after the check has exited. Readers still blocked after
+/// DRAIN_GRACE mean something it spawned holds the pipes: kill the tree, give them one more
+/// grace period, then leave any that remain detached.
+fn join_readers(readers: Vec<JoinHandle<()>>, child: &mut Child) {
+    let drained = |readers: &[JoinHandle<()>]| {
+        let deadline = Instant::now() + DRAIN_GRACE;
+        while readers.iter().any(|r| !r.is_finished()) {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        true
+    };
+    if !drained(&readers) {
+        kill_tree(child);
+        drained(&readers);
+    }
+    for r in readers.into_iter().filter(|r| r.is_finished()) {
+        let _ = r.join();
+    }
+}
+
+/// Lines to show for one line of output: cargo's JSON messages become their rendered
+/// text and its other JSON records are hidden (the log keeps the raw JSON).
+fn display_lines(format: DiagnosticFormat, line: &str) -> Vec<String> {
+    if format == DiagnosticFormat::Cargo && line.starts_with('{') {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(line) {
+            return v
+                .get("message")
+                .and_then(|m| m.get("rendered"))
+                .and_then(|r| r.as_str())
+                .map(|r| r.trim_end().lines().map(str::to_string).collect())
+                .unwrap_or_default();
+        }
+    }
+    vec![line.to_string()]
+}
+
+fn pump(
+    app: AppHandle,
+    run_id: String,
+    stream: &'static str,
+    source: impl Read,
+    log: Arc<Mutex<File>>,
+    format: DiagnosticFormat,
+) {
+    let mut reader = BufReader::new(source);
+    let mut buf = Vec::new();
+    while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+        if let Ok(mut f) = log.lock() {
+            let _ = f.write_all(&buf);
+        }
+        let text = String::from_utf8_lossy(&buf);
+        for line in display_lines(format, text.trim_end_matches(['\r', '\n'])) {
+            let line = match line.char_indices().nth(EVENT_LINE_MAX_CHARS) {
+                Some((i, _)) => format!("{}…", &line[..i]),
+                None => line,
+            };
+            let _ = app.emit(
+                OUTPUT_EVENT,
+                CheckOutputEvent {
+                    run_id: run_id.clone(),
+                    stream,
+                    line,
+                },
+            );
+        }
+        buf.clear();
+    }
+}
+
+/// Remove all but the newest CHECK_LOGS_KEEP logs, never one of a running check. Run ids
+/// start with a UTC timestamp, so lexical order is start order.
+fn prune_logs(dir: &Path, running: &HashMap<String, RunHandle>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.strip_suffix(".log").map(str::to_string))
+        .filter(|id| !running.contains_key(id))
+        .collect();
+    ids.sort();
+    let excess = ids.len().saturating_sub(CHECK_LOGS_KEEP);
+    for old in &ids[..excess] {
+        let _ = std::fs::remove_file(dir.join(format!("{}.log", old)));
+    }
+}
+
+fn exit_code(status: Option<ExitStatus>) -> i32 {
+    status.and_then(|s| s.code()).unwrap_or(-1)
+}
+
+/// Checks that can run in this workspace: project_detect's build, test, lint and typecheck
+/// commands (package.json, Cargo.toml, pyproject.toml, go.mod, pubspec.yaml, Makefile).
+#[tauri::command]
+pub fn checks_list(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<CheckSpec>> {
+    registry::check_read(&registry, &workspace_root)?;
+    Ok(detect(&canonical_root(&workspace_root)?))
+}
+
+/// Start a detected check by id. Output streams as `checks://output`; completion is
+/// `checks://finished` with the record and session_id, for the caller to record.
+#[tauri::command]
+pub fn checks_run(
+    app: AppHandle,
+    workspace_root: String,
+    check_id: String,
+    session_id: Option<String>,
+    timeout_ms: Option<u64>,
+    state: tauri::State<'_, Mutex<CheckRunnerState>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<CheckRunStarted> {
+    registry::check_read(&registry, &workspace_root)?;
+    let root = canonical_root(&workspace_root)?;
+    let check = detect(&root)
+        .into_iter()
+        .find(|c| c.id == check_id)
+        .ok_or_else(|| {
+            format!(
+                "Unknown check: {} (not detected for this workspace)",
+                check_id
+            )
+        })?;
+    let timeout =
+        Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).min(MAX_TIMEOUT_MS));
+
+    let run_id = format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%dT%H%M%S%3fZ"),
+        RUN_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let log_rel = format!("{}/{}.log", CHECK_LOGS_DIR, run_id);
+    registry::check_write(&registry, &workspace_root, &root.join(&log_rel))?;
+    std::fs::create_dir_all(root.join(CHECK_LOGS_DIR)).map_err(|e| e.to_string())?;
+    prune_logs(&root.join(CHECK_LOGS_DIR), &state.lock().map_err(|e| e.to_string())?.runs);
+    let log = Arc::new(Mutex::new(
+        File::create(root.join(&log_rel))
+            .map_err(|e| format!("Failed to create check log: {}", e))?,
+    ));
+
+    let mut cmd = Command::new(program_path(&check.program));
+    cmd.args(&check.args)
+        .current_dir(root.join(&check.cwd))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("CI", "1")
+        .env("NO_COLOR", "1");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let started = Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", check.command, e))?;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    state.lock().map_err(|e| e.to_string())?.runs.insert(
+        run_id.clone(),
+        RunHandle {
+            cancel: cancel.clone(),
+        },
+    );
+
+    let readers: Vec<_> = [
+        child
+            .stdout
+            .take()
+            .map(|s| ("stdout", Box::new(s) as Box<dyn Read + Send>)),
+        child
+            .stderr
+            .take()
+            .map(|s| ("stderr", Box::new(s) as Box<dyn Read + Send>)),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|(name, src)| {
+        let (app, run_id, log) = (app.clone(), run_id.clone(), log.clone());
+        let format = check.format;
+        std::thread::spawn(move || pump(app, run_id, name, src, log, format))
+    })
+    .collect();
+
+    let (thread_check, thread_run_id) = (check.clone(), run_id.clone());
+    let key = root.to_string_lossy().replace('\\', "/");
+    let log_ref = log_rel.clone();
+    std::thread::Builder::new()
+        .name("check-runner".into())
+        .spawn(move || {
+            let (mut timed_out, mut cancelled) = (false, false);
+            let status = loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => break Some(status),
+                    Ok(None) => {}
+                    Err(_) => break None,
+                }
+                if cancel.load(Ordering::Relaxed) || started.elapsed() >= timeout {
+                    cancelled = cancel.load(Ordering::Relaxed);
+                    timed_out = !cancelled;
+                    kill_tree(&mut child);
+                    break child.wait().ok();
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            };
+            join_readers(readers, &mut child);
+            let output = std::fs::read(root.join(&log_ref))
+                .map(|b| String::from_utf8_lossy(&b).into_owned())
+                .unwrap_or_default();
+            let diagnostics = diagnostics::parse(
+                &root,
+                &root.join(&thread_check.cwd),
+                thread_check.format,
+                &output,
+            );
+            let count = |sev: Severity| diagnostics.iter().filter(|d| d.severity == sev).count();
+            let record = CheckRecord {
+                run_id: thread_run_id.clone(),
+                check_id: thread_check.id,
+                step: thread_check.step,
+                command: thread_check.command,
+                exit_code: if timed_out || cancelled {
+                    -1
+                } else {
+                    exit_code(status)
+                },
+                duration_ms: started.elapsed().as_millis() as u64,
+                timed_out,
+                cancelled,
+                errors: count(Severity::Error),
+                warnings: count(Severity::Warning),
+                output_path_ref: log_ref,
+                finished_at: Utc::now().to_rfc3339(),
+            };
+            if let Some(s) = app.try_state::<Mutex<CheckRunnerState>>() {
+                if let Ok(mut s) = s.lock() {
+                    s.runs.remove(&thread_run_id);
+                }
+            }
+            let _ = app.emit(
+                FINISHED_EVENT,
+                CheckFinishedEvent {
+                    workspace_root: key,
+                    session_id,
+                    record,
+                    diagnostics,
+                },
+            );
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(CheckRunStarted {
+        run_id,
+        check,
+        output_path_ref: log_rel,
+    })
+}
+
+/// Cancel a running check (kills its process tree). Returns false if it is not running.
+#[tauri::command]
+pub fn checks_cancel(
+    run_id: String,
+    state: tauri::State<'_, Mutex<CheckRunnerState>>,
+) -> AppResult<bool> {
+    let s = state.lock().map_err(|e| e.to_string())?;
+    Ok(match s.runs.get(&run_id) {
+        Some(h) => {
+            h.cancel.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    })
+}
@@ -0,0 +1,489 @@
+//! Context packing: fit selected files, knowledge chunks and candidate files into the
+//! model's token budget. Files are kept whole when they fit, otherwise cut down to the
+//! symbols the prompt mentions, otherwise to head and tail. Every input gets a manifest
+//! entry saying what happened to it and why.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppResult;
+use crate::registry::{self, WorkspaceRegistry};
+use crate::runtime::{self, RuntimeState};
+use crate::symbols;
+use crate::textfile::{self, CutMode, ReadRange};
+use crate::tokens::{estimate_tokens, Counter};
+use crate::workspace::{allow_external_symlinks, resolve};
+
+/// Used when no server is running and the caller gave no budget.
+const DEFAULT_CTX_SIZE: usize = 4096;
+/// Default room left for the model's answer.
+const DEFAULT_RESERVE_TOKENS: usize = 1024;
+/// System prompt, role instructions and section headers added around the packed content.
+const OVERHEAD_TOKENS: usize = 512;
+/// One candidate file may take at most this share of the budget.
+const CANDIDATE_SHARE: f64 = 0.25;
+/// Trimmed slices smaller than this are not worth including.
+const MIN_SLICE_TOKENS: usize = 64;
+/// Bytes read per file; larger files are read from the start and can only be trimmed.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+/// Head gets this share of the kept lines when cutting to head and tail.
+const HEAD_SHARE: f64 = 2.0 / 3.0;
+const MIN_TERM_LEN: usize = 3;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeChunk {
+    pub title: String,
+    pub source_path: String,
+    pub chunk_text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackContextRequest {
+    pub prompt: String,
+    #[serde(default)]
+    pub selected_paths: Vec<String>,
+    /// Suggested or retrieved files; ranked by prompt relevance, included while room remains.
+    #[serde(default)]
+    pub candidate_paths: Vec<String>,
+    #[serde(default)]
+    pub knowledge_chunks: Vec<KnowledgeChunk>,
+    /// Explicit budget for packed content. Default: runtime ctx size minus reserve and overhead.
+    pub token_budget: Option<usize>,
+    /// Tokens kept free for the answer (default 1024).
+    pub reserve_tokens: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    /// Only listed when the prompt alone does not fit the context window.
+    Prompt,
+    Selected,
+    Candidate,
+    Knowledge,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PackMode {
+    Whole,
+    /// Only the symbols the prompt mentions.
+    Symbols,
+    HeadTail,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Decision {
+    Included,
+    Trimmed,
+    Dropped,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// File path, "title (sourcePath)" for knowledge chunks, or "prompt".
+    pub id: String,
+    pub role: Role,
+    pub decision: Decision,
+    pub mode: Option<PackMode>,
+    /// Tokens used in the packed context.
+    pub tokens: usize,
+    /// Tokens of the full input (0 when it could not be read).
+    pub original_tokens: usize,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackedFile {
+    pub path: String,
+    pub content: String,
+    pub mode: PackMode,
+    pub tokens: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackContextResult {
+    pub selected_files: Vec<PackedFile>,
+    /// Included candidate files, most relevant first.
+    pub suggested_files: Vec<PackedFile>,
+    pub knowledge_chunks: Vec<KnowledgeChunk>,
+    /// One entry per input, in packing order.
+    pub manifest: Vec<ManifestEntry>,
+    pub budget_tokens: usize,
+    pub used_tokens: usize,
+    pub prompt_tokens: usize,
+    /// All counts came from the loaded model's tokenizer.
+    pub exact: bool,
+}
+
+/// Lowercased identifiers in the prompt, for symbol and relevance matching.
+fn prompt_terms(prompt: &str) -> HashSet<String> {
+    prompt
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| w.len() >= MIN_TERM_LEN)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn relevance(path: &str, text: &str, terms: &HashSet<String>) -> usize {
+    let path = path.to_lowercase();
+    let words: HashSet<String> = prompt_terms(text);
+    terms
+        .iter()
+        .map(|t| 2 * usize::from(path.contains(t.as_str())) + usize::from(words.contains(t)))
+        .sum()
+}
+
+/// Text of a workspace file (at most MAX_READ_BYTES) and whether it was cut.
+fn read_text(
+    workspace_root: &str,
+    path: &str,
+    allow_external_symlinks: bool,
+) -> Result<(String, bool), String> {
+    let full = resolve(workspace_root, path, allow_external_symlinks).map_err(|e| e.message)?;
+    let w = textfile::read_window(&full, ReadRange::Whole, Some(MAX_READ_BYTES), CutMode::Head)
+        .map_err(|e| e.to_string())?;
+    let text = w.decoded.text.ok_or("binary file")?;
+    Ok((text, w.truncated))
+}
+
+fn omitted(from: usize, to: usize) -> String {
+    format!("... [lines {}-{} omitted]\n", from, to)
+}
+
+/// Lines of the symbols named in the prompt (merged ranges), with omission markers.
+fn symbol_slice(path: &str, text: &str, terms: &HashSet<String>) -> Option<String> {
+    let mut ranges: Vec<(usize, usize)> = symbols::extract(path, text.as_bytes())
+        .into_iter()
+        .filter(|s| terms.contains(&s.name.to_lowercase()))
+        .map(|s| (s.start_line as usize, s.end_line as usize))
+        .collect();
+    if ranges.is_empty() {
+        return None;
+    }
+    ranges.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (a, b) in ranges {
+        match merged.last_mut() {
+            Some(last) if a <= last.1 + 1 => last.1 = last.1.max(b),
+            _ => merged.push((a, b)),
+        }
+    }
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut out = String::new();
+    let mut next = 1;
+    for (a, b) in merged {
+        if a > next {
+            out.push_str(&omitted(next, a - 1));
+        }
+        let start = (a.max(1) - 1).min(lines.len());
+        for line in &lines[start..b.min(lines.len()).max(start)] {
+            out.push_str(line);
+        }
+        next = b + 1;
+    }
+    if next <= lines.len() {
+        out.push_str(&omitted(next, lines.len()));
+    }
+    Some(out)
+}
+
+/// First and last lines of text whose estimated cost fits target. `scale` converts the
+/// heuristic estimate to the model's tokenizer (exact / estimated for the whole file).
+fn head_tail(text: &str, target: usize, scale: f64) -> Option<String> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let cost: Vec<f64> = lines
+        .iter()
+        .map(|l| estimate_tokens(l) as f64 * scale)
+        .collect();
+    let marker = estimate_tokens(&omitted(lines.len(), lines.len())) as f64 * scale;
+    let split = |k: usize| {
+        let head = ((k as f64) * HEAD_SHARE).ceil() as usize;
+        (head.min(k), k - head.min(k))
+    };
+    let fits = |k: usize| {
+        let (h, t) = split(k);
+        let c: f64 = cost[..h].iter().sum::<f64>() + cost[lines.len() - t..].iter().sum::<f64>();
+        c + marker <= target as f64
+    };
+    // Largest k (total kept lines) that fits; cost grows with k.
+    let (mut lo, mut hi) = (0, lines.len());
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if fits(mid) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    if lo == 0 {
+        return None;
+    }
+    let (h, t) = split(lo);
+    let mut out: String = lines[..h].concat();
+    if h + t < lines.len() {
+        out.push_str(&omitted(h + 1, lines.len() - t));
+    }
+    out.push_str(&lines[lines.len() - t..].concat());
+    Some(out)
+}
+
+struct Packer<'a> {
+    counter: Counter,
+    terms: HashSet<String>,
+    workspace_root: &'a str,
+    allow_external_symlinks: bool,
+    remaining: usize,
+    budget: usize,
+    manifest: Vec<ManifestEntry>,
+    exact: bool,
+}
+
+impl Packer<'_> {
+    async fn count(&mut self, text: &str) -> usize {
+        let (n, exact) = self.counter.count(text).await;
+        self.exact &= exact;
+        n
+    }
+
+    fn skip(&mut self, id: &str, role: Role, original: usize, reason: &str) {
+        self.manifest.push(ManifestEntry {
+            id: id.to_string(),
+            role,
+            decision: Decision::Dropped,
+            mode: None,
+            tokens: 0,
+            original_tokens: original,
+            reason: Some(reason.to_string()),
+        });
+    }
+
+    /// Whole file if it fits within `allowance`, else a symbol slice, else head and tail.
+    async fn pack_file(
+        &mut self,
+        path: &str,
+        text: &str,
+        cut: bool,
+        role: Role,
+        allowance: usize,
+    ) -> Option<PackedFile> {
+        let original = self.count(text).await;
+        let mut candidates: Vec<(PackMode, String, Option<&str>)> = Vec::new();
+        if original <= allowance && !cut {
+            candidates.push((PackMode::Whole, text.to_string(), None));
+        } else if allowance >= MIN_SLICE_TOKENS {
+            let why = if cut {
+                "file larger than read limit"
+            } else {
+                "over budget"
+            };
+            if let Some(s) = symbol_slice(path, text, &self.terms) {
+                candidates.push((PackMode::Symbols, s, Some(why)));
+            }
+            let scale = original as f64 / estimate_tokens(text).max(1) as f64;
+            // Shrink the target if the exact count of the cut overshoots.
+            for factor in [1.0, 0.85, 0.7] {
+                let target = (allowance as f64 * factor) as usize;
+                if let Some(s) = head_tail(text, target, scale) {
+                    candidates.push((PackMode::HeadTail, s, Some(why)));
+                }
+            }
+        }
+        for (mode, content, why) in candidates {
+            let tokens = if mode == PackMode::Whole {
+                original
+            } else {
+                self.count(&content).await
+            };
+            if tokens > allowance {
+                continue;
+            }
+            self.remaining -= tokens;
+            self.manifest.push(ManifestEntry {
+                id: path.to_string(),
+                role,
+                decision: if mode == PackMode::Whole {
+                    Decision::Included
+                } else {
+                    Decision::Trimmed
+                },
+                mode: Some(mode),
+                tokens,
+                original_tokens: original,
+                reason: why.map(str::to_string),
+            });
+            return Some(PackedFile {
+                path: path.to_string(),
+                content,
+                mode,
+                tokens,
+            });
+        }
+        self.skip(path, role, original, "over budget");
+        None
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.trim()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+/// Choose, order and trim context for a prompt within the token budget (derived from the
+/// runtime's ctx size unless given). Priority: selected files, knowledge chunks, then
+/// candidate files by relevance. The manifest records every inclusion, trim and drop.
+#[tauri::command]
+pub async fn context_pack(
+    workspace_root: String,
+    request: PackContextRequest,
+    state: tauri::State<'_, Mutex<RuntimeState>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<PackContextResult> {
+    registry::check_read(&registry, &workspace_root)?;
+    let mut counter = Counter::new(&state).await?;
+    let (prompt_tokens, prompt_exact) = counter.count(&request.prompt).await;
+    // Room for prompt and packed content together; only known when derived from ctx size.
+    let mut room = None;
+    let budget = match request.token_budget {
+        Some(b) => b,
+        None => {
+            let ctx = runtime::context_size(&state)
+                .await?
+                .map(|c| c as usize)
+                .unwrap_or(DEFAULT_CTX_SIZE);
+            let reserve = request.reserve_tokens.unwrap_or(DEFAULT_RESERVE_TOKENS);
+            let r = ctx.saturating_sub(reserve + OVERHEAD_TOKENS);
+            room = Some(r);
+            r.saturating_sub(prompt_tokens)
+        }
+    };
+
+    let mut p = Packer {
+        counter,
+        terms: prompt_terms(&request.prompt),
+        workspace_root: &workspace_root,
+        allow_external_symlinks: allow_external_symlinks(),
+        remaining: budget,
+        budget,
+        manifest: Vec::new(),
+        exact: prompt_exact,
+    };
+    if let Some(room) = room.filter(|&r| prompt_tokens > r) {
+        p.manifest.push(ManifestEntry {
+            id: "prompt".into(),
+            role: Role::Prompt,
+            decision: Decision::Included,
+            mode: None,
+            tokens: prompt_tokens,
+            original_tokens: prompt_tokens,
+            reason: Some(format!(
+                "prompt alone needs {} tokens but only {} fit after the reserve; no context packed",
+                prompt_tokens, room
+            )),
+        });
+    }
+    let mut seen: HashSet<String> = HashSet::new();
+
+    let mut selected_files = Vec::new();
+    for path in request.selected_paths.iter().map(|s| normalize(s)) {
+        if !seen.insert(path.clone()) {
+            p.skip(&path, Role::Selected, 0, "duplicate");
+            continue;
+        }
+        match read_text(p.workspace_root, &path, p.allow_external_symlinks) {
+            Ok((text, cut)) => {
+                let allowance = p.remaining;
+                if let Some(f) = p
+                    .pack_file(&path, &text, cut, Role::Selected, allowance)
+                    .await
+                {
+                    selected_files.push(f);
+                }
+            }
+            Err(e) => p.skip(&path, Role::Selected, 0, &e),
+        }
+    }
+
+    let mut knowledge_chunks = Vec::new();
+    for chunk in request.knowledge_chunks {
+        let id = format!("{} ({})", chunk.title, chunk.source_path);
+        if !seen.insert(format!("knowledge:{}", id)) {
+            p.skip(&id, Role::Knowledge, 0, "duplicate");
+            continue;
+        }
+        let tokens = p.count(&chunk.chunk_text).await;
+        if tokens > p.remaining {
+            p.skip(&id, Role::Knowledge, tokens, "over budget");
+            continue;
+        }
+        p.remaining -= tokens;
+        p.manifest.push(ManifestEntry {
+            id,
+            role: Role::Knowledge,
+            decision: Decision::Included,
+            mode: Some(PackMode::Whole),
+            tokens,
+            original_tokens: tokens,
+            reason: None,
+        });
+        knowledge_chunks.push(chunk);
+    }
+
+    // Read all candidates first so they can be ranked by relevance to the prompt.
+    let mut candidates = Vec::new();
+    for path in request.candidate_paths.iter().map(|s| normalize(s)) {
+        if !seen.insert(path.clone()) {
+            let reason = if request.selected_paths.iter().any(|s| normalize(s) == path) {
+                "already selected"
+            } else {
+                "duplicate"
+            };
+            p.skip(&path, Role::Candidate, 0, reason);
+            continue;
+        }
+        match read_text(p.workspace_root, &path, p.allow_external_symlinks) {
+            Ok((text, cut)) => {
+                let score = relevance(&path, &text, &p.terms);
+                candidates.push((score, path, text, cut));
+            }
+            Err(e) => p.skip(&path, Role::Candidate, 0, &e),
+        }
+    }
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
+    let share = (p.budget as f64 * CANDIDATE_SHARE) as usize;
+    let mut suggested_files = Vec::new();
+    for (_, path, text, cut) in candidates {
+        if p.remaining < MIN_SLICE_TOKENS {
+            p.skip(&path, Role::Candidate, 0, "budget exhausted");
+            continue;
+        }
+        let allowance = p.remaining.min(share);
+        if let Some(f) = p
+            .pack_file(&path, &text, cut, Role::Candidate, allowance)
+            .await
+        {
+            suggested_files.push(f);
+        }
+    }
+
+    Ok(PackContextResult {
+        selected_files,
+        suggested_files,
+        knowledge_chunks,
+        used_tokens: budget - p.remaining,
+        budget_tokens: budget,
+        prompt_tokens,
+        exact: p.exact,
+        manifest: p.manifest,
+    })
+}
@@ -0,0 +1,773 @@
+//! Structured diagnostics from build/lint/test output: cargo (JSON messages, rustc text,
+//! test panics), tsc, eslint (JSON and stylish), pytest and ruff (JSON and text). Paths are
+//! made workspace-relative so failing locations can be fed back into context.
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult, IoResultExt};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::workspace::{allow_external_symlinks, resolve};
+
+/// Lines of pytest `E` output kept per failure.
+const MAX_PYTEST_MESSAGE_LINES: usize = 20;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticFormat {
+    Cargo,
+    Tsc,
+    Eslint,
+    Pytest,
+    Ruff,
+    /// Try every parser; for output whose tool is unknown (e.g. npm scripts).
+    Auto,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// Workspace-relative; None when the location is outside the workspace or unknown.
+    pub file: Option<String>,
+    /// 1-based.
+    pub line: Option<u32>,
+    /// 1-based.
+    pub column: Option<u32>,
+    pub severity: Severity,
+    /// Tool-specific code: E0308, TS2322, no-unused-vars, F401, AssertionError.
+    pub code: Option<String>,
+    pub message: String,
+    /// Parser that produced it ("cargo", "tsc", "eslint", "pytest", "ruff").
+    pub source: &'static str,
+}
+
+/// Where relative paths in the output are resolved from.
+struct Ctx<'a> {
+    root: &'a Path,
+    cwd: &'a Path,
+}
+
+impl Ctx<'_> {
+    /// Workspace-relative form of a path printed by a tool (absolute or relative to cwd).
+    fn rel(&self, file: &str) -> Option<String> {
+        let p = Path::new(file.trim());
+        let abs = if p.is_absolute() {
+            p.to_path_buf()
+        } else {
+            self.cwd.join(p)
+        };
+        let abs = abs
+            .canonicalize()
+            .unwrap_or_else(|_| lexical_normalize(&abs));
+        let rel = abs.strip_prefix(self.root).ok()?;
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        (!rel.is_empty()).then_some(rel)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn diag(
+        &self,
+        source: &'static str,
+        file: Option<&str>,
+        line: Option<u32>,
+        column: Option<u32>,
+        severity: Severity,
+        code: Option<String>,
+        message: String,
+    ) -> Diagnostic {
+        Diagnostic {
+            file: file.and_then(|f| self.rel(f)),
+            line,
+            column,
+            severity,
+            code: code.filter(|c| !c.is_empty()),
+            message: message.trim().to_string(),
+            source,
+        }
+    }
+}
+
+fn lexical_normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn num(s: Option<regex::Match<'_>>) -> Option<u32> {
+    s.and_then(|m| m.as_str().parse().ok())
+}
+
+fn json_u32(v: &serde_json::Value, key: &str) -> Option<u32> {
+    v.get(key).and_then(|n| n.as_u64()).map(|n| n as u32)
+}
+
+fn json_str<'a>(v: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    v.get(key).and_then(|s| s.as_str())
+}
+
+fn severity_of(level: &str) -> Severity {
+    match level {
+        "error" | "fatal" => Severity::Error,
+        "warning" | "warn" => Severity::Warning,
+        _ => Severity::Info,
+    }
+}
+
+// ---- cargo / rustc ----
+
+static RUSTC_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(error|warning)(?:\[(\w+)\])?: (.+)$").unwrap());
+static RUSTC_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--> (.+?):(\d+):(\d+)$").unwrap());
+static RUST_PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^thread '([^']*)' panicked at (.+?):(\d+):(\d+):?(.*)$").unwrap()
+});
+
+/// Summary lines rustc emits as messages of their own.
+fn is_rustc_summary(message: &str) -> bool {
+    message.starts_with("aborting due to")
+        || message.starts_with("For more information")
+        || message.contains("warnings emitted")
+        || message.contains("warning emitted")
+        || message.starts_with("could not compile")
+}
+
+fn cargo_json_message(ctx: &Ctx, msg: &serde_json::Value) -> Option<Diagnostic> {
+    let text = json_str(msg, "message")?;
+    let level = json_str(msg, "level").unwrap_or("error");
+    if is_rustc_summary(text) || level == "failure-note" {
+        return None;
+    }
+    let code = msg
+        .get("code")
+        .and_then(|c| json_str(c, "code"))
+        .map(str::to_string);
+    let spans = msg.get("spans").and_then(|s| s.as_array());
+    let span = spans.and_then(|s| {
+        s.iter()
+            .find(|sp| sp.get("is_primary").and_then(|p| p.as_bool()) == Some(true))
+            .or_else(|| s.first())
+    });
+    Some(ctx.diag(
+        "cargo",
+        span.and_then(|s| json_str(s, "file_name")),
+        span.and_then(|s| json_u32(s, "line_start")),
+        span.and_then(|s| json_u32(s, "column_start")),
+        severity_of(level),
+        code,
+        text.to_string(),
+    ))
+}
+
+fn parse_cargo(ctx: &Ctx, output: &str, out: &mut Vec<Diagnostic>) {
+    let lines: Vec<&str> = output.lines().collect();
+    // rustc text diagnostic waiting for its "-->" location.
+    let mut pending: Option<(Severity, Option<String>, String)> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.trim_end();
+        if line.starts_with('{') {
+            if let Ok(v) = serde_json::from_str::<serde_json::Value>(line) {
+                if json_str(&v, "reason") == Some("compiler-message") {
+                    if let Some(d) = v.get("message").and_then(|m| cargo_json_message(ctx, m)) {
+                        out.push(d);
+                    }
+                }
+                continue;
+            }
+        }
+        if let Some(c) = RUSTC_HEADER.captures(line) {
+            if let Some((sev, code, msg)) = pending.take() {
+                out.push(ctx.diag("cargo", None, None, None, sev, code, msg));
+            }
+            let msg = c[3].to_string();
+            if !is_rustc_summary(&msg) {
+                pending = Some((
+                    severity_of(&c[1]),
+                    c.get(2).map(|m| m.as_str().to_string()),
+                    msg,
+                ));
+            }
+            continue;
+        }
+        if let Some(c) = RUSTC_LOCATION.captures(line) {
+            if let Some((sev, code, msg)) = pending.take() {
+                out.push(ctx.diag(
+                    "cargo",
+                    Some(&c[1]),
+                    num(c.get(2)),
+                    num(c.get(3)),
+                    sev,
+                    code,
+                    msg,
+                ));
+            }
+            continue;
+        }
+        if let Some(c) = RUST_PANIC.captures(line) {
+            // Since Rust 1.73 the panic message is on the following line.
+            let inline = c[5].trim().trim_start_matches(',').trim();
+            let message = if inline.is_empty() {
+                lines.get(i + 1).map(|l| l.trim()).unwrap_or("panicked")
+            } else {
+                inline
+            };
+            out.push(ctx.diag(
+                "cargo",
+                Some(&c[2]),
+                num(c.get(3)),
+                num(c.get(4)),
+                Severity::Error,
+                None,
+                format!("test {} panicked: {}", &c[1], message),
+            ));
+        }
+    }
+    if let Some((sev, code, msg)) = pending {
+        out.push(ctx.diag("cargo", None, None, None, sev, code, msg));
+    }
+}
+
+// ---- tsc ----
+
+static TSC_PLAIN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\S[^(]*)\((\d+),(\d+)\): (error|warning) (TS\d+): (.*)$").unwrap()
+});
+static TSC_PRETTY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\S.*?):(\d+):(\d+) - (error|warning) (TS\d+): (.*)$").unwrap());
+
+fn parse_tsc(ctx: &Ctx, output: &str, out: &mut Vec<Diagnostic>) {
+    let mut lines = output.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(c) = TSC_PLAIN.captures(line.trim_end()) {
+            // Message chains continue on indented lines.
+            let mut message = c[6].to_string();
+            while let Some(next) = lines.peek().filter(|l| l.starts_with("  ")) {
+                message.push('\n');
+                message.push_str(next.trim());
+                lines.next();
+            }
+            out.push(ctx.diag(
+                "tsc",
+                Some(&c[1]),
+                num(c.get(2)),
+                num(c.get(3)),
+                severity_of(&c[4]),
+                Some(c[5].to_string()),
+                message,
+            ));
+        } else if let Some(c) = TSC_PRETTY.captures(line.trim_end()) {
+            out.push(ctx.diag(
+                "tsc",
+                Some(&c[1]),
+                num(c.get(2)),
+                num(c.get(3)),
+                severity_of(&c[4]),
+                Some(c[5].to_string()),
+                c[6].to_string(),
+            ));
+        }
+    }
+}
+
+// ---- eslint / ruff JSON ----
+
+/// The JSON array in the output, if any (npm prints a banner before it).
+fn json_array(output: &str) -> Option<Vec<serde_json::Value>> {
+    let start = output.find("\n[").map(|i| i + 1).or_else(|| {
+        output
+            .trim_start()
+            .starts_with('[')
+            .then(|| output.len() - output.trim_start().len())
+    })?;
+    let end = output.rfind(']')?;
+    serde_json::from_str(output.get(start..=end)?).ok()
+}
+
+fn eslint_json(ctx: &Ctx, items: &[serde_json::Value], out: &mut Vec<Diagnostic>) {
+    for item in items {
+        let (Some(file), Some(messages)) = (
+            json_str(item, "filePath"),
+            item.get("messages").and_then(|m| m.as_array()),
+        ) else {
+            continue;
+        };
+        for m in messages {
+            let severity = match m.get("severity").and_then(|s| s.as_u64()) {
+                Some(2) => Severity::Error,
+                Some(1) => Severity::Warning,
+                _ => Severity::Info,
+            };
+            out.push(ctx.diag(
+                "eslint",
+                Some(file),
+                json_u32(m, "line"),
+                json_u32(m, "column"),
+                severity,
+                json_str(m, "ruleId").map(str::to_string),
+                json_str(m, "message").unwrap_or_default().to_string(),
+            ));
+        }
+    }
+}
+
+fn ruff_json(ctx: &Ctx, items: &[serde_json::Value], out: &mut Vec<Diagnostic>) {
+    for item in items {
+        let (Some(file), Some(message)) = (json_str(item, "filename"), json_str(item, "message"))
+        else {
+            continue;
+        };
+        let location = item.get("location");
+        out.push(ctx.diag(
+            "ruff",
+            Some(file),
+            location.and_then(|l| json_u32(l, "row")),
+            location.and_then(|l| json_u32(l, "column")),
+            Severity::Error,
+            json_str(item, "code").map(str::to_string),
+            message.to_string(),
+        ));
+    }
+}
+
+// ---- eslint stylish ----
+
+static ESLINT_STYLISH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s+(\d+):(\d+)\s+(error|warning)\s+(.+?)(?:\s{2,}(\S+))?$").unwrap()
+});
+
+fn parse_eslint(ctx: &Ctx, output: &str, out: &mut Vec<Diagnostic>) {
+    if let Some(items) = json_array(output) {
+        eslint_json(ctx, &items, out);
+        return;
+    }
+    let mut file: Option<&str> = None;
+    for line in output.lines() {
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            file = None;
+        } else if !line.starts_with(char::is_whitespace) {
+            // Stylish prints each file's absolute path on its own line.
+            file = Path::new(trimmed).is_absolute().then_some(trimmed);
+        } else if let (Some(f), Some(c)) = (file, ESLINT_STYLISH.captures(trimmed)) {
+            out.push(ctx.diag(
+                "eslint",
+                Some(f),
+                num(c.get(1)),
+                num(c.get(2)),
+                severity_of(&c[3]),
+                c.get(5).map(|m| m.as_str().to_string()),
+                c[4].to_string(),
+            ));
+        }
+    }
+}
+
+// ---- ruff text ----
+
+static RUFF_TEXT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:[A-Za-z]:)?[^\s:][^:]*):(\d+):(\d+): ([A-Z]+[0-9]+) (?:\[\*\] )?(.*)$")
+        .unwrap()
+});
+
+fn parse_ruff(ctx: &Ctx, output: &str, out: &mut Vec<Diagnostic>) {
+    if let Some(items) = json_array(output) {
+        ruff_json(ctx, &items, out);
+        return;
+    }
+    for line in output.lines() {
+        if let Some(c) = RUFF_TEXT.captures(line.trim_end()) {
+            out.push(ctx.diag(
+                "ruff",
+                Some(&c[1]),
+                num(c.get(2)),
+                num(c.get(3)),
+                Severity::Error,
+                Some(c[4].to_string()),
+                c[5].to_string(),
+            ));
+        }
+    }
+}
+
+// ---- pytest ----
+
+static PYTEST_SECTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^_{3,} (.+?) _{3,}$").unwrap());
+static PYTEST_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\S[^:]*\.py):(\d+): (\w+(?:\.\w+)*)$").unwrap());
+static PYTEST_SUMMARY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(FAILED|ERROR) ([^\s:]+\.py)(?:::(\S+))?(?: - (.*))?$").unwrap()
+});
+
+/// Last segment of a test id: "tests/a.py::TestX::test_y[1]" and "TestX.test_y[1]" -> "test_y[1]".
+fn test_name(id: &str) -> &str {
+    let id = id.rsplit("::").next().unwrap_or(id);
+    match id.find('[') {
+        Some(b) => {
+            let head = &id[..b];
+            head.rfind('.').map(|d| &id[d + 1..]).unwrap_or(id)
+        }
+        None => id.rsplit('.').next().unwrap_or(id),
+    }
+}
+
+fn parse_pytest(ctx: &Ctx, output: &str, out: &mut Vec<Diagnostic>) {
+    let mut e_lines: Vec<&str> = Vec::new();
+    let mut test: Option<&str> = None;
+    // Tests located by a traceback; their short-summary lines add nothing.
+    let mut located: HashSet<&str> = HashSet::new();
+    let mut summary: Vec<(&str, Diagnostic)> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim_end();
+        if let Some(c) = PYTEST_SECTION.captures(line) {
+            test = c.get(1).map(|m| test_name(m.as_str()));
+            e_lines.clear();
+        } else if let Some(rest) = line.strip_prefix("E ") {
+            if e_lines.len() < MAX_PYTEST_MESSAGE_LINES {
+                e_lines.push(rest.trim());
+            }
+        } else if let Some(c) = PYTEST_LOCATION.captures(line) {
+            let message = if e_lines.is_empty() {
+                c[3].to_string()
+            } else {
+                e_lines.join("\n")
+            };
+            out.push(ctx.diag(
+                "pytest",
+                Some(&c[1]),
+                num(c.get(2)),
+                None,
+                Severity::Error,
+                Some(c[3].to_string()),
+                message,
+            ));
+            located.extend(test);
+            e_lines.clear();
+        } else if let Some(c) = PYTEST_SUMMARY.captures(line) {
+            let id = c
+                .get(3)
+                .map(|m| m.as_str())
+                .unwrap_or(c.get(2).unwrap().as_str());
+            let detail = c
+                .get(4)
+                .map(|m| m.as_str())
+                .unwrap_or(c.get(1).unwrap().as_str());
+            let d = ctx.diag(
+                "pytest",
+                c.get(2).map(|m| m.as_str()),
+                None,
+                None,
+                Severity::Error,
+                None,
+                format!("{}: {}", id, detail),
+            );
+            summary.push((test_name(id), d));
+        }
+    }
+    out.extend(
+        summary
+            .into_iter()
+            .filter(|(name, _)| !located.contains(name))
+            .map(|(_, d)| d),
+    );
+}
+
+/// Parse tool output into diagnostics. `cwd` is the absolute directory the tool ran in
+/// (relative paths in the output are resolved against it).
+pub(crate) fn parse(
+    root: &Path,
+    cwd: &Path,
+    format: DiagnosticFormat,
+    output: &str,
+) -> Vec<Diagnostic> {
+    let ctx = Ctx { root, cwd };
+    let mut out = Vec::new();
+    match format {
+        DiagnosticFormat::Cargo => parse_cargo(&ctx, output, &mut out),
+        DiagnosticFormat::Tsc => parse_tsc(&ctx, output, &mut out),
+        DiagnosticFormat::Eslint => parse_eslint(&ctx, output, &mut out),
+        DiagnosticFormat::Pytest => parse_pytest(&ctx, output, &mut out),
+        DiagnosticFormat::Ruff => parse_ruff(&ctx, output, &mut out),
+        DiagnosticFormat::Auto => {
+            parse_cargo(&ctx, output, &mut out);
+            parse_tsc(&ctx, output, &mut out);
+            parse_eslint(&ctx, output, &mut out);
+            parse_pytest(&ctx, output, &mut out);
+            parse_ruff(&ctx, output, &mut out);
+        }
+    }
+    let mut seen = HashSet::new();
+    out.retain(|d| {
+        seen.insert((
+            d.file.clone(),
+            d.line,
+            d.column,
+            d.code.clone(),
+            d.message.clone(),
+        ))
+    });
+    out
+}
+
+/// Parse build/lint/test output (given inline, or a workspace-relative log such as a check's
+/// outputPathRef) into diagnostics with workspace-relative paths. `cwd` is the root-relative
+/// directory the tool ran in; `format` defaults to auto.
+#[tauri::command]
+pub fn diagnostics_parse(
+    workspace_root: String,
+    output: Option<String>,
+    log_path: Option<String>,
+    format: Option<DiagnosticFormat>,
+    cwd: Option<String>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<Diagnostic>> {
+    registry::check_read(&registry, &workspace_root)?;
+    let external = allow_external_symlinks();
+    let root = resolve(&workspace_root, "", external)?;
+    let cwd = resolve(&workspace_root, cwd.as_deref().unwrap_or(""), external)?;
+    let output = match (output, log_path) {
+        (Some(o), _) => o,
+        (None, Some(p)) => {
+            let path = resolve(&workspace_root, &p, external)?;
+            let bytes = std::fs::read(&path).at(&path)?;
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        (None, None) => return Err(AppError::invalid("output or log_path is required")),
+    };
+    Ok(parse(
+        &root,
+        &cwd,
+        format.unwrap_or(DiagnosticFormat::Auto),
+        &output,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Never exists, so paths are normalized lexically and no fixture files are needed.
+    const ROOT: &str = "/diagnostics-test-ws";
+
+    fn run(format: DiagnosticFormat, cwd: &str, output: &str) -> Vec<Diagnostic> {
+        let root = Path::new(ROOT);
+        parse(root, &root.join(cwd), format, output)
+    }
+
+    /// (file, line, column, code) of a diagnostic.
+    type Loc<'a> = (Option<&'a str>, Option<u32>, Option<u32>, Option<&'a str>);
+
+    /// Locations of each diagnostic, for compact assertions.
+    fn locs(v: &[Diagnostic]) -> Vec<Loc<'_>> {
+        v.iter()
+            .map(|d| (d.file.as_deref(), d.line, d.column, d.code.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn cargo_json_messages_skip_summaries_and_other_records() {
+        let output = r#"{"reason":"compiler-artifact","target":{"name":"app"}}
+{"reason":"compiler-message","message":{"message":"mismatched types","level":"error","code":{"code":"E0308"},"spans":[{"file_name":"src/other.rs","line_start":1,"column_start":1,"is_primary":false},{"file_name":"src/main.rs","line_start":4,"column_start":5,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"unused import: `std::fs`","level":"warning","code":{"code":"unused_imports"},"spans":[{"file_name":"src/lib.rs","line_start":2,"column_start":5,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","level":"error","code":null,"spans":[]}}
+{"reason":"build-finished","success":false}"#;
+        let v = run(DiagnosticFormat::Cargo, "app", output);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("app/src/main.rs"), Some(4), Some(5), Some("E0308")),
+                (Some("app/src/lib.rs"), Some(2), Some(5), Some("unused_imports")),
+            ]
+        );
+        assert!(v[0].severity == Severity::Error && v[1].severity == Severity::Warning);
+        assert_eq!(v[0].message, "mismatched types");
+    }
+
+    #[test]
+    fn cargo_text_diagnostics_take_the_following_location() {
+        let output = "warning: unused variable: `x`\n  --> src/lib.rs:3:9\n   |\n3  |     let x = 1;\n\
+                      error[E0425]: cannot find value `y` in this scope\n --> src/lib.rs:5:1\n\
+                      error: aborting due to 1 previous error; 1 warning emitted\n";
+        let v = run(DiagnosticFormat::Cargo, "", output);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("src/lib.rs"), Some(3), Some(9), None),
+                (Some("src/lib.rs"), Some(5), Some(1), Some("E0425")),
+            ]
+        );
+        assert_eq!(v[1].message, "cannot find value `y` in this scope");
+    }
+
+    #[test]
+    fn cargo_test_panics_read_the_message_from_the_next_line() {
+        let output = "running 2 tests\n\
+                      thread 'tests::adds' panicked at src/lib.rs:10:9:\n\
+                      assertion `left == right` failed\n  left: 2\n right: 3\n";
+        let v = run(DiagnosticFormat::Cargo, "", output);
+        assert_eq!(locs(&v), vec![(Some("src/lib.rs"), Some(10), Some(9), None)]);
+        assert_eq!(v[0].message, "test tests::adds panicked: assertion `left == right` failed");
+    }
+
+    #[test]
+    fn tsc_plain_output_joins_message_chains() {
+        let output = "src/main.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n  \
+                      Type 'x' is not 'y'.\nsrc/util.ts(1,1): warning TS6133: 'a' is declared but never used.\n";
+        let v = run(DiagnosticFormat::Tsc, "app", output);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("app/src/main.ts"), Some(3), Some(7), Some("TS2322")),
+                (Some("app/src/util.ts"), Some(1), Some(1), Some("TS6133")),
+            ]
+        );
+        assert_eq!(
+            v[0].message,
+            "Type 'string' is not assignable to type 'number'.\nType 'x' is not 'y'."
+        );
+        assert!(v[1].severity == Severity::Warning);
+    }
+
+    #[test]
+    fn tsc_pretty_output() {
+        let output = "src/main.ts:9:1 - error TS2304: Cannot find name 'foo'.\n\n9 foo();\n  ~~~\n\nFound 1 error.\n";
+        let v = run(DiagnosticFormat::Tsc, "", output);
+        assert_eq!(locs(&v), vec![(Some("src/main.ts"), Some(9), Some(1), Some("TS2304"))]);
+        assert_eq!(v[0].message, "Cannot find name 'foo'.");
+    }
+
+    #[test]
+    fn eslint_json_after_an_npm_banner() {
+        let output = format!(
+            "\n> app@0.1.0 lint\n> eslint . -f json\n\n[{{\"filePath\":\"{}/app/src/main.ts\",\"messages\":[\
+             {{\"ruleId\":\"no-unused-vars\",\"severity\":2,\"message\":\"'a' is unused\",\"line\":1,\"column\":7}},\
+             {{\"ruleId\":null,\"severity\":1,\"message\":\"Unused eslint-disable directive\",\"line\":2,\"column\":1}}]}}]\n",
+            ROOT
+        );
+        let v = run(DiagnosticFormat::Eslint, "app", &output);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("app/src/main.ts"), Some(1), Some(7), Some("no-unused-vars")),
+                (Some("app/src/main.ts"), Some(2), Some(1), None),
+            ]
+        );
+        assert!(v[0].severity == Severity::Error && v[1].severity == Severity::Warning);
+    }
+
+    #[test]
+    fn eslint_stylish_groups_problems_under_file_lines() {
+        let output = format!(
+            "\n{root}/app/src/main.ts\n   1:7  error    'a' is assigned a value but never used  no-unused-vars\n   \
+             2:1  warning  Unexpected console statement                    no-console\n\n\
+             {root}/app/src/b.ts\n  4:2  error  Parsing error: Unexpected token\n\n✖ 3 problems (2 errors, 1 warning)\n",
+            root = ROOT
+        );
+        let v = run(DiagnosticFormat::Eslint, "app", &output);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("app/src/main.ts"), Some(1), Some(7), Some("no-unused-vars")),
+                (Some("app/src/main.ts"), Some(2), Some(1), Some("no-console")),
+                (Some("app/src/b.ts"), Some(4), Some(2), None),
+            ]
+        );
+        assert_eq!(v[2].message, "Parsing error: Unexpected token");
+    }
+
+    #[test]
+    fn pytest_tracebacks_win_over_the_short_summary() {
+        let output = "=================================== FAILURES ===================================
+___________________________________ test_add ___________________________________
+
+    def test_add():
+>       assert 1 + 1 == 3
+E       assert (1 + 1) == 3
+E        +  where 2 = 1 + 1
+
+tests/test_a.py:4: AssertionError
+_____________________________ TestMath.test_div[0] _____________________________
+
+tests/test_b.py:12: ZeroDivisionError
+=========================== short test summary info ============================
+FAILED tests/test_a.py::test_add - assert (1 + 1) == 3
+FAILED tests/test_b.py::TestMath::test_div[0] - ZeroDivisionError
+ERROR tests/test_c.py - ImportError: no module named x
+";
+        let v = run(DiagnosticFormat::Pytest, "", output);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("tests/test_a.py"), Some(4), None, Some("AssertionError")),
+                (Some("tests/test_b.py"), Some(12), None, Some("ZeroDivisionError")),
+                (Some("tests/test_c.py"), None, None, None),
+            ]
+        );
+        assert_eq!(v[0].message, "assert (1 + 1) == 3\n+  where 2 = 1 + 1");
+        assert_eq!(v[1].message, "ZeroDivisionError");
+        assert_eq!(v[2].message, "tests/test_c.py: ImportError: no module named x");
+    }
+
+    #[test]
+    fn test_names_drop_module_and_class_prefixes() {
+        assert_eq!(test_name("tests/a.py::TestX::test_y[1]"), "test_y[1]");
+        assert_eq!(test_name("TestX.test_y[a.b]"), "test_y[a.b]");
+        assert_eq!(test_name("test_plain"), "test_plain");
+    }
+
+    #[test]
+    fn ruff_text_and_json() {
+        let text = "tests/test_a.py:1:8: F401 [*] `os` imported but unused\nsrc/m.py:3:1: E402 Module level import not at top of file\nFound 2 errors.\n";
+        let v = run(DiagnosticFormat::Ruff, "py", text);
+        assert_eq!(
+            locs(&v),
+            vec![
+                (Some("py/tests/test_a.py"), Some(1), Some(8), Some("F401")),
+                (Some("py/src/m.py"), Some(3), Some(1), Some("E402")),
+            ]
+        );
+        assert_eq!(v[0].message, "`os` imported but unused");
+
+        let json = format!(
+            r#"[{{"code":"F401","filename":"{}/py/tests/test_a.py","location":{{"row":1,"column":8}},"message":"`os` imported but unused"}}]"#,
+            ROOT
+        );
+        let v = run(DiagnosticFormat::Ruff, "py", &json);
+        assert_eq!(locs(&v), vec![(Some("py/tests/test_a.py"), Some(1), Some(8), Some("F401"))]);
+    }
+
+    #[test]
+    fn paths_outside_the_workspace_have_no_file() {
+        let v = run(DiagnosticFormat::Tsc, "app", "../../elsewhere/x.ts(1,1): error TS1005: ';' expected.\n");
+        assert_eq!(locs(&v), vec![(None, Some(1), Some(1), Some("TS1005"))]);
+    }
+
+    #[test]
+    fn auto_runs_every_parser_and_dedups() {
+        let output = "src/main.ts(3,7): error TS2322: Bad.\nsrc/main.ts(3,7): error TS2322: Bad.\n\
+                      tests/test_a.py:1:8: F401 `os` imported but unused\n";
+        let v = run(DiagnosticFormat::Auto, "", output);
+        let sources: Vec<&str> = v.iter().map(|d| d.source).collect();
+        assert_eq!(sources, vec!["tsc", "ruff"]);
+    }
+}
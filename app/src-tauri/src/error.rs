@@ -0,0 +1,136 @@
+//! Error type returned by commands: a stable `kind` the UI can switch on, a human-readable
+//! message and structured details. Serialized as
+//! `{ "kind": "ServerHttp", "status": 503, "message": "...", "details": { ... } }`.
+
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Stable categories. Renaming a variant is a breaking change for the frontend.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind")]
+pub enum ErrorKind {
+    /// Bad argument: relative root, empty query, conflicting options.
+    InvalidInput,
+    NotFound,
+    /// Path resolves outside the workspace root.
+    EscapesWorkspace,
+    PermissionDenied,
+    /// Target already exists or is in a conflicting state (e.g. port in use).
+    Conflict,
+    /// Content the operation cannot handle, e.g. a binary file read as text.
+    Unsupported,
+    /// No llama-server has been started by this app.
+    RuntimeNotStarted,
+    /// llama-server binary not found for this platform.
+    RuntimeMissing,
+    /// GGUF model file not found.
+    ModelMissing,
+    /// llama-server could not be reached.
+    ServerUnreachable,
+    /// llama-server answered with a non-success status.
+    ServerHttp { status: u16 },
+    Timeout,
+    Io,
+    /// Malformed data: JSON, TOML, server responses.
+    Parse,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppError {
+    #[serde(flatten)]
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError {
+            kind,
+            message: message.into(),
+            details: Map::new(),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    /// Attach a structured detail (path, endpoint, ...).
+    pub fn with(mut self, key: &str, value: impl Serialize) -> Self {
+        if let Ok(v) = serde_json::to_value(value) {
+            self.details.insert(key.to_string(), v);
+        }
+        self
+    }
+
+    /// io::Error for `path`, categorized by its ErrorKind.
+    pub fn io(path: &Path, e: std::io::Error) -> Self {
+        let kind = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            std::io::ErrorKind::AlreadyExists => ErrorKind::Conflict,
+            std::io::ErrorKind::TimedOut => ErrorKind::Timeout,
+            std::io::ErrorKind::InvalidInput => ErrorKind::InvalidInput,
+            _ => ErrorKind::Io,
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        Self::new(kind, format!("{}: {}", path, e)).with("path", path)
+    }
+
+    /// Lock poisoned by a panic on another thread.
+    pub fn poisoned(what: &str) -> Self {
+        Self::new(ErrorKind::Other, format!("{} lock poisoned", what))
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// String errors from helpers not yet converted.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+}
+
+/// Run a walk or other long blocking job for an async command on the blocking pool, so it
+/// holds neither the main thread nor an async worker. A panic in `f` becomes an Other error.
+pub(crate) async fn blocking<T, E>(
+    f: impl FnOnce() -> Result<T, E> + Send + 'static,
+) -> AppResult<T>
+where
+    T: Send + 'static,
+    E: Into<AppError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::new(ErrorKind::Other, format!("task failed: {}", e)))?
+        .map_err(Into::into)
+}
+
+/// `.at(path)` on io results: categorize the error and record the path.
+pub trait IoResultExt<T> {
+    fn at(self, path: &Path) -> AppResult<T>;
+}
+
+impl<T> IoResultExt<T> for std::io::Result<T> {
+    fn at(self, path: &Path) -> AppResult<T> {
+        self.map_err(|e| AppError::io(path, e))
+    }
+}
@@ -0,0 +1,212 @@
+//! Fuzzy file-name matching for the file picker: fzf-style subsequence scoring over the
+//! full relative path, with a per-root cached file list so keystroke queries skip the walk.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::walker;
+
+/// Cached lists older than this are rebuilt. The watcher also invalidates on create/remove.
+const CACHE_TTL: Duration = Duration::from_secs(15);
+/// Roots kept in the cache; the least recently built is evicted.
+const CACHE_MAX_ROOTS: usize = 8;
+const LIST_MAX_DEPTH: usize = 26;
+const LIST_MAX_FILES: usize = 100_000;
+
+const SCORE_MATCH: i32 = 16;
+const GAP_START: i32 = 3;
+const GAP_EXTENSION: i32 = 1;
+/// Match right after a path separator (or at the very start).
+const BONUS_SEPARATOR: i32 = 10;
+/// Match right after `_`, `-`, `.` or a space.
+const BONUS_WORD: i32 = 8;
+/// Match at a lower→upper (camelCase) or letter→digit transition.
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 4;
+/// Per matched character inside the file name (vs. its directories).
+const BONUS_BASENAME: i32 = 3;
+const NONE: i32 = i32::MIN / 4;
+
+#[derive(Default)]
+pub struct FileListCache {
+    roots: HashMap<PathBuf, CachedList>,
+}
+
+struct CachedList {
+    files: Arc<Vec<String>>,
+    built: Instant,
+}
+
+/// Every non-ignored file under root (root-relative, sorted), from cache when fresh.
+pub fn file_list(cache: &Mutex<FileListCache>, root: &Path) -> Result<Arc<Vec<String>>, String> {
+    {
+        let c = cache.lock().map_err(|e| e.to_string())?;
+        if let Some(hit) = c.roots.get(root) {
+            if hit.built.elapsed() < CACHE_TTL {
+                return Ok(hit.files.clone());
+            }
+        }
+    }
+    // Walk without holding the lock; a concurrent rebuild just does the work twice.
+    let opts = walker::WalkOptions {
+        max_depth: Some(LIST_MAX_DEPTH),
+        ..Default::default()
+    };
+    let files: Arc<Vec<String>> = Arc::new(
+        walker::walk(root, &opts)?
+            .filter(|e| !e.is_dir)
+            .map(|e| e.rel_path)
+            .take(LIST_MAX_FILES)
+            .collect(),
+    );
+    let mut c = cache.lock().map_err(|e| e.to_string())?;
+    if c.roots.len() >= CACHE_MAX_ROOTS && !c.roots.contains_key(root) {
+        let oldest = c
+            .roots
+            .iter()
+            .min_by_key(|(_, v)| v.built)
+            .map(|(k, _)| k.clone());
+        if let Some(k) = oldest {
+            c.roots.remove(&k);
+        }
+    }
+    c.roots.insert(
+        root.to_path_buf(),
+        CachedList {
+            files: files.clone(),
+            built: Instant::now(),
+        },
+    );
+    Ok(files)
+}
+
+/// Drop the cached list for root so the next query walks again.
+pub fn invalidate(cache: &Mutex<FileListCache>, root: &Path) {
+    if let Ok(mut c) = cache.lock() {
+        c.roots.remove(root);
+    }
+}
+
+fn bonus(prev: Option<char>, cur: char) -> i32 {
+    match prev {
+        None | Some('/') | Some('\\') => BONUS_SEPARATOR,
+        Some('_' | '-' | '.' | ' ') => BONUS_WORD,
+        Some(p) if p.is_lowercase() && cur.is_uppercase() => BONUS_CAMEL,
+        Some(p) if !p.is_ascii_digit() && cur.is_ascii_digit() => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Best alignment score of term (already lowercased) as a subsequence of path, or None
+/// if it does not match. Case-insensitive; bonuses use the path's original case.
+fn score_term(term: &[char], path: &[char], base_start: usize) -> Option<i32> {
+    let (n, m) = (term.len(), path.len());
+    if n == 0 || n > m {
+        return None;
+    }
+    let folded: Vec<char> = path.iter().map(|&c| lower(c)).collect();
+    let mut qi = 0;
+    for &c in &folded {
+        if c == term[qi] {
+            qi += 1;
+            if qi == n {
+                break;
+            }
+        }
+    }
+    if qi < n {
+        return None;
+    }
+
+    let at: Vec<i32> = (0..m)
+        .map(|j| {
+            let prev = j.checked_sub(1).map(|p| path[p]);
+            let base = if j >= base_start { BONUS_BASENAME } else { 0 };
+            SCORE_MATCH + bonus(prev, path[j]) + base
+        })
+        .collect();
+    // row[j]: best score with the current term char matched at path[j].
+    let mut row: Vec<i32> = (0..m)
+        .map(|j| if folded[j] == term[0] { at[j] } else { NONE })
+        .collect();
+    for &tc in &term[1..] {
+        let mut next = vec![NONE; m];
+        let mut gap = NONE;
+        for j in 1..m {
+            if j >= 2 {
+                gap = (gap - GAP_EXTENSION).max(row[j - 2] - GAP_START);
+            }
+            if folded[j] != tc {
+                continue;
+            }
+            let best = (row[j - 1] + BONUS_CONSECUTIVE).max(gap);
+            if best > NONE / 2 {
+                next[j] = best + at[j];
+            }
+        }
+        row = next;
+    }
+    row.into_iter().max().filter(|&s| s > NONE / 2)
+}
+
+/// Score path against a whitespace-separated query; every term must match.
+fn score(terms: &[Vec<char>], path: &str) -> Option<i32> {
+    let chars: Vec<char> = path.chars().collect();
+    let base_start = chars
+        .iter()
+        .rposition(|&c| c == '/')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    terms
+        .iter()
+        .map(|t| score_term(t, &chars, base_start))
+        .sum()
+}
+
+/// 0 = exact file name, 1 = exact stem, 2 = fuzzy.
+fn tier(path: &str, query_lower: &str) -> u8 {
+    let p = Path::new(path);
+    let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
+    let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+    if name == query_lower {
+        0
+    } else if stem == query_lower {
+        1
+    } else {
+        2
+    }
+}
+
+/// Top `limit` paths for query: exact name, then exact stem, then by fuzzy score; ties go
+/// to the shorter, then alphabetically first, path.
+pub fn rank(files: &[String], query: &str, limit: usize) -> Vec<String> {
+    let query_lower = query.trim().replace('\\', "/").to_lowercase();
+    let terms: Vec<Vec<char>> = query_lower
+        .split_whitespace()
+        .map(|t| t.chars().collect())
+        .collect();
+    if terms.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let mut scored: Vec<(u8, i32, &String)> = files
+        .iter()
+        .filter_map(|f| score(&terms, f).map(|s| (tier(f, &query_lower), s, f)))
+        .collect();
+    scored.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(b.1.cmp(&a.1))
+            .then(a.2.len().cmp(&b.2.len()))
+            .then(a.2.cmp(b.2))
+    });
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, f)| f.clone())
+        .collect()
+}
@@ -0,0 +1,629 @@
+//! Git integration via libgit2: working-tree status, staged/unstaged diffs, recent log,
+//! blame and HEAD. The workspace may be a subdirectory of the repository; paths in and out
+//! are workspace-relative and entries outside the workspace are left out.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, FixedOffset};
+use git2::{
+    BlameOptions, BranchType, Delta, DiffOptions, ErrorCode, Oid, Repository, Sort, Status,
+    StatusOptions,
+};
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult, ErrorKind, IoResultExt};
+use crate::registry::{self, WorkspaceRegistry};
+
+const DEFAULT_LOG_LIMIT: usize = 20;
+const MAX_LOG_LIMIT: usize = 500;
+/// Commits examined when filtering the log by path.
+const LOG_SCAN_LIMIT: usize = 5000;
+const DEFAULT_CONTEXT_LINES: u32 = 3;
+/// Patch text per file and per diff call.
+const MAX_FILE_PATCH_BYTES: usize = 64 * 1024;
+const MAX_TOTAL_PATCH_BYTES: usize = 512 * 1024;
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    Typechange,
+    Untracked,
+    Conflicted,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHead {
+    /// None when detached.
+    pub branch: Option<String>,
+    /// None on an unborn branch (no commits yet).
+    pub commit: Option<String>,
+    pub summary: Option<String>,
+    pub detached: bool,
+    /// e.g. "origin/main".
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatus {
+    pub path: String,
+    /// Staged change (HEAD -> index).
+    pub staged: Option<ChangeKind>,
+    /// Unstaged change (index -> working tree).
+    pub unstaged: Option<ChangeKind>,
+    /// Previous path of a rename.
+    pub old_path: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    pub is_repo: bool,
+    /// Absolute repository working directory.
+    pub repo_root: Option<String>,
+    pub head: Option<GitHead>,
+    pub files: Vec<GitFileStatus>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: ChangeKind,
+    pub additions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+    /// Unified diff (headers use repository paths); empty for binary files.
+    pub patch: String,
+    pub truncated: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiff {
+    pub staged: bool,
+    pub files: Vec<GitFileDiff>,
+    /// The total patch budget ran out; later files have empty patches.
+    pub truncated: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommit {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub author: String,
+    pub email: String,
+    /// RFC 3339 with the author's offset.
+    pub time: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameLine {
+    /// 1-based.
+    pub line: usize,
+    /// None for uncommitted lines.
+    pub commit: Option<String>,
+    pub short_id: Option<String>,
+    pub author: Option<String>,
+    pub time: Option<String>,
+    pub summary: Option<String>,
+    pub content: String,
+}
+
+pub(crate) fn err(e: git2::Error) -> AppError {
+    let kind = match e.code() {
+        ErrorCode::NotFound => ErrorKind::NotFound,
+        ErrorCode::Exists | ErrorCode::Locked | ErrorCode::Conflict => ErrorKind::Conflict,
+        _ => ErrorKind::Other,
+    };
+    AppError::new(kind, e.message())
+}
+
+/// A repository plus where the workspace sits inside its working directory.
+pub(crate) struct Repo {
+    pub(crate) repo: Repository,
+    /// Workspace root relative to the workdir, with a trailing slash ("" when they coincide).
+    prefix: String,
+}
+
+impl Repo {
+    /// None when the workspace is not inside a (non-bare) repository.
+    fn open(workspace_root: &str) -> AppResult<Option<Repo>> {
+        let root = crate::workspace::canonical_root(workspace_root)?;
+        let repo = match Repository::discover(&root) {
+            Ok(r) => r,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(err(e)),
+        };
+        let Some(workdir) = repo.workdir() else {
+            return Ok(None);
+        };
+        let workdir = workdir.canonicalize().at(workdir)?;
+        let rel = root.strip_prefix(&workdir).map_err(|_| {
+            AppError::new(
+                ErrorKind::Other,
+                "workspace is outside the repository working directory",
+            )
+        })?;
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let prefix = if rel.is_empty() {
+            rel
+        } else {
+            format!("{}/", rel)
+        };
+        Ok(Some(Repo { repo, prefix }))
+    }
+
+    pub(crate) fn require(workspace_root: &str) -> AppResult<Repo> {
+        Self::open(workspace_root)?.ok_or_else(|| AppError::not_found("not a git repository"))
+    }
+
+    /// Workspace-relative path for a repo path; None when outside the workspace.
+    pub(crate) fn to_ws(&self, repo_path: &Path) -> Option<String> {
+        let p = repo_path.to_string_lossy().replace('\\', "/");
+        p.strip_prefix(self.prefix.as_str())
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+    }
+
+    pub(crate) fn to_repo(&self, ws_path: &str) -> AppResult<String> {
+        let p = ws_path.trim().replace('\\', "/");
+        let p = p.trim_start_matches("./").trim_matches('/');
+        if p.split('/').any(|c| c == "..") {
+            return Err(AppError::new(
+                ErrorKind::EscapesWorkspace,
+                "path must not escape workspace",
+            )
+            .with("path", ws_path));
+        }
+        Ok(format!("{}{}", self.prefix, p))
+    }
+
+    /// Pathspec limiting git operations to the workspace (or a path inside it).
+    pub(crate) fn pathspec(&self, ws_path: Option<&str>) -> AppResult<Option<String>> {
+        match ws_path.filter(|p| !p.trim().is_empty()) {
+            Some(p) => self.to_repo(p).map(Some),
+            None if self.prefix.is_empty() => Ok(None),
+            None => Ok(Some(self.prefix.trim_end_matches('/').to_string())),
+        }
+    }
+}
+
+pub(crate) fn format_time(t: git2::Time) -> String {
+    let offset = FixedOffset::east_opt(t.offset_minutes() * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    DateTime::from_timestamp(t.seconds(), 0)
+        .map(|d| d.with_timezone(&offset).to_rfc3339())
+        .unwrap_or_default()
+}
+
+pub(crate) fn short(oid: Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+fn head_info(repo: &Repository) -> AppResult<GitHead> {
+    let detached = repo.head_detached().unwrap_or(false);
+    let head = match repo.head() {
+        Ok(h) => Some(h),
+        Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => None,
+        Err(e) => return Err(err(e)),
+    };
+    let branch = if detached {
+        None
+    } else {
+        match &head {
+            Some(h) => h.shorthand().map(str::to_string),
+            // Unborn: HEAD still names the branch it will create.
+            None => repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|r| r.symbolic_target().map(str::to_string))
+                .map(|t| t.trim_start_matches("refs/heads/").to_string()),
+        }
+    };
+    let commit = head.as_ref().and_then(|h| h.peel_to_commit().ok());
+
+    let (mut upstream, mut ahead, mut behind) = (None, 0, 0);
+    if let (Some(name), Some(c)) = (&branch, &commit) {
+        if let Ok(up) = repo
+            .find_branch(name, BranchType::Local)
+            .and_then(|b| b.upstream())
+        {
+            upstream = up.name().ok().flatten().map(str::to_string);
+            if let Some(up_oid) = up.get().target() {
+                if let Ok((a, b)) = repo.graph_ahead_behind(c.id(), up_oid) {
+                    (ahead, behind) = (a, b);
+                }
+            }
+        }
+    }
+
+    Ok(GitHead {
+        branch,
+        commit: commit.as_ref().map(|c| c.id().to_string()),
+        summary: commit
+            .as_ref()
+            .and_then(|c| c.summary().map(str::to_string)),
+        detached,
+        upstream,
+        ahead,
+        behind,
+    })
+}
+
+fn staged_kind(s: Status) -> Option<ChangeKind> {
+    if s.is_conflicted() {
+        Some(ChangeKind::Conflicted)
+    } else if s.is_index_new() {
+        Some(ChangeKind::Added)
+    } else if s.is_index_modified() {
+        Some(ChangeKind::Modified)
+    } else if s.is_index_deleted() {
+        Some(ChangeKind::Deleted)
+    } else if s.is_index_renamed() {
+        Some(ChangeKind::Renamed)
+    } else if s.is_index_typechange() {
+        Some(ChangeKind::Typechange)
+    } else {
+        None
+    }
+}
+
+fn unstaged_kind(s: Status) -> Option<ChangeKind> {
+    if s.is_conflicted() {
+        Some(ChangeKind::Conflicted)
+    } else if s.is_wt_new() {
+        Some(ChangeKind::Untracked)
+    } else if s.is_wt_modified() {
+        Some(ChangeKind::Modified)
+    } else if s.is_wt_deleted() {
+        Some(ChangeKind::Deleted)
+    } else if s.is_wt_renamed() {
+        Some(ChangeKind::Renamed)
+    } else if s.is_wt_typechange() {
+        Some(ChangeKind::Typechange)
+    } else {
+        None
+    }
+}
+
+fn delta_kind(d: Delta) -> ChangeKind {
+    match d {
+        Delta::Added => ChangeKind::Added,
+        Delta::Deleted => ChangeKind::Deleted,
+        Delta::Renamed => ChangeKind::Renamed,
+        Delta::Copied => ChangeKind::Copied,
+        Delta::Typechange => ChangeKind::Typechange,
+        Delta::Untracked => ChangeKind::Untracked,
+        Delta::Conflicted => ChangeKind::Conflicted,
+        _ => ChangeKind::Modified,
+    }
+}
+
+/// Status of files changed in the working tree or index (untracked included, ignored not).
+fn status_files(r: &Repo) -> AppResult<Vec<GitFileStatus>> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true);
+    if let Some(spec) = r.pathspec(None)? {
+        opts.pathspec(spec);
+    }
+    let statuses = r.repo.statuses(Some(&mut opts)).map_err(err)?;
+    let mut files = Vec::new();
+    for e in statuses.iter() {
+        let s = e.status();
+        // For renames e.path() is the old name; report the current one.
+        let renamed = e
+            .index_to_workdir()
+            .filter(|_| s.is_wt_renamed())
+            .or_else(|| e.head_to_index().filter(|_| s.is_index_renamed()));
+        let current = renamed
+            .as_ref()
+            .and_then(|d| d.new_file().path())
+            .or_else(|| e.path().map(Path::new));
+        let Some(path) = current.and_then(|p| r.to_ws(p)) else {
+            continue;
+        };
+        let old_path = e
+            .head_to_index()
+            .filter(|_| s.is_index_renamed())
+            .or(renamed)
+            .and_then(|d| d.old_file().path().and_then(|p| r.to_ws(p)));
+        let (staged, unstaged) = (staged_kind(s), unstaged_kind(s));
+        if staged.is_none() && unstaged.is_none() {
+            continue;
+        }
+        files.push(GitFileStatus {
+            path,
+            staged,
+            unstaged,
+            old_path,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Per-file patches of a diff (renames detected), with paths made workspace-relative.
+pub(crate) fn collect_diff(
+    r: &Repo,
+    diff: &mut git2::Diff<'_>,
+    staged: bool,
+) -> AppResult<GitDiff> {
+    diff.find_similar(None).map_err(err)?;
+
+    let mut files = Vec::new();
+    let mut budget = MAX_TOTAL_PATCH_BYTES;
+    for i in 0..diff.deltas().len() {
+        let Some(patch) = git2::Patch::from_diff(diff, i).map_err(err)? else {
+            continue;
+        };
+        let delta = patch.delta();
+        let Some(path) = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .and_then(|p| r.to_ws(p))
+        else {
+            continue;
+        };
+        let status = delta_kind(delta.status());
+        let old_path = matches!(status, ChangeKind::Renamed | ChangeKind::Copied)
+            .then(|| delta.old_file().path().and_then(|p| r.to_ws(p)))
+            .flatten();
+        let binary = delta.flags().is_binary();
+        let (_, additions, deletions) = patch.line_stats().map_err(err)?;
+        let (mut text, mut truncated) = (String::new(), false);
+        if !binary {
+            let mut patch = patch;
+            let buf = patch.to_buf().map_err(err)?;
+            let full = String::from_utf8_lossy(&buf);
+            let limit = MAX_FILE_PATCH_BYTES.min(budget);
+            if full.len() > limit {
+                let mut cut = limit;
+                while !full.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                text = full[..cut].to_string();
+                truncated = true;
+            } else {
+                text = full.into_owned();
+            }
+            budget -= text.len();
+        }
+        files.push(GitFileDiff {
+            path,
+            old_path,
+            status,
+            additions,
+            deletions,
+            binary,
+            patch: text,
+            truncated,
+        });
+    }
+    Ok(GitDiff {
+        staged,
+        files,
+        truncated: budget == 0,
+    })
+}
+
+/// Branch, HEAD commit and upstream tracking for the workspace's repository.
+#[tauri::command]
+pub fn git_head(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<GitHead> {
+    registry::check_read(&registry, &workspace_root)?;
+    head_info(&Repo::require(&workspace_root)?.repo)
+}
+
+/// Working-tree status: HEAD plus every changed, staged or untracked file in the workspace.
+/// is_repo is false (not an error) when the workspace is not under git.
+#[tauri::command]
+pub fn git_status(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<GitStatus> {
+    registry::check_read(&registry, &workspace_root)?;
+    let Some(r) = Repo::open(&workspace_root)? else {
+        return Ok(GitStatus {
+            is_repo: false,
+            repo_root: None,
+            head: None,
+            files: Vec::new(),
+        });
+    };
+    Ok(GitStatus {
+        is_repo: true,
+        repo_root: r.repo.workdir().map(|p| {
+            p.to_string_lossy()
+                .trim_end_matches(['/', '\\'])
+                .to_string()
+        }),
+        head: Some(head_info(&r.repo)?),
+        files: status_files(&r)?,
+    })
+}
+
+/// Staged (HEAD -> index) or unstaged (index -> working tree, untracked files included)
+/// diff, optionally for one file or directory.
+#[tauri::command]
+pub fn git_diff(
+    workspace_root: String,
+    staged: Option<bool>,
+    path: Option<String>,
+    context_lines: Option<u32>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<GitDiff> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let staged = staged.unwrap_or(false);
+    let mut opts = DiffOptions::new();
+    opts.context_lines(context_lines.unwrap_or(DEFAULT_CONTEXT_LINES));
+    if let Some(spec) = r.pathspec(path.as_deref())? {
+        opts.pathspec(spec);
+    }
+    let mut diff = if staged {
+        let tree = match r.repo.head() {
+            Ok(h) => Some(h.peel_to_tree().map_err(err)?),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(err(e)),
+        };
+        r.repo
+            .diff_tree_to_index(tree.as_ref(), None, Some(&mut opts))
+            .map_err(err)?
+    } else {
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        r.repo
+            .diff_index_to_workdir(None, Some(&mut opts))
+            .map_err(err)?
+    };
+    collect_diff(&r, &mut diff, staged)
+}
+
+/// Most recent commits reachable from HEAD, newest first; with `path`, only commits that
+/// touched that file or directory.
+#[tauri::command]
+pub fn git_log(
+    workspace_root: String,
+    limit: Option<usize>,
+    path: Option<String>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<GitCommit>> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+    let spec = match path.as_deref().filter(|p| !p.trim().is_empty()) {
+        Some(p) => Some(r.to_repo(p)?),
+        None => None,
+    };
+    let mut walk = r.repo.revwalk().map_err(err)?;
+    walk.set_sorting(Sort::TIME).map_err(err)?;
+    match walk.push_head() {
+        Ok(()) => {}
+        Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => {
+            return Ok(Vec::new())
+        }
+        Err(e) => return Err(err(e)),
+    }
+
+    let mut out = Vec::new();
+    for oid in walk.take(LOG_SCAN_LIMIT) {
+        let commit = r.repo.find_commit(oid.map_err(err)?).map_err(err)?;
+        if let Some(spec) = &spec {
+            let tree = commit.tree().map_err(err)?;
+            let parent = commit.parent(0).ok().and_then(|p| p.tree().ok());
+            let mut opts = DiffOptions::new();
+            opts.pathspec(spec);
+            let diff = r
+                .repo
+                .diff_tree_to_tree(parent.as_ref(), Some(&tree), Some(&mut opts))
+                .map_err(err)?;
+            if diff.deltas().len() == 0 {
+                continue;
+            }
+        }
+        let author = commit.author();
+        out.push(GitCommit {
+            id: commit.id().to_string(),
+            short_id: short(commit.id()),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: author.name().unwrap_or_default().to_string(),
+            email: author.email().unwrap_or_default().to_string(),
+            time: format_time(author.when()),
+        });
+        if out.len() >= limit {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+/// Who last changed each line in [start_line, end_line] (1-based, inclusive) of the file as
+/// it is in the working tree; lines changed since HEAD have no commit.
+#[tauri::command]
+pub fn git_blame(
+    workspace_root: String,
+    path: String,
+    start_line: usize,
+    end_line: usize,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<GitBlameLine>> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    if start_line == 0 || end_line < start_line {
+        return Err(AppError::invalid("invalid line range"));
+    }
+    let repo_path = r.to_repo(&path)?;
+    let workdir = r
+        .repo
+        .workdir()
+        .ok_or_else(|| AppError::not_found("not a git repository"))?;
+    let file = workdir.join(&repo_path);
+    let content = std::fs::read(&file).at(&file)?;
+    let blame = r
+        .repo
+        .blame_file(Path::new(&repo_path), Some(&mut BlameOptions::new()))
+        .map_err(err)?;
+    let blame = blame.blame_buffer(&content).map_err(err)?;
+
+    let text = String::from_utf8_lossy(&content);
+    let mut commits: HashMap<Oid, Option<String>> = HashMap::new();
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate().skip(start_line - 1) {
+        let n = i + 1;
+        if n > end_line {
+            break;
+        }
+        let hunk = blame.get_line(n);
+        let oid = hunk
+            .as_ref()
+            .map(|h| h.final_commit_id())
+            .filter(|o| !o.is_zero());
+        let summary = oid.and_then(|o| {
+            commits
+                .entry(o)
+                .or_insert_with(|| {
+                    r.repo
+                        .find_commit(o)
+                        .ok()
+                        .and_then(|c| c.summary().map(str::to_string))
+                })
+                .clone()
+        });
+        let sig = hunk
+            .as_ref()
+            .filter(|_| oid.is_some())
+            .map(|h| h.final_signature());
+        out.push(GitBlameLine {
+            line: n,
+            commit: oid.map(|o| o.to_string()),
+            short_id: oid.map(short),
+            author: sig.as_ref().and_then(|s| s.name().map(str::to_string)),
+            time: sig.as_ref().map(|s| format_time(s.when())),
+            summary,
+            content: line.to_string(),
+        });
+    }
+    Ok(out)
+}
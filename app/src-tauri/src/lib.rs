@@ -1,30 +1,77 @@
+mod checkpoints;
+mod checks;
+mod contextpack;
+mod diagnostics;
+mod error;
+mod fuzzy;
+mod git;
+mod logging;
+mod project;
 mod project_root;
+mod registry;
+mod repomap;
 mod runtime;
+mod search;
+mod session_commit;
+mod settings;
+mod snapshot;
+mod symbols;
+mod textfile;
+mod tokens;
 mod toolroot;
+mod walker;
+mod watcher;
 mod workspace;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
+        .manage(std::sync::Mutex::new(registry::WorkspaceRegistry::default()))
         .manage(std::sync::Mutex::new(runtime::RuntimeState::default()))
+        .manage(std::sync::Mutex::new(watcher::WatcherState::default()))
+        .manage(std::sync::Mutex::new(fuzzy::FileListCache::default()))
+        .manage(std::sync::Mutex::new(symbols::SymbolIndexState::default()))
+        .manage(std::sync::Mutex::new(checks::CheckRunnerState::default()))
+        .manage(std::sync::Mutex::new(settings::SettingsWatchState::default()))
         .invoke_handler(tauri::generate_handler![
+            registry::workspace_open,
+            registry::workspace_open_enclosing,
+            registry::workspace_set_read_only,
+            registry::workspace_policy,
+            registry::workspace_close,
             workspace::workspace_read_dir,
             workspace::workspace_read_file,
+            workspace::workspace_read_file_detailed,
             workspace::workspace_write_file,
             workspace::write_project_file,
             workspace::workspace_exists,
             workspace::workspace_mkdir_all,
             workspace::workspace_file_size,
+            workspace::workspace_file_stats,
             workspace::workspace_resolve_path,
             workspace::workspace_ensure_log_dir,
             workspace::workspace_append_file,
             workspace::workspace_search_files_by_name,
-            workspace::workspace_walk_snapshot,
+            search::workspace_search_content,
+            snapshot::workspace_walk_snapshot,
+            snapshot::workspace_snapshot_changes,
+            symbols::workspace_find_symbol,
+            symbols::workspace_symbols_in_file,
+            symbols::workspace_enclosing_symbol,
+            repomap::workspace_repo_map,
+            watcher::workspace_watch_start,
+            watcher::workspace_watch_stop,
             project_root::detect_project_root,
+            project::project_detect,
+            settings::settings_get,
+            settings::settings_set,
+            settings::settings_watch,
+            settings::settings_unwatch,
             toolroot::find_tool_root,
             toolroot::scan_models_for_gguf,
             toolroot::scan_models_for_gguf_by_mtime,
@@ -34,7 +81,30 @@ pub fn run() {
             runtime::runtime_chat,
             runtime::runtime_status,
             runtime::runtime_stop,
+            runtime::runtime_discover,
+            runtime::runtime_autostart,
+            logging::logs_attach,
+            logging::logs_query,
+            logging::logs_tail,
             runtime::runtime_generate,
+            tokens::runtime_tokenize,
+            tokens::runtime_count_tokens,
+            contextpack::context_pack,
+            checks::checks_list,
+            checks::checks_run,
+            checks::checks_cancel,
+            diagnostics::diagnostics_parse,
+            git::git_status,
+            git::git_head,
+            git::git_diff,
+            git::git_log,
+            git::git_blame,
+            checkpoints::git_checkpoint_create,
+            checkpoints::git_checkpoint_list,
+            checkpoints::git_checkpoint_diff,
+            checkpoints::git_checkpoint_restore,
+            session_commit::git_session_commit_plan,
+            session_commit::git_session_commit,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,426 @@
+//! App logging via tracing. Events go to an in-memory ring (logs_query) and, once a workspace
+//! is attached, to JSON lines in .devassistant/logs/devassistant.log. The file rotates by size
+//! and by day; rotated files are pruned by count and age (settings `logging`).
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use chrono::{Local, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+use crate::error::{AppError, AppResult, IoResultExt};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::settings::{self, LoggingSettings};
+
+pub const LOGS_REL: &str = ".devassistant/logs";
+const FILE_STEM: &str = "devassistant";
+const FILE_EXT: &str = ".log";
+/// Records kept in memory for logs_query, across workspaces.
+const RING_CAPACITY: usize = 5000;
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 5000;
+
+/// One log line. Written to disk as a JSON object per line.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    /// RFC 3339 UTC with milliseconds; sorts as text.
+    pub ts: String,
+    /// TRACE, DEBUG, INFO, WARN or ERROR.
+    pub level: String,
+    pub target: String,
+    /// Process the line belongs to, e.g. "llama-server:11435:4242".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogQuery {
+    /// Lowest level to include (default: all).
+    pub min_level: Option<String>,
+    /// Target prefix, e.g. "llama_server".
+    pub target: Option<String>,
+    pub instance: Option<String>,
+    /// Case-insensitive substring of the message.
+    pub contains: Option<String>,
+    /// RFC 3339; only records at or after this time.
+    pub since: Option<String>,
+    /// Most recent N matches (default 200).
+    pub limit: Option<usize>,
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "TRACE" => 0,
+        "DEBUG" => 1,
+        "INFO" => 2,
+        "WARN" | "WARNING" => 3,
+        "ERROR" => 4,
+        _ => 2,
+    }
+}
+
+impl LogQuery {
+    fn matches(&self, r: &LogRecord) -> bool {
+        let contains = self.contains.as_deref().map(str::to_lowercase);
+        self.min_level
+            .as_deref()
+            .is_none_or(|l| level_rank(&r.level) >= level_rank(l))
+            && self.target.as_deref().is_none_or(|t| r.target.starts_with(t))
+            && self
+                .instance
+                .as_deref()
+                .is_none_or(|i| r.instance.as_deref() == Some(i))
+            && contains.is_none_or(|c| r.message.to_lowercase().contains(&c))
+            && self.since.as_deref().is_none_or(|s| r.ts.as_str() >= s)
+    }
+
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+/// Size- and day-rotated file: `devassistant.log` is active, rotated files are
+/// `devassistant-YYYYMMDD-HHMMSS.mmm.log`.
+struct RollingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    day: NaiveDate,
+    limits: LoggingSettings,
+}
+
+fn active_path(dir: &Path) -> PathBuf {
+    dir.join(format!("{}{}", FILE_STEM, FILE_EXT))
+}
+
+/// Rotated files in dir, newest first.
+fn rotated_files(dir: &Path) -> Vec<PathBuf> {
+    let prefix = format!("{}-", FILE_STEM);
+    let mut out: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(FILE_EXT))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // The timestamp in the name sorts chronologically.
+    out.sort();
+    out.reverse();
+    out
+}
+
+impl RollingFile {
+    fn open(dir: &Path, limits: LoggingSettings) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = active_path(dir);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let meta = file.metadata()?;
+        let day = meta
+            .modified()
+            .map(|t| chrono::DateTime::<Local>::from(t).date_naive())
+            .unwrap_or_else(|_| Local::now().date_naive());
+        let rf = RollingFile {
+            dir: dir.to_path_buf(),
+            file,
+            size: meta.len(),
+            day,
+            limits,
+        };
+        rf.prune();
+        Ok(rf)
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        let max_bytes = self.limits.max_file_mb as u64 * 1024 * 1024;
+        if self.size > 0 && (Local::now().date_naive() != self.day || self.size + len > max_bytes)
+        {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let active = active_path(&self.dir);
+        let stamp = Local::now().format("%Y%m%d-%H%M%S%.3f");
+        let rotated = self.dir.join(format!("{}-{}{}", FILE_STEM, stamp, FILE_EXT));
+        std::fs::rename(&active, rotated)?;
+        self.file = OpenOptions::new().create(true).append(true).open(&active)?;
+        self.size = 0;
+        self.day = Local::now().date_naive();
+        self.prune();
+        Ok(())
+    }
+
+    /// Keep at most maxFiles files (active included) and none older than retentionDays.
+    fn prune(&self) {
+        let max_age = Duration::from_secs(self.limits.retention_days as u64 * 24 * 60 * 60);
+        let now = SystemTime::now();
+        let keep = (self.limits.max_files as usize).saturating_sub(1);
+        for (i, path) in rotated_files(&self.dir).into_iter().enumerate() {
+            let expired = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| now.duration_since(t).ok())
+                .is_some_and(|age| age > max_age);
+            if i >= keep || expired {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+struct Sink {
+    ring: VecDeque<LogRecord>,
+    file: Option<RollingFile>,
+    /// Canonical workspace root the file belongs to.
+    workspace: Option<PathBuf>,
+}
+
+static SINK: Mutex<Sink> = Mutex::new(Sink {
+    ring: VecDeque::new(),
+    file: None,
+    workspace: None,
+});
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+fn record(rec: LogRecord) {
+    let Ok(mut sink) = SINK.lock() else {
+        return;
+    };
+    if let Some(file) = sink.file.as_mut() {
+        if let Ok(line) = serde_json::to_string(&rec) {
+            let _ = file.write_line(&line);
+        }
+    }
+    if sink.ring.len() >= RING_CAPACITY {
+        sink.ring.pop_front();
+    }
+    sink.ring.push_back(rec);
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    instance: Option<String>,
+    fields: BTreeMap<String, String>,
+}
+
+impl FieldVisitor {
+    fn put(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = value,
+            "instance" => self.instance = Some(value),
+            name => {
+                self.fields.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.put(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.put(field, format!("{:?}", value));
+    }
+}
+
+/// Layer that turns events into LogRecords for the ring and the rolling file.
+struct StoreLayer;
+
+impl<S: Subscriber> Layer<S> for StoreLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut v = FieldVisitor::default();
+        event.record(&mut v);
+        let meta = event.metadata();
+        record(LogRecord {
+            ts: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            level: meta.level().to_string(),
+            target: meta.target().to_string(),
+            instance: v.instance,
+            message: v.message,
+            fields: v.fields,
+        });
+    }
+}
+
+/// RUST_LOG when set, else the settings level.
+fn filter_for(level: &str) -> EnvFilter {
+    std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|v| EnvFilter::try_new(v).ok())
+        .or_else(|| EnvFilter::try_new(level).ok())
+        .unwrap_or_else(|| EnvFilter::new("info"))
+}
+
+/// Install the global subscriber. Until a workspace is attached, records only reach the
+/// ring (and stderr in debug builds).
+pub fn init() {
+    let level = settings::load(None).logging.level;
+    let (filter, handle) = reload::Layer::new(filter_for(&level));
+    let stderr = cfg!(debug_assertions).then(|| fmt::layer().with_writer(std::io::stderr));
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(StoreLayer)
+        .with(stderr);
+    if tracing::subscriber::set_global_default(subscriber).is_ok() {
+        let _ = FILTER.set(handle);
+    }
+    tracing::info!(target: "app", version = env!("CARGO_PKG_VERSION"), "started");
+}
+
+/// Write the log file under workspace_root/.devassistant/logs from now on, with that
+/// workspace's logging settings. Returns the active log file.
+pub(crate) fn attach(workspace_root: &Path) -> AppResult<PathBuf> {
+    let root = workspace_root.canonicalize().at(workspace_root)?;
+    let logging = settings::load(Some(&root)).logging;
+    let dir = root.join(LOGS_REL);
+    {
+        let mut sink = SINK.lock().map_err(|_| AppError::poisoned("log sink"))?;
+        if sink.workspace.as_deref() == Some(root.as_path()) && sink.file.is_some() {
+            if let Some(f) = sink.file.as_mut() {
+                f.limits = logging.clone();
+            }
+        } else {
+            let file = RollingFile::open(&dir, logging.clone()).at(&dir)?;
+            sink.file = Some(file);
+            sink.workspace = Some(root.clone());
+        }
+    }
+    if let Some(handle) = FILTER.get() {
+        let _ = handle.reload(filter_for(&logging.level));
+    }
+    tracing::debug!(target: "app", workspace = %root.display(), "log attached");
+    Ok(active_path(&dir))
+}
+
+/// Active log file for a workspace (it may not exist yet).
+pub(crate) fn log_file_path(workspace_root: &Path) -> PathBuf {
+    active_path(&workspace_root.join(LOGS_REL))
+}
+
+/// Forward each line of llama-server's stdout/stderr to the log (target `llama_server`),
+/// tagged with instance and stream. The thread ends at EOF, i.e. when the child exits.
+pub(crate) fn pipe_server_output<R: Read + Send + 'static>(
+    reader: Option<R>,
+    instance: String,
+    stream: &'static str,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+    let spawned = std::thread::Builder::new()
+        .name(format!("log-{}", stream))
+        .spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match reader.read_until(b'\n', &mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
+                }
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.trim_end();
+                if line.is_empty() {
+                    continue;
+                }
+                tracing::info!(target: "llama_server", instance = %instance, stream, "{}", line);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!(target: "app", error = %e, "could not start log pipe");
+    }
+}
+
+/// Route the app log to workspace_root/.devassistant/logs. Returns the active log file.
+#[tauri::command]
+pub fn logs_attach(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<String> {
+    let root = registry::check_read(&registry, &workspace_root)?;
+    registry::check_write(&registry, &workspace_root, &root.join(LOGS_REL))?;
+    attach(&root).map(|p| p.to_string_lossy().replace('\\', "/"))
+}
+
+/// Recent records held in memory (this app session), oldest first.
+#[tauri::command]
+pub fn logs_query(query: Option<LogQuery>) -> AppResult<Vec<LogRecord>> {
+    let q = query.unwrap_or_default();
+    let sink = SINK.lock().map_err(|_| AppError::poisoned("log sink"))?;
+    let mut out: Vec<LogRecord> = sink
+        .ring
+        .iter()
+        .rev()
+        .filter(|r| q.matches(r))
+        .take(q.limit())
+        .cloned()
+        .collect();
+    out.reverse();
+    Ok(out)
+}
+
+/// Last matching records from the workspace's log files (active, then rotated newest first),
+/// oldest first. Covers earlier sessions too; lines that are not log records are skipped.
+#[tauri::command]
+pub fn logs_tail(
+    workspace_root: String,
+    query: Option<LogQuery>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<LogRecord>> {
+    let root = registry::check_read(&registry, &workspace_root)?;
+    let q = query.unwrap_or_default();
+    let dir = root.join(LOGS_REL);
+    let limit = q.limit();
+    let mut out: Vec<LogRecord> = Vec::new();
+    let files = std::iter::once(active_path(&dir)).chain(rotated_files(&dir));
+    for path in files {
+        let Ok(raw) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in raw.lines().rev() {
+            let Ok(rec) = serde_json::from_str::<LogRecord>(line) else {
+                continue;
+            };
+            // Files are chronological, so everything further back is older still.
+            if q.since.as_deref().is_some_and(|s| rec.ts.as_str() < s) {
+                out.reverse();
+                return Ok(out);
+            }
+            if q.matches(&rec) {
+                out.push(rec);
+                if out.len() >= limit {
+                    out.reverse();
+                    return Ok(out);
+                }
+            }
+        }
+    }
+    out.reverse();
+    Ok(out)
+}
@@ -0,0 +1,1067 @@
+//! Project detection from the manifests themselves: package.json scripts and dependencies,
+//! Cargo.toml targets and features, pyproject.toml tool sections, Makefile targets, go.mod
+//! and pubspec.yaml. Reports languages, frameworks, entry points and runnable commands,
+//! each with the file (and key or line) it came from.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::error::AppResult;
+use crate::registry::{self, WorkspaceRegistry};
+use crate::walker;
+use crate::workspace::canonical_root;
+
+/// Manifests are read in the root and up to three directory levels below it.
+const MANIFEST_MAX_DEPTH: usize = 3;
+/// Files counted for the language breakdown before the walk stops.
+const MAX_SCAN_FILES: usize = 20_000;
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("mts", "TypeScript"),
+    ("cts", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("dart", "Dart"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("cxx", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("swift", "Swift"),
+    ("php", "PHP"),
+    ("rb", "Ruby"),
+    ("ps1", "PowerShell"),
+    ("psm1", "PowerShell"),
+    ("psd1", "PowerShell"),
+];
+
+/// npm dependency -> framework.
+const NODE_FRAMEWORKS: &[(&str, &str)] = &[
+    ("next", "Next.js"),
+    ("react", "React"),
+    ("react-native", "React Native"),
+    ("vue", "Vue"),
+    ("nuxt", "Nuxt"),
+    ("svelte", "Svelte"),
+    ("@sveltejs/kit", "SvelteKit"),
+    ("@angular/core", "Angular"),
+    ("solid-js", "Solid"),
+    ("astro", "Astro"),
+    ("@remix-run/react", "Remix"),
+    ("express", "Express"),
+    ("fastify", "Fastify"),
+    ("@nestjs/core", "NestJS"),
+    ("electron", "Electron"),
+    ("@tauri-apps/api", "Tauri"),
+    ("vite", "Vite"),
+    ("jest", "Jest"),
+    ("vitest", "Vitest"),
+];
+
+/// Cargo dependency -> framework.
+const RUST_FRAMEWORKS: &[(&str, &str)] = &[
+    ("tauri", "Tauri"),
+    ("axum", "Axum"),
+    ("actix-web", "Actix Web"),
+    ("rocket", "Rocket"),
+    ("warp", "Warp"),
+    ("tokio", "Tokio"),
+    ("bevy", "Bevy"),
+    ("leptos", "Leptos"),
+    ("yew", "Yew"),
+    ("dioxus", "Dioxus"),
+];
+
+/// Python distribution name (lowercase) -> framework.
+const PYTHON_FRAMEWORKS: &[(&str, &str)] = &[
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("starlette", "Starlette"),
+    ("streamlit", "Streamlit"),
+    ("pytest", "pytest"),
+];
+
+/// Go module path -> framework.
+const GO_FRAMEWORKS: &[(&str, &str)] = &[
+    ("github.com/gin-gonic/gin", "Gin"),
+    ("github.com/labstack/echo", "Echo"),
+    ("github.com/gofiber/fiber", "Fiber"),
+    ("github.com/go-chi/chi", "Chi"),
+    ("github.com/spf13/cobra", "Cobra"),
+];
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandKind {
+    Build,
+    Test,
+    Lint,
+    Typecheck,
+    Format,
+    Dev,
+    Run,
+    Other,
+}
+
+/// Kind implied by a script or make target name.
+fn kind_from_name(name: &str) -> CommandKind {
+    let n = name.to_ascii_lowercase();
+    let n = n.split([':', '-', '_']).next().unwrap_or("");
+    match n {
+        "build" | "compile" | "bundle" | "dist" | "all" => CommandKind::Build,
+        "test" | "tests" | "e2e" | "coverage" => CommandKind::Test,
+        "lint" | "clippy" | "vet" => CommandKind::Lint,
+        "typecheck" | "type" | "types" | "tsc" => CommandKind::Typecheck,
+        "format" | "fmt" | "prettier" => CommandKind::Format,
+        "dev" | "start" | "serve" | "watch" | "preview" => CommandKind::Dev,
+        "run" => CommandKind::Run,
+        _ => CommandKind::Other,
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Detected {
+    pub name: String,
+    /// Where it was found, e.g. "package.json#dependencies.react" or "files: 42 .rs".
+    pub source: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryPoint {
+    /// Workspace-relative.
+    pub path: String,
+    /// "main", "bin", "lib", "module" or "script".
+    pub kind: String,
+    pub source: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCommand {
+    /// Script or target name, e.g. "build" or "run --bin app".
+    pub name: String,
+    pub kind: CommandKind,
+    /// Command line to run from `cwd`.
+    pub command: String,
+    /// Workspace-relative directory ("" for the root).
+    pub cwd: String,
+    /// Where it came from, e.g. "app/package.json#scripts.build" or "Makefile:12".
+    pub source: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageInfo {
+    /// Workspace-relative manifest path.
+    pub manifest: String,
+    /// "node", "rust", "python", "go" or "dart".
+    pub ecosystem: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Cargo features (other ecosystems leave this empty).
+    pub features: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInfo {
+    /// Coarse project types as used for knowledge packs ("Node/TS", "Rust", "Tauri", ...).
+    pub detected_types: Vec<String>,
+    /// By file count, most first.
+    pub languages: Vec<Detected>,
+    pub frameworks: Vec<Detected>,
+    pub entry_points: Vec<EntryPoint>,
+    /// Root-level commands first, then nested packages.
+    pub commands: Vec<ProjectCommand>,
+    pub packages: Vec<PackageInfo>,
+    /// Manifests and config files worth reading first.
+    pub important_files: Vec<String>,
+    /// The file walk hit MAX_SCAN_FILES, so language counts are partial.
+    pub truncated: bool,
+}
+
+#[derive(Default)]
+struct Collector {
+    types: Vec<String>,
+    frameworks: Vec<Detected>,
+    entry_points: Vec<EntryPoint>,
+    commands: Vec<ProjectCommand>,
+    packages: Vec<PackageInfo>,
+    important: Vec<String>,
+    seen_commands: HashSet<(String, String)>,
+}
+
+impl Collector {
+    fn add_type(&mut self, t: &str) {
+        if !self.types.iter().any(|x| x == t) {
+            self.types.push(t.to_string());
+        }
+    }
+
+    fn framework(&mut self, name: &str, source: String) {
+        if !self.frameworks.iter().any(|f| f.name == name) {
+            self.frameworks.push(Detected {
+                name: name.to_string(),
+                source,
+            });
+        }
+    }
+
+    fn entry(&mut self, path: String, kind: &str, source: String) {
+        if !self.entry_points.iter().any(|e| e.path == path) {
+            self.entry_points.push(EntryPoint {
+                path,
+                kind: kind.to_string(),
+                source,
+            });
+        }
+    }
+
+    fn command(
+        &mut self,
+        dir: &str,
+        name: &str,
+        kind: CommandKind,
+        command: String,
+        source: String,
+    ) {
+        if self
+            .seen_commands
+            .insert((dir.to_string(), command.clone()))
+        {
+            self.commands.push(ProjectCommand {
+                name: name.to_string(),
+                kind,
+                command,
+                cwd: dir.to_string(),
+                source,
+            });
+        }
+    }
+
+    fn important(&mut self, rel: String) {
+        if !self.important.contains(&rel) {
+            self.important.push(rel);
+        }
+    }
+}
+
+fn join_rel(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// Entry point path relative to the workspace, if the file exists.
+fn existing(base: &Path, dir: &str, rel: &str) -> Option<String> {
+    let rel = rel.trim_start_matches("./");
+    base.join(rel).is_file().then(|| join_rel(dir, rel))
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// Package manager implied by the lockfile in `dir` (npm when there is none).
+fn node_package_manager(dir: &Path) -> &'static str {
+    if dir.join("pnpm-lock.yaml").is_file() {
+        "pnpm"
+    } else if dir.join("yarn.lock").is_file() {
+        "yarn"
+    } else if dir.join("bun.lockb").is_file() || dir.join("bun.lock").is_file() {
+        "bun"
+    } else {
+        "npm"
+    }
+}
+
+fn node_package(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let manifest = join_rel(dir, "package.json");
+    let Some(pkg) = read_json(&base.join("package.json")) else {
+        return;
+    };
+    c.add_type("Node/TS");
+    c.important(manifest.clone());
+    let text = |key: &str| pkg.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    c.packages.push(PackageInfo {
+        manifest: manifest.clone(),
+        ecosystem: "node".into(),
+        name: text("name"),
+        version: text("version"),
+        features: Vec::new(),
+    });
+
+    for key in ["main", "module"] {
+        if let Some(path) = text(key).and_then(|p| existing(&base, dir, &p)) {
+            c.entry(path, key, format!("{}#{}", manifest, key));
+        }
+    }
+    match pkg.get("bin") {
+        Some(serde_json::Value::String(p)) => {
+            if let Some(path) = existing(&base, dir, p) {
+                c.entry(path, "bin", format!("{}#bin", manifest));
+            }
+        }
+        Some(serde_json::Value::Object(bins)) => {
+            for (name, p) in bins {
+                if let Some(path) = p.as_str().and_then(|p| existing(&base, dir, p)) {
+                    c.entry(path, "bin", format!("{}#bin.{}", manifest, name));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let pm = node_package_manager(&base);
+    if let Some(scripts) = pkg.get("scripts").and_then(|s| s.as_object()) {
+        for name in scripts.keys() {
+            // npm's pre/post hooks run with their script.
+            let hooked = name
+                .strip_prefix("pre")
+                .or_else(|| name.strip_prefix("post"));
+            if hooked.is_some_and(|s| scripts.contains_key(s)) {
+                continue;
+            }
+            c.command(
+                dir,
+                name,
+                kind_from_name(name),
+                format!("{} run {}", pm, name),
+                format!("{}#scripts.{}", manifest, name),
+            );
+        }
+    }
+    let scripts = pkg.get("scripts").and_then(|s| s.as_object());
+    let typecheck = ["typecheck", "type-check"]
+        .iter()
+        .any(|n| scripts.is_some_and(|s| s.contains_key(*n)));
+    if !typecheck && base.join("tsconfig.json").is_file() {
+        c.command(
+            dir,
+            "tsc",
+            CommandKind::Typecheck,
+            "npx tsc --noEmit".into(),
+            join_rel(dir, "tsconfig.json"),
+        );
+    }
+
+    for section in ["dependencies", "devDependencies", "peerDependencies"] {
+        let Some(deps) = pkg.get(section).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (dep, name) in NODE_FRAMEWORKS {
+            if deps.contains_key(*dep) {
+                c.framework(name, format!("{}#{}.{}", manifest, section, dep));
+            }
+        }
+    }
+}
+
+fn rust_package(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let manifest = join_rel(dir, "Cargo.toml");
+    let Some(toml) = read_toml(&base.join("Cargo.toml")) else {
+        return;
+    };
+    c.add_type("Rust");
+    c.important(manifest.clone());
+    let package = toml.get("package").and_then(|p| p.as_table());
+    let pkg_text = |key: &str| {
+        package
+            .and_then(|p| p.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let name = pkg_text("name");
+    if package.is_some() {
+        c.packages.push(PackageInfo {
+            manifest: manifest.clone(),
+            ecosystem: "rust".into(),
+            name: name.clone(),
+            version: pkg_text("version"),
+            features: toml
+                .get("features")
+                .and_then(|f| f.as_table())
+                .map(|f| f.keys().cloned().collect())
+                .unwrap_or_default(),
+        });
+    }
+    let section = if package.is_some() {
+        "[package]"
+    } else {
+        "[workspace]"
+    };
+    for (cmd, kind) in [
+        ("build", CommandKind::Build),
+        ("test", CommandKind::Test),
+        ("clippy --all-targets", CommandKind::Lint),
+        ("fmt --check", CommandKind::Format),
+    ] {
+        c.command(
+            dir,
+            cmd.split(' ').next().unwrap_or(cmd),
+            kind,
+            format!("cargo {}", cmd),
+            format!("{}#{}", manifest, section),
+        );
+    }
+    if package.is_none() {
+        return;
+    }
+
+    // Targets: explicit [lib]/[[bin]] first, then Cargo's auto-discovered paths.
+    let lib_path = toml
+        .get("lib")
+        .and_then(|l| l.get("path"))
+        .and_then(|p| p.as_str())
+        .unwrap_or("src/lib.rs");
+    if let Some(path) = existing(&base, dir, lib_path) {
+        c.entry(path, "lib", format!("{}#lib", manifest));
+    }
+    let mut bins: Vec<(String, String, String)> = Vec::new();
+    if let Some(list) = toml.get("bin").and_then(|b| b.as_array()) {
+        for (i, b) in list.iter().enumerate() {
+            let Some(bin_name) = b.get("name").and_then(|n| n.as_str()) else {
+                continue;
+            };
+            let path = b
+                .get("path")
+                .and_then(|p| p.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("src/bin/{}.rs", bin_name));
+            bins.push((
+                bin_name.to_string(),
+                path,
+                format!("{}#bin[{}]", manifest, i),
+            ));
+        }
+    }
+    if let Some(n) = &name {
+        if !bins.iter().any(|(b, _, _)| b == n) && base.join("src/main.rs").is_file() {
+            bins.push((
+                n.clone(),
+                "src/main.rs".into(),
+                format!("{}#package", manifest),
+            ));
+        }
+    }
+    if let Ok(entries) = std::fs::read_dir(base.join("src/bin")) {
+        let mut auto: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let p = e.file_name().to_string_lossy().to_string();
+                p.strip_suffix(".rs").map(str::to_string)
+            })
+            .collect();
+        auto.sort();
+        for b in auto {
+            if !bins.iter().any(|(n, _, _)| n == &b) {
+                let path = format!("src/bin/{}.rs", b);
+                bins.push((b, path, format!("{} (src/bin)", manifest)));
+            }
+        }
+    }
+    let single = bins.len() == 1;
+    for (bin_name, path, source) in bins {
+        if let Some(p) = existing(&base, dir, &path) {
+            c.entry(p, "bin", source.clone());
+        }
+        let (label, command) = if single {
+            ("run".to_string(), "cargo run".to_string())
+        } else {
+            (
+                format!("run --bin {}", bin_name),
+                format!("cargo run --bin {}", bin_name),
+            )
+        };
+        c.command(dir, &label, CommandKind::Run, command, source);
+    }
+
+    for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        let Some(deps) = toml.get(section).and_then(|d| d.as_table()) else {
+            continue;
+        };
+        for (dep, fw) in RUST_FRAMEWORKS {
+            if deps.contains_key(*dep) {
+                c.framework(fw, format!("{}#{}.{}", manifest, section, dep));
+            }
+        }
+    }
+}
+
+/// Distribution name from a PEP 508 requirement ("Django>=4; python_version>'3'" -> "django").
+fn requirement_name(req: &str) -> String {
+    req.trim()
+        .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.'))
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase()
+        .replace('_', "-")
+}
+
+/// `source` is the prefix the dependency name is appended to, e.g. "pyproject.toml#dependencies.".
+fn python_frameworks(c: &mut Collector, names: &[String], source: &str) {
+    for (dep, fw) in PYTHON_FRAMEWORKS {
+        if names.iter().any(|n| n == dep) {
+            c.framework(fw, format!("{}{}", source, dep));
+        }
+    }
+}
+
+fn python_package(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let pyproject = base.join("pyproject.toml");
+    let requirements = base.join("requirements.txt");
+    if !pyproject.is_file() && !requirements.is_file() {
+        return;
+    }
+    c.add_type("Python");
+    let mut deps: Vec<String> = Vec::new();
+    let mut pytest = base.join("pytest.ini").is_file() || base.join("tests").is_dir();
+
+    if let Ok(raw) = std::fs::read_to_string(&requirements) {
+        let manifest = join_rel(dir, "requirements.txt");
+        c.important(manifest.clone());
+        let names: Vec<String> = raw
+            .lines()
+            .map(|l| l.split('#').next().unwrap_or("").trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('-'))
+            .map(requirement_name)
+            .collect();
+        python_frameworks(c, &names, &format!("{}#", manifest));
+        deps.extend(names);
+    }
+
+    if let Some(toml) = read_toml(&pyproject) {
+        let manifest = join_rel(dir, "pyproject.toml");
+        c.important(manifest.clone());
+        let project = toml.get("project").and_then(|p| p.as_table());
+        let tool = toml.get("tool").and_then(|t| t.as_table());
+        let poetry = tool
+            .and_then(|t| t.get("poetry"))
+            .and_then(|p| p.as_table());
+        let text = |t: Option<&toml::Table>, key: &str| {
+            t.and_then(|t| t.get(key))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        c.packages.push(PackageInfo {
+            manifest: manifest.clone(),
+            ecosystem: "python".into(),
+            name: text(project, "name").or_else(|| text(poetry, "name")),
+            version: text(project, "version").or_else(|| text(poetry, "version")),
+            features: Vec::new(),
+        });
+
+        let mut names: Vec<String> = project
+            .and_then(|p| p.get("dependencies"))
+            .and_then(|d| d.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(requirement_name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(optional) = project
+            .and_then(|p| p.get("optional-dependencies"))
+            .and_then(|d| d.as_table())
+        {
+            for list in optional.values().filter_map(|v| v.as_array()) {
+                names.extend(list.iter().filter_map(|v| v.as_str()).map(requirement_name));
+            }
+        }
+        for key in ["dependencies", "dev-dependencies"] {
+            if let Some(d) = poetry.and_then(|p| p.get(key)).and_then(|d| d.as_table()) {
+                names.extend(d.keys().map(|k| requirement_name(k)));
+            }
+        }
+        python_frameworks(c, &names, &format!("{}#dependencies.", manifest));
+        deps.extend(names);
+
+        // Console scripts: [project.scripts] and [tool.poetry.scripts].
+        for (table, key) in [
+            (project, "project.scripts"),
+            (poetry, "tool.poetry.scripts"),
+        ] {
+            let Some(scripts) = table
+                .and_then(|t| t.get("scripts"))
+                .and_then(|s| s.as_table())
+            else {
+                continue;
+            };
+            for (name, target) in scripts {
+                let source = format!("{}#{}.{}", manifest, key, name);
+                if let Some(module) = target.as_str().and_then(|t| t.split(':').next()) {
+                    let rel = format!("{}.py", module.replace('.', "/"));
+                    let found = existing(&base, dir, &rel)
+                        .or_else(|| existing(&base, dir, &format!("src/{}", rel)));
+                    if let Some(path) = found {
+                        c.entry(path, "script", source.clone());
+                    }
+                }
+                c.command(dir, name, CommandKind::Run, name.clone(), source);
+            }
+        }
+
+        if let Some(tool) = tool {
+            let section = |name: &str| format!("{}#tool.{}", manifest, name);
+            if tool.contains_key("pytest") {
+                pytest = true;
+            }
+            if tool.contains_key("ruff") {
+                c.command(
+                    dir,
+                    "ruff",
+                    CommandKind::Lint,
+                    "ruff check .".into(),
+                    section("ruff"),
+                );
+                c.command(
+                    dir,
+                    "ruff format",
+                    CommandKind::Format,
+                    "ruff format --check .".into(),
+                    section("ruff"),
+                );
+            }
+            if tool.contains_key("black") {
+                c.command(
+                    dir,
+                    "black",
+                    CommandKind::Format,
+                    "black --check .".into(),
+                    section("black"),
+                );
+            }
+            if tool.contains_key("isort") {
+                c.command(
+                    dir,
+                    "isort",
+                    CommandKind::Format,
+                    "isort --check-only .".into(),
+                    section("isort"),
+                );
+            }
+            if tool.contains_key("mypy") {
+                c.command(
+                    dir,
+                    "mypy",
+                    CommandKind::Typecheck,
+                    "mypy .".into(),
+                    section("mypy"),
+                );
+            }
+            if tool.contains_key("pyright") {
+                c.command(
+                    dir,
+                    "pyright",
+                    CommandKind::Typecheck,
+                    "pyright".into(),
+                    section("pyright"),
+                );
+            }
+        }
+        if toml.contains_key("build-system") {
+            c.command(
+                dir,
+                "build",
+                CommandKind::Build,
+                "python -m build".into(),
+                format!("{}#build-system", manifest),
+            );
+        }
+    }
+
+    if pytest || deps.iter().any(|d| d == "pytest") {
+        let source = if pyproject.is_file() {
+            join_rel(dir, "pyproject.toml")
+        } else {
+            join_rel(dir, "requirements.txt")
+        };
+        c.command(dir, "pytest", CommandKind::Test, "pytest".into(), source);
+    }
+    for main in ["manage.py", "main.py", "app.py", "__main__.py"] {
+        if let Some(path) = existing(&base, dir, main) {
+            c.entry(path.clone(), "main", path);
+        }
+    }
+}
+
+/// Make targets in file order, skipping special (.PHONY), pattern (%) and variable lines.
+fn make_targets(raw: &str) -> Vec<(String, usize)> {
+    let mut out: Vec<(String, usize)> = Vec::new();
+    for (i, line) in raw.lines().enumerate() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((head, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') || head.contains(['=', '%', '$']) {
+            continue;
+        }
+        for t in head.split_whitespace() {
+            if !out.iter().any(|(n, _)| n == t) {
+                out.push((t.to_string(), i + 1));
+            }
+        }
+    }
+    out
+}
+
+fn makefile(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let Some(name) = ["Makefile", "makefile", "GNUmakefile"]
+        .into_iter()
+        .find(|n| base.join(n).is_file())
+    else {
+        return;
+    };
+    let Ok(raw) = std::fs::read_to_string(base.join(name)) else {
+        return;
+    };
+    let manifest = join_rel(dir, name);
+    c.important(manifest.clone());
+    for (target, line) in make_targets(&raw) {
+        c.command(
+            dir,
+            &target,
+            kind_from_name(&target),
+            format!("make {}", target),
+            format!("{}:{}", manifest, line),
+        );
+    }
+}
+
+fn go_module(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let Ok(raw) = std::fs::read_to_string(base.join("go.mod")) else {
+        return;
+    };
+    let manifest = join_rel(dir, "go.mod");
+    c.add_type("Go");
+    c.important(manifest.clone());
+    let mut module = None;
+    let mut version = None;
+    let mut requires: Vec<String> = Vec::new();
+    let mut in_require = false;
+    for line in raw.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_require {
+            if line == ")" {
+                in_require = false;
+            } else if let Some(path) = line.split_whitespace().next() {
+                requires.push(path.to_string());
+            }
+        } else if let Some(m) = line.strip_prefix("module ") {
+            module = Some(m.trim().trim_matches('"').to_string());
+        } else if let Some(v) = line.strip_prefix("go ") {
+            version = Some(v.trim().to_string());
+        } else if line == "require (" {
+            in_require = true;
+        } else if let Some(r) = line.strip_prefix("require ") {
+            if let Some(path) = r.split_whitespace().next() {
+                requires.push(path.to_string());
+            }
+        }
+    }
+    c.packages.push(PackageInfo {
+        manifest: manifest.clone(),
+        ecosystem: "go".into(),
+        name: module,
+        version,
+        features: Vec::new(),
+    });
+    for (path, fw) in GO_FRAMEWORKS {
+        // Major-version suffixes (github.com/labstack/echo/v4) count too.
+        if requires
+            .iter()
+            .any(|r| r == path || r.starts_with(&format!("{}/", path)))
+        {
+            c.framework(fw, format!("{}#require.{}", manifest, path));
+        }
+    }
+    for (cmd, kind) in [
+        ("build ./...", CommandKind::Build),
+        ("test ./...", CommandKind::Test),
+        ("vet ./...", CommandKind::Lint),
+    ] {
+        c.command(
+            dir,
+            cmd.split(' ').next().unwrap_or(cmd),
+            kind,
+            format!("go {}", cmd),
+            manifest.clone(),
+        );
+    }
+    if let Some(path) = existing(&base, dir, "main.go") {
+        c.entry(path.clone(), "main", path.clone());
+        c.command(dir, "run", CommandKind::Run, "go run .".into(), path);
+    }
+    if let Ok(entries) = std::fs::read_dir(base.join("cmd")) {
+        let mut cmds: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join("main.go").is_file())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        cmds.sort();
+        for name in cmds {
+            let rel = format!("cmd/{}/main.go", name);
+            if let Some(path) = existing(&base, dir, &rel) {
+                c.entry(path.clone(), "main", path.clone());
+                c.command(
+                    dir,
+                    &format!("run ./cmd/{}", name),
+                    CommandKind::Run,
+                    format!("go run ./cmd/{}", name),
+                    path,
+                );
+            }
+        }
+    }
+}
+
+/// pubspec.yaml: top-level `name:`/`version:` and whether `flutter:` is a dependency.
+fn dart_package(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let Ok(raw) = std::fs::read_to_string(base.join("pubspec.yaml")) else {
+        return;
+    };
+    let manifest = join_rel(dir, "pubspec.yaml");
+    c.important(manifest.clone());
+    let top = |key: &str| {
+        raw.lines()
+            .find_map(|l| l.strip_prefix(key))
+            .map(|v| {
+                v.trim()
+                    .trim_matches(|ch| ch == '"' || ch == '\'')
+                    .to_string()
+            })
+            .filter(|v| !v.is_empty())
+    };
+    c.packages.push(PackageInfo {
+        manifest: manifest.clone(),
+        ecosystem: "dart".into(),
+        name: top("name:"),
+        version: top("version:"),
+        features: Vec::new(),
+    });
+    let flutter = raw
+        .lines()
+        .any(|l| l.trim_start() == "flutter:" && l.starts_with(' '));
+    let tool = if flutter {
+        c.add_type("Flutter");
+        c.framework("Flutter", format!("{}#dependencies.flutter", manifest));
+        "flutter"
+    } else {
+        "dart"
+    };
+    c.command(
+        dir,
+        "test",
+        CommandKind::Test,
+        format!("{} test", tool),
+        manifest.clone(),
+    );
+    c.command(
+        dir,
+        "analyze",
+        CommandKind::Lint,
+        format!("{} analyze", tool),
+        manifest.clone(),
+    );
+    c.command(
+        dir,
+        "run",
+        CommandKind::Dev,
+        format!("{} run", tool),
+        manifest,
+    );
+    if let Some(path) = existing(&base, dir, "lib/main.dart") {
+        c.entry(path.clone(), "main", path);
+    }
+}
+
+/// Config files that identify a framework or type without a manifest to parse.
+fn config_files(root: &Path, dir: &str, c: &mut Collector) {
+    let base = root.join(dir);
+    let has = |name: &str| base.join(name).is_file();
+    if has("tsconfig.json") {
+        c.add_type("Node/TS");
+        c.important(join_rel(dir, "tsconfig.json"));
+    }
+    if has("poetry.lock") {
+        c.add_type("Python");
+    }
+    if has("tauri.conf.json") {
+        let rel = join_rel(dir, "tauri.conf.json");
+        c.framework("Tauri", rel.clone());
+        c.important(rel);
+    }
+    if has("firebase.json") {
+        let rel = join_rel(dir, "firebase.json");
+        c.framework("Firebase", rel.clone());
+        c.important(rel);
+    }
+    for name in [
+        "next.config.js",
+        "next.config.mjs",
+        "next.config.ts",
+        "next.config.mts",
+    ] {
+        if has(name) {
+            let rel = join_rel(dir, name);
+            c.framework("Next.js", rel.clone());
+            c.important(rel);
+        }
+    }
+    if has("CMakeLists.txt") {
+        let rel = join_rel(dir, "CMakeLists.txt");
+        c.add_type("C/C++");
+        c.important(rel.clone());
+        c.command(
+            dir,
+            "cmake",
+            CommandKind::Build,
+            "cmake -S . -B build && cmake --build build".into(),
+            rel,
+        );
+    }
+}
+
+fn scan_dir(root: &Path, dir: &str, c: &mut Collector) {
+    node_package(root, dir, c);
+    rust_package(root, dir, c);
+    python_package(root, dir, c);
+    go_module(root, dir, c);
+    dart_package(root, dir, c);
+    makefile(root, dir, c);
+    config_files(root, dir, c);
+}
+
+pub(crate) fn detect(root: &Path) -> ProjectInfo {
+    let mut c = Collector::default();
+    let mut counts: BTreeMap<&'static str, (usize, BTreeMap<String, usize>)> = BTreeMap::new();
+    let mut files = 0usize;
+    let mut truncated = false;
+    if let Ok(entries) = walker::walk(root, &walker::WalkOptions::default()) {
+        for e in entries.filter(|e| !e.is_dir) {
+            files += 1;
+            if files > MAX_SCAN_FILES {
+                truncated = true;
+                break;
+            }
+            let ext = e
+                .rel_path
+                .rsplit_once('.')
+                .map(|(_, x)| x.to_ascii_lowercase());
+            if let Some(ext) = ext {
+                if let Some((_, lang)) = LANGUAGES.iter().find(|(x, _)| *x == ext) {
+                    let entry = counts.entry(lang).or_default();
+                    entry.0 += 1;
+                    *entry.1.entry(ext).or_default() += 1;
+                }
+            }
+        }
+    }
+    // Manifest directories come from their own shallow walk so the file cap above never
+    // hides a package; shallow directories first so root-level commands lead.
+    let shallow = walker::WalkOptions {
+        max_depth: Some(MANIFEST_MAX_DEPTH),
+        ..Default::default()
+    };
+    let mut dirs = vec![String::new()];
+    if let Ok(entries) = walker::walk(root, &shallow) {
+        dirs.extend(entries.filter(|e| e.is_dir).map(|e| e.rel_path));
+    }
+    dirs.sort_by_key(|d| {
+        (
+            d.matches('/').count() + usize::from(!d.is_empty()),
+            d.clone(),
+        )
+    });
+    for dir in &dirs {
+        scan_dir(root, dir, &mut c);
+    }
+
+    let mut languages: Vec<(usize, Detected)> = counts
+        .into_iter()
+        .map(|(lang, (n, exts))| {
+            let by_ext: Vec<String> = exts
+                .into_iter()
+                .map(|(ext, k)| format!("{} .{}", k, ext))
+                .collect();
+            (
+                n,
+                Detected {
+                    name: lang.to_string(),
+                    source: format!("files: {}", by_ext.join(", ")),
+                },
+            )
+        })
+        .collect();
+    languages.sort_by_key(|(n, _)| std::cmp::Reverse(*n));
+    let languages: Vec<Detected> = languages.into_iter().map(|(_, d)| d).collect();
+
+    // Types that come from source files or frameworks rather than one manifest.
+    let lang = |name: &str| languages.iter().any(|l| l.name == name);
+    if lang("C") || lang("C++") {
+        c.add_type("C/C++");
+    }
+    if lang("PowerShell") {
+        c.add_type("PowerShell");
+    }
+    for fw in ["Tauri", "Firebase", "Next.js"] {
+        if c.frameworks.iter().any(|f| f.name == fw) {
+            c.add_type(fw);
+        }
+    }
+
+    ProjectInfo {
+        detected_types: c.types,
+        languages,
+        frameworks: c.frameworks,
+        entry_points: c.entry_points,
+        commands: c.commands,
+        packages: c.packages,
+        important_files: c.important,
+        truncated,
+    }
+}
+
+/// Languages, frameworks, entry points and runnable commands parsed from the manifests in
+/// the workspace root and up to three levels below, each with its source.
+#[tauri::command]
+pub fn project_detect(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<ProjectInfo> {
+    registry::check_read(&registry, &workspace_root)?;
+    let root = canonical_root(&workspace_root)?;
+    Ok(detect(&root))
+}
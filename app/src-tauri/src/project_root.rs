@@ -1,82 +1,734 @@
 //! Project root detection: walk upward from start path, find first directory
-//! containing project signals (.git, package.json, Cargo.toml, etc.).
-
-use std::path::Path;
-
-const SIGNALS: &[&str] = &[
-    ".git",
-    "package.json",
-    "pnpm-lock.yaml",
-    "package-lock.json",
-    "yarn.lock",
-    "Cargo.toml",
-    "pyproject.toml",
-    "requirements.txt",
-    "go.mod",
-    "composer.json",
+//! containing project signals (.git, package.json, Cargo.toml, etc.). Signals come from
+//! the ECOSYSTEMS table, whose weights and priorities also pick the root's ecosystem.
+//! Also reports the scopes around it so the user can choose one: the nearest package,
+//! the enclosing workspace (Cargo `[workspace]`, package.json `workspaces`,
+//! pnpm-workspace.yaml, go.work) with its members, and the VCS root.
+
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::error::{AppError, AppResult, IoResultExt};
+use crate::walker;
+
+/// One marker file of an ecosystem: an exact file name or `*.ext`. `weight` is what it adds
+/// to the ecosystem's score in a directory; `manifest` markers also make the directory a
+/// package root (lockfiles and build scripts alone do not). Markers that are not `root`
+/// (per-directory build files) score but do not make the directory the project root.
+pub(crate) struct Marker {
+    pub pattern: &'static str,
+    pub weight: u32,
+    pub manifest: bool,
+    pub root: bool,
+}
+
+/// An entry of the ecosystem table. In a directory the ecosystem with the highest score
+/// wins; `priority` breaks ties (deno.json next to package.json is Deno, a Bazel workspace
+/// outranks the per-language files inside it, a bare Makefile ranks last).
+pub(crate) struct Ecosystem {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub priority: u32,
+    pub markers: &'static [Marker],
+}
+
+const fn manifest(pattern: &'static str, weight: u32) -> Marker {
+    Marker {
+        pattern,
+        weight,
+        manifest: true,
+        root: true,
+    }
+}
+
+const fn marker(pattern: &'static str, weight: u32) -> Marker {
+    Marker {
+        pattern,
+        weight,
+        manifest: false,
+        root: true,
+    }
+}
+
+/// A build file every directory of a tree may carry (BUILD.bazel, Makefile, CMakeLists.txt).
+const fn local(m: Marker) -> Marker {
+    Marker { root: false, ..m }
+}
+
+pub(crate) const ECOSYSTEMS: &[Ecosystem] = &[
+    Ecosystem {
+        id: "bazel",
+        label: "Bazel",
+        priority: 90,
+        markers: &[
+            manifest("MODULE.bazel", 10),
+            manifest("WORKSPACE", 10),
+            manifest("WORKSPACE.bazel", 10),
+            local(marker("BUILD.bazel", 4)),
+            marker(".bazelrc", 2),
+        ],
+    },
+    Ecosystem {
+        id: "deno",
+        label: "Deno",
+        priority: 85,
+        markers: &[
+            manifest("deno.json", 10),
+            manifest("deno.jsonc", 10),
+            marker("deno.lock", 3),
+        ],
+    },
+    Ecosystem {
+        id: "rust",
+        label: "Rust (Cargo)",
+        priority: 80,
+        markers: &[manifest("Cargo.toml", 10), marker("Cargo.lock", 3)],
+    },
+    Ecosystem {
+        id: "node",
+        label: "Node.js",
+        priority: 70,
+        markers: &[
+            manifest("package.json", 10),
+            marker("pnpm-lock.yaml", 3),
+            marker("package-lock.json", 3),
+            marker("yarn.lock", 3),
+            marker("bun.lockb", 3),
+            marker("tsconfig.json", 2),
+        ],
+    },
+    Ecosystem {
+        id: "go",
+        label: "Go",
+        priority: 70,
+        markers: &[
+            manifest("go.mod", 10),
+            manifest("go.work", 8),
+            marker("go.sum", 3),
+        ],
+    },
+    Ecosystem {
+        id: "dart",
+        label: "Dart/Flutter",
+        priority: 70,
+        markers: &[manifest("pubspec.yaml", 10), marker("pubspec.lock", 3)],
+    },
+    Ecosystem {
+        id: "gradle",
+        label: "Gradle",
+        priority: 70,
+        markers: &[
+            manifest("build.gradle", 10),
+            manifest("build.gradle.kts", 10),
+            manifest("settings.gradle", 8),
+            manifest("settings.gradle.kts", 8),
+            marker("gradlew", 3),
+        ],
+    },
+    Ecosystem {
+        id: "maven",
+        label: "Maven",
+        priority: 65,
+        markers: &[manifest("pom.xml", 10), marker("mvnw", 3)],
+    },
+    Ecosystem {
+        id: "dotnet",
+        label: ".NET",
+        priority: 70,
+        markers: &[
+            manifest("*.sln", 10),
+            manifest("*.csproj", 10),
+            manifest("*.fsproj", 10),
+            marker("global.json", 3),
+            marker("Directory.Build.props", 3),
+        ],
+    },
+    Ecosystem {
+        id: "elixir",
+        label: "Elixir (Mix)",
+        priority: 70,
+        markers: &[manifest("mix.exs", 10), marker("mix.lock", 3)],
+    },
+    Ecosystem {
+        id: "python",
+        label: "Python",
+        priority: 60,
+        markers: &[
+            manifest("pyproject.toml", 10),
+            manifest("setup.py", 8),
+            marker("setup.cfg", 4),
+            marker("requirements.txt", 6),
+            marker("Pipfile", 6),
+            marker("poetry.lock", 3),
+        ],
+    },
+    Ecosystem {
+        id: "ruby",
+        label: "Ruby",
+        priority: 60,
+        markers: &[
+            manifest("Gemfile", 10),
+            manifest("*.gemspec", 8),
+            marker("Gemfile.lock", 3),
+        ],
+    },
+    Ecosystem {
+        id: "php",
+        label: "PHP (Composer)",
+        priority: 60,
+        markers: &[manifest("composer.json", 10), marker("composer.lock", 3)],
+    },
+    Ecosystem {
+        id: "meson",
+        label: "Meson",
+        priority: 55,
+        markers: &[manifest("meson.build", 10), marker("meson_options.txt", 2)],
+    },
+    Ecosystem {
+        id: "cmake",
+        label: "CMake",
+        priority: 50,
+        markers: &[
+            local(manifest("CMakeLists.txt", 10)),
+            marker("CMakePresets.json", 2),
+        ],
+    },
+    Ecosystem {
+        id: "powershell",
+        label: "PowerShell",
+        priority: 20,
+        markers: &[manifest("*.psd1", 6), marker("*.psm1", 4)],
+    },
+    Ecosystem {
+        id: "firebase",
+        label: "Firebase",
+        priority: 15,
+        markers: &[marker("firebase.json", 4), marker(".firebaserc", 2)],
+    },
+    Ecosystem {
+        id: "make",
+        label: "Make",
+        priority: 10,
+        markers: &[
+            local(marker("Makefile", 4)),
+            local(marker("makefile", 4)),
+            local(marker("GNUmakefile", 4)),
+        ],
+    },
 ];
 
-fn collect_signals(dir: &Path) -> Vec<String> {
-    let mut found = Vec::new();
-    for sig in SIGNALS {
-        let p = dir.join(sig);
-        if p.exists() {
-            if p.is_dir() {
-                found.push(sig.to_string());
-            } else if std::fs::metadata(&p).map(|m| m.is_file()).unwrap_or(false) {
-                found.push(sig.to_string());
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// How deep below a workspace root member globs are matched.
+const MEMBER_MAX_DEPTH: usize = 6;
+const MAX_MEMBERS: usize = 500;
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EcosystemMatch {
+    pub id: String,
+    pub label: String,
+    /// Sum of the weights of the markers found.
+    pub score: u32,
+    pub priority: u32,
+    /// Marker files found, e.g. ["Cargo.toml", "Cargo.lock"].
+    pub markers: Vec<String>,
+}
+
+/// What one directory contains: its signals and the ecosystems they point to, best first.
+struct DirScan {
+    signals: Vec<String>,
+    ecosystems: Vec<EcosystemMatch>,
+    is_package: bool,
+    /// A VCS directory or a root marker was found, not only per-directory build files.
+    is_root: bool,
+}
+
+/// A CMakeLists.txt that declares `project()`, which only the top-level one must.
+fn cmake_project(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("CMakeLists.txt")).is_ok_and(|raw| {
+        raw.lines().any(|l| {
+            let l = l.trim_start().to_ascii_lowercase();
+            l.strip_prefix("project")
+                .is_some_and(|rest| rest.trim_start().starts_with('('))
+        })
+    })
+}
+
+fn marker_matches(pattern: &str, dir: &Path, names: &mut Option<Vec<String>>) -> Vec<String> {
+    match pattern.strip_prefix('*') {
+        Some(suffix) => {
+            // Listed once per directory, only when a `*.ext` marker needs it.
+            let names = names.get_or_insert_with(|| {
+                let mut list: Vec<String> = std::fs::read_dir(dir)
+                    .map(|rd| {
+                        rd.filter_map(|e| e.ok())
+                            .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+                            .map(|e| e.file_name().to_string_lossy().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                list.sort();
+                list
+            });
+            names
+                .iter()
+                .filter(|n| n.len() > suffix.len() && n.ends_with(suffix))
+                .cloned()
+                .collect()
+        }
+        None if dir.join(pattern).is_file() => vec![pattern.to_string()],
+        None => Vec::new(),
+    }
+}
+
+fn scan_dir(dir: &Path) -> DirScan {
+    let mut signals: Vec<String> = VCS_DIRS
+        .iter()
+        .filter(|d| dir.join(d).exists())
+        .map(|d| d.to_string())
+        .collect();
+    let mut ecosystems = Vec::new();
+    let mut is_package = false;
+    let mut is_root = !signals.is_empty();
+    let mut names = None;
+    for eco in ECOSYSTEMS {
+        let mut found = Vec::new();
+        let mut score = 0;
+        for m in eco.markers {
+            let hits = marker_matches(m.pattern, dir, &mut names);
+            if !hits.is_empty() {
+                score += m.weight;
+                is_package |= m.manifest;
+                is_root |= m.root || (m.pattern == "CMakeLists.txt" && cmake_project(dir));
+                found.extend(hits);
             }
         }
+        if !found.is_empty() {
+            for f in &found {
+                if !signals.contains(f) {
+                    signals.push(f.clone());
+                }
+            }
+            ecosystems.push(EcosystemMatch {
+                id: eco.id.to_string(),
+                label: eco.label.to_string(),
+                score,
+                priority: eco.priority,
+                markers: found,
+            });
+        }
+    }
+    ecosystems.sort_by_key(|e| std::cmp::Reverse((e.score, e.priority)));
+    DirScan {
+        signals,
+        ecosystems,
+        is_package,
+        is_root,
     }
-    found
 }
 
-/// Walk upward from start_path until we find a directory with a signal,
-/// or reach filesystem root.
-#[tauri::command]
-pub fn detect_project_root(start_path: String) -> Result<DetectResult, String> {
-    let start = Path::new(&start_path.trim());
-    let mut current = if start.is_dir() {
-        start.canonicalize().map_err(|e| e.to_string())?
-    } else if let Some(parent) = start.parent() {
-        parent.canonicalize().map_err(|e| e.to_string())?
+/// Why the first ecosystem won, e.g.
+/// "Rust (Cargo): Cargo.toml (10) + Cargo.lock (3) scored 13; next was Node.js with 10".
+fn ecosystem_reason(ranked: &[EcosystemMatch]) -> Option<String> {
+    let winner = ranked.first()?;
+    let weights: Vec<String> = winner
+        .markers
+        .iter()
+        .map(|name| {
+            let eco = ECOSYSTEMS.iter().find(|e| e.id == winner.id);
+            let w = eco
+                .and_then(|e| {
+                    e.markers
+                        .iter()
+                        .find(|m| match m.pattern.strip_prefix('*') {
+                            Some(suffix) => name.ends_with(suffix),
+                            None => m.pattern == name,
+                        })
+                })
+                .map_or(0, |m| m.weight);
+            format!("{} ({})", name, w)
+        })
+        .collect();
+    let mut reason = format!(
+        "{}: {} scored {}",
+        winner.label,
+        weights.join(" + "),
+        winner.score
+    );
+    if let Some(next) = ranked.get(1) {
+        if next.score == winner.score {
+            reason.push_str(&format!(
+                "; tied with {} and won on priority {} over {}",
+                next.label, winner.priority, next.priority
+            ));
+        } else {
+            reason.push_str(&format!("; next was {} with {}", next.label, next.score));
+        }
+    }
+    Some(reason)
+}
+
+fn path_str(p: &Path) -> String {
+    p.to_string_lossy().replace('\\', "/")
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceKind {
+    Cargo,
+    Npm,
+    Pnpm,
+    Go,
+}
+
+impl WorkspaceKind {
+    /// Manifest a member directory must contain.
+    fn member_manifest(self) -> &'static str {
+        match self {
+            WorkspaceKind::Cargo => "Cargo.toml",
+            WorkspaceKind::Npm | WorkspaceKind::Pnpm => "package.json",
+            WorkspaceKind::Go => "go.mod",
+        }
+    }
+}
+
+/// A workspace declaration found in one directory.
+struct WorkspaceDecl {
+    kind: WorkspaceKind,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    /// Whether the root itself is a package (Cargo `[package]` next to `[workspace]`).
+    root_is_member: bool,
+}
+
+fn normalize_pattern(p: &str) -> String {
+    let p = p.trim().replace('\\', "/");
+    let p = p.trim_start_matches("./").trim_end_matches('/');
+    if p == "." {
+        String::new()
     } else {
-        return Err("Invalid start path".into());
+        p.to_string()
+    }
+}
+
+fn cargo_workspace(dir: &Path) -> Option<WorkspaceDecl> {
+    let raw = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Table = raw.parse().ok()?;
+    let ws = manifest.get("workspace")?.as_table()?;
+    let list = |key: &str| -> Vec<String> {
+        ws.get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(normalize_pattern)
+                    .collect()
+            })
+            .unwrap_or_default()
     };
+    Some(WorkspaceDecl {
+        kind: WorkspaceKind::Cargo,
+        include: list("members"),
+        exclude: list("exclude"),
+        root_is_member: manifest.contains_key("package"),
+    })
+}
 
-    loop {
-        let signals = collect_signals(&current);
-        if !signals.is_empty() {
-            let root_str = current.to_string_lossy().replace('\\', "/");
-            return Ok(DetectResult {
-                root_path: root_str,
-                signals_found: signals,
-            });
+fn npm_workspace(dir: &Path) -> Option<WorkspaceDecl> {
+    let raw = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let ws = json.get("workspaces")?;
+    // Either ["packages/*"] or { "packages": ["packages/*"] } (yarn).
+    let list = ws.as_array().or_else(|| ws.get("packages")?.as_array())?;
+    let (mut include, mut exclude) = (Vec::new(), Vec::new());
+    for p in list.iter().filter_map(|v| v.as_str()) {
+        match p.strip_prefix('!') {
+            Some(neg) => exclude.push(normalize_pattern(neg)),
+            None => include.push(normalize_pattern(p)),
         }
-        if let Some(parent) = current.parent() {
-            if parent == current {
-                break;
+    }
+    Some(WorkspaceDecl {
+        kind: WorkspaceKind::Npm,
+        include,
+        exclude,
+        root_is_member: false,
+    })
+}
+
+/// pnpm-workspace.yaml: only the `packages:` list is read, one `- pattern` per line.
+fn pnpm_workspace(dir: &Path) -> Option<WorkspaceDecl> {
+    let raw = std::fs::read_to_string(dir.join("pnpm-workspace.yaml")).ok()?;
+    let (mut include, mut exclude) = (Vec::new(), Vec::new());
+    let mut in_packages = false;
+    for line in raw.lines() {
+        let code = line.split(" #").next().unwrap_or("").trim_end();
+        if code.trim().is_empty() || code.trim_start().starts_with('#') {
+            continue;
+        }
+        if !code.starts_with(char::is_whitespace) && !code.starts_with('-') {
+            in_packages = code.trim() == "packages:";
+            continue;
+        }
+        if !in_packages {
+            continue;
+        }
+        if let Some(item) = code.trim().strip_prefix('-') {
+            let item = item.trim().trim_matches(|c| c == '\'' || c == '"');
+            match item.strip_prefix('!') {
+                Some(neg) => exclude.push(normalize_pattern(neg)),
+                None => include.push(normalize_pattern(item)),
+            }
+        }
+    }
+    Some(WorkspaceDecl {
+        kind: WorkspaceKind::Pnpm,
+        include,
+        exclude,
+        root_is_member: false,
+    })
+}
+
+/// go.work: `use ./dir` and `use ( ./a ./b )` directives.
+fn go_workspace(dir: &Path) -> Option<WorkspaceDecl> {
+    let raw = std::fs::read_to_string(dir.join("go.work")).ok()?;
+    let mut include = Vec::new();
+    let mut in_block = false;
+    for line in raw.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                include.push(normalize_pattern(line.trim_matches('"')));
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                include.push(normalize_pattern(rest.trim_matches('"')));
             }
-            let parent_canon = parent.canonicalize().unwrap_or_else(|_| parent.to_path_buf());
-            if parent_canon == current {
+        }
+    }
+    Some(WorkspaceDecl {
+        kind: WorkspaceKind::Go,
+        root_is_member: include.iter().any(|p| p.is_empty()),
+        include: include.into_iter().filter(|p| !p.is_empty()).collect(),
+        exclude: Vec::new(),
+    })
+}
+
+/// pnpm-workspace.yaml takes precedence over package.json `workspaces` in the same directory.
+fn workspace_decl(dir: &Path) -> Option<WorkspaceDecl> {
+    cargo_workspace(dir)
+        .or_else(|| pnpm_workspace(dir))
+        .or_else(|| npm_workspace(dir))
+        .or_else(|| go_workspace(dir))
+}
+
+fn globset(patterns: &[String]) -> GlobSet {
+    let mut b = GlobSetBuilder::new();
+    for p in patterns.iter().filter(|p| !p.is_empty()) {
+        // `*` stays within one path segment, as in Cargo and npm.
+        if let Ok(g) = GlobBuilder::new(p).literal_separator(true).build() {
+            b.add(g);
+        }
+    }
+    b.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+impl WorkspaceDecl {
+    /// Whether `rel` (workspace-relative, forward slashes; "" for the root) is a member.
+    fn includes(&self, rel: &str, include: &GlobSet, exclude: &GlobSet) -> bool {
+        if rel.is_empty() {
+            return self.root_is_member;
+        }
+        include.is_match(rel) && !exclude.is_match(rel)
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    /// Absolute, forward slashes.
+    pub path: String,
+    /// Relative to the workspace root ("" for the root package).
+    pub rel_path: String,
+    /// Package name from the member's manifest, when it has one.
+    pub name: Option<String>,
+}
+
+fn package_name(dir: &Path, kind: WorkspaceKind) -> Option<String> {
+    let raw = std::fs::read_to_string(dir.join(kind.member_manifest())).ok()?;
+    match kind {
+        WorkspaceKind::Cargo => {
+            let manifest: toml::Table = raw.parse().ok()?;
+            Some(manifest.get("package")?.get("name")?.as_str()?.to_string())
+        }
+        WorkspaceKind::Npm | WorkspaceKind::Pnpm => {
+            let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+            Some(json.get("name")?.as_str()?.to_string())
+        }
+        WorkspaceKind::Go => raw
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("module "))
+            .map(|m| m.trim().trim_matches('"').to_string()),
+    }
+}
+
+fn members(root: &Path, decl: &WorkspaceDecl) -> Vec<WorkspaceMember> {
+    let include = globset(&decl.include);
+    let exclude = globset(&decl.exclude);
+    let member = |dir: &Path, rel: String| WorkspaceMember {
+        path: path_str(dir),
+        name: package_name(dir, decl.kind),
+        rel_path: rel,
+    };
+    let mut out = Vec::new();
+    if decl.root_is_member {
+        out.push(member(root, String::new()));
+    }
+    let opts = walker::WalkOptions {
+        max_depth: Some(MEMBER_MAX_DEPTH),
+        ..Default::default()
+    };
+    if let Ok(entries) = walker::walk(root, &opts) {
+        for e in entries.filter(|e| e.is_dir) {
+            if out.len() >= MAX_MEMBERS {
                 break;
             }
-            current = parent_canon;
-        } else {
+            if decl.includes(&e.rel_path, &include, &exclude)
+                && e.abs_path.join(decl.kind.member_manifest()).is_file()
+            {
+                out.push(member(&e.abs_path, e.rel_path));
+            }
+        }
+    }
+    out.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    out
+}
+
+fn is_vcs_root(dir: &Path) -> bool {
+    // .git is a file in worktrees and submodules.
+    VCS_DIRS.iter().any(|d| dir.join(d).exists())
+}
+
+/// `dir` and its ancestors, nearest first, canonicalized where possible.
+fn ancestors(start: PathBuf) -> Vec<PathBuf> {
+    let mut out = vec![start];
+    loop {
+        let current = out.last().expect("non-empty");
+        let Some(parent) = current.parent() else {
+            break;
+        };
+        if parent == current {
+            break;
+        }
+        let parent_canon = parent
+            .canonicalize()
+            .unwrap_or_else(|_| parent.to_path_buf());
+        if &parent_canon == current {
             break;
         }
+        out.push(parent_canon);
     }
+    out
+}
 
-    let root_str = current.to_string_lossy().replace('\\', "/");
-    Ok(DetectResult {
-        root_path: root_str,
+/// Walk upward from start_path until we find a directory with a root signal, or reach
+/// filesystem root. A directory with only per-directory build files is the root when
+/// nothing above it has a root signal. The other scopes are found on the same walk, which stops at
+/// the VCS root so workspaces outside the repository are not picked up.
+#[tauri::command]
+pub fn detect_project_root(start_path: String) -> AppResult<DetectResult> {
+    let start = Path::new(start_path.trim());
+    let start = if start.is_dir() {
+        start.canonicalize().at(start)?
+    } else if let Some(parent) = start.parent() {
+        parent.canonicalize().at(parent)?
+    } else {
+        return Err(AppError::invalid("Invalid start path").with("startPath", &start_path));
+    };
+
+    let dirs = ancestors(start);
+    let mut result = DetectResult {
+        root_path: path_str(dirs.last().expect("non-empty")),
         signals_found: Vec::new(),
-    })
+        package_root: None,
+        workspace_root: None,
+        workspace_kind: None,
+        vcs_root: None,
+        members: Vec::new(),
+        ecosystem: None,
+        ecosystem_reason: None,
+        ecosystems: Vec::new(),
+    };
+    let mut root_found = false;
+    let mut any_signals = false;
+    let mut package_root: Option<&PathBuf> = None;
+    let mut workspace: Option<(&PathBuf, WorkspaceDecl)> = None;
+    for dir in &dirs {
+        let scan = scan_dir(dir);
+        if !root_found && !scan.signals.is_empty() && (scan.is_root || !any_signals) {
+            result.root_path = path_str(dir);
+            result.signals_found = scan.signals;
+            result.ecosystem_reason = ecosystem_reason(&scan.ecosystems);
+            result.ecosystem = scan.ecosystems.first().cloned();
+            result.ecosystems = scan.ecosystems;
+            root_found = scan.is_root;
+            any_signals = true;
+        }
+        if package_root.is_none() && scan.is_package {
+            package_root = Some(dir);
+        }
+        if workspace.is_none() {
+            if let Some(decl) = workspace_decl(dir) {
+                // The workspace must contain the package we started in, not just sit above it.
+                let inner = package_root.unwrap_or(dir);
+                let rel = inner.strip_prefix(dir).map(path_str).unwrap_or_default();
+                let (include, exclude) = (globset(&decl.include), globset(&decl.exclude));
+                if rel.is_empty() || decl.includes(&rel, &include, &exclude) {
+                    workspace = Some((dir, decl));
+                }
+            }
+        }
+        if is_vcs_root(dir) {
+            result.vcs_root = Some(path_str(dir));
+            break;
+        }
+    }
+
+    result.package_root = package_root.map(|p| path_str(p));
+    if let Some((dir, decl)) = workspace {
+        result.workspace_root = Some(path_str(dir));
+        result.workspace_kind = Some(decl.kind);
+        result.members = members(dir, &decl);
+    }
+    Ok(result)
 }
 
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DetectResult {
+    /// Nearest directory with a root signal: VCS, a manifest, a Bazel workspace or a
+    /// CMakeLists.txt with project(). Falls back to the nearest directory with any signal.
     pub root_path: String,
     pub signals_found: Vec<String>,
+    /// Nearest directory with a package manifest.
+    pub package_root: Option<String>,
+    /// Nearest enclosing workspace whose members include the package root.
+    pub workspace_root: Option<String>,
+    pub workspace_kind: Option<WorkspaceKind>,
+    /// Nearest directory holding .git, .hg or .svn.
+    pub vcs_root: Option<String>,
+    /// Packages of the workspace, sorted by relative path.
+    pub members: Vec<WorkspaceMember>,
+    /// Winning ecosystem at root_path (None when only VCS signals were found).
+    pub ecosystem: Option<EcosystemMatch>,
+    /// Human-readable scoring behind `ecosystem`.
+    pub ecosystem_reason: Option<String>,
+    /// Every ecosystem matched at root_path, best first.
+    pub ecosystems: Vec<EcosystemMatch>,
 }
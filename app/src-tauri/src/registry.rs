@@ -0,0 +1,459 @@
+//! Opened workspaces and what workspace commands may do in them. A root gets in only through
+//! the native folder dialog (workspace_open) or a natively confirmed switch to the enclosing
+//! project workspace, so a buggy or compromised frontend cannot point commands at arbitrary
+//! directories. Each root carries a policy: read-only or writable, plus protected globs.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::error::{AppError, AppResult, ErrorKind, IoResultExt};
+use crate::workspace::canonical_root;
+
+/// App data (settings, sessions, logs) stays writable in read-only workspaces.
+const APP_DIR: &str = ".devassistant";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacePolicy {
+    /// Project files cannot be changed; only the app's own .devassistant/ data can.
+    pub read_only: bool,
+    /// Globs never written (settings security.protectedPaths when the workspace was opened).
+    pub protected: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedWorkspace {
+    pub root: String,
+    pub policy: WorkspacePolicy,
+}
+
+struct Entry {
+    policy: WorkspacePolicy,
+    /// Patterns without a slash, matched against the file name.
+    by_name: GlobSet,
+    /// Patterns with a slash, matched against the root-relative path.
+    by_path: GlobSet,
+}
+
+impl Entry {
+    fn new(policy: WorkspacePolicy) -> AppResult<Self> {
+        let mut by_name = GlobSetBuilder::new();
+        let mut by_path = GlobSetBuilder::new();
+        for p in &policy.protected {
+            let glob = GlobBuilder::new(p.trim_start_matches('/'))
+                .literal_separator(true)
+                .case_insensitive(cfg!(windows))
+                .build()
+                .map_err(|e| AppError::invalid(format!("protected path {:?}: {}", p, e)))?;
+            if p.contains('/') {
+                by_path.add(glob);
+            } else {
+                by_name.add(glob);
+            }
+        }
+        let build = |b: GlobSetBuilder| b.build().map_err(|e| AppError::invalid(e.to_string()));
+        Ok(Entry {
+            by_name: build(by_name)?,
+            by_path: build(by_path)?,
+            policy,
+        })
+    }
+
+    fn is_protected(&self, rel: &Path) -> bool {
+        self.by_path.is_match(rel) || rel.file_name().is_some_and(|n| self.by_name.is_match(n))
+    }
+}
+
+/// Roots opened in this app session, keyed by canonical path.
+#[derive(Default)]
+pub struct WorkspaceRegistry {
+    opened: HashMap<PathBuf, Entry>,
+}
+
+fn path_str(p: &Path) -> String {
+    p.to_string_lossy().replace('\\', "/")
+}
+
+fn lock(
+    state: &Mutex<WorkspaceRegistry>,
+) -> AppResult<std::sync::MutexGuard<'_, WorkspaceRegistry>> {
+    state
+        .lock()
+        .map_err(|_| AppError::poisoned("workspace registry"))
+}
+
+impl WorkspaceRegistry {
+    /// Register `root` with protected globs from the user's settings. Only for roots the user
+    /// picked or confirmed natively; never with a path taken from the frontend as is.
+    pub(crate) fn insert(&mut self, root: &Path, read_only: bool) -> AppResult<OpenedWorkspace> {
+        let protected = crate::settings::load(None).security.protected_paths;
+        self.insert_policy(
+            root,
+            WorkspacePolicy {
+                read_only,
+                protected,
+            },
+        )
+    }
+
+    fn insert_policy(
+        &mut self,
+        root: &Path,
+        policy: WorkspacePolicy,
+    ) -> AppResult<OpenedWorkspace> {
+        let root = root.canonicalize().at(root)?;
+        let entry = Entry::new(policy)?;
+        let opened = OpenedWorkspace {
+            root: path_str(&root),
+            policy: entry.policy.clone(),
+        };
+        self.opened.insert(root, entry);
+        Ok(opened)
+    }
+
+    fn entry(&self, workspace_root: &str) -> AppResult<(PathBuf, &Entry)> {
+        let root = canonical_root(workspace_root)?;
+        match self.opened.get(&root) {
+            Some(e) => Ok((root, e)),
+            None => Err(AppError::new(
+                ErrorKind::PermissionDenied,
+                "workspace is not open; open it with the folder dialog first",
+            )
+            .with("workspaceRoot", workspace_root)),
+        }
+    }
+
+    /// Fail unless `full` (from workspace::resolve) may be written: the workspace is writable
+    /// or `full` really is app data (judged after following links, so a linked .devassistant
+    /// does not count), and neither `full` nor what it links to is protected.
+    fn check_write(&self, workspace_root: &str, full: &Path) -> AppResult<()> {
+        let (root, entry) = self.entry(workspace_root)?;
+        let denied = |msg: &str| {
+            AppError::new(ErrorKind::PermissionDenied, msg).with("path", path_str(full))
+        };
+        let rel = full
+            .strip_prefix(&root)
+            .map_err(|_| denied("path is outside the workspace"))?;
+        let target = crate::workspace::canonicalize_partial(full).ok();
+        let target_rel = target.as_deref().and_then(|t| t.strip_prefix(&root).ok());
+        let app_data =
+            rel.starts_with(APP_DIR) && target_rel.is_some_and(|t| t.starts_with(APP_DIR));
+        if entry.policy.read_only && !app_data {
+            return Err(denied("workspace is read-only"));
+        }
+        if entry.is_protected(rel) || target_rel.is_some_and(|t| entry.is_protected(t)) {
+            return Err(denied("path is protected by workspace policy"));
+        }
+        Ok(())
+    }
+}
+
+/// Canonical root of an opened workspace; fails for anything not opened through the dialog.
+pub(crate) fn check_read(
+    state: &Mutex<WorkspaceRegistry>,
+    workspace_root: &str,
+) -> AppResult<PathBuf> {
+    lock(state)?.entry(workspace_root).map(|(root, _)| root)
+}
+
+/// Fail unless the workspace policy allows writing `full` (a path from workspace::resolve).
+pub(crate) fn check_write(
+    state: &Mutex<WorkspaceRegistry>,
+    workspace_root: &str,
+    full: &Path,
+) -> AppResult<()> {
+    lock(state)?.check_write(workspace_root, full)
+}
+
+/// Native OK/Cancel prompt. Blocks, so only call it off the main thread (async commands).
+fn confirm(app: &AppHandle, title: &str, message: String) -> bool {
+    app.dialog()
+        .message(message)
+        .title(title)
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancel)
+        .blocking_show()
+}
+
+/// Let the user pick a folder with the native dialog and register it. None when cancelled.
+#[tauri::command]
+pub async fn workspace_open(
+    app: AppHandle,
+    read_only: Option<bool>,
+    state: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Option<OpenedWorkspace>> {
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .set_title("Open workspace folder")
+        .blocking_pick_folder()
+    else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .map_err(|e| AppError::new(ErrorKind::Unsupported, e.to_string()))?;
+    let opened = lock(&state)?.insert(&path, read_only.unwrap_or(false))?;
+    tracing::info!(target: "registry", root = %opened.root, read_only = opened.policy.read_only, "workspace opened");
+    Ok(Some(opened))
+}
+
+/// When the opened `workspace_root` belongs to a larger project workspace (Cargo, npm, ...),
+/// ask natively whether to open that root instead and register it with the same policy.
+/// None when there is no enclosing workspace or the user declines.
+#[tauri::command]
+pub async fn workspace_open_enclosing(
+    app: AppHandle,
+    workspace_root: String,
+    state: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Option<OpenedWorkspace>> {
+    let (root, read_only) = {
+        let reg = lock(&state)?;
+        let (root, entry) = reg.entry(&workspace_root)?;
+        (root, entry.policy.read_only)
+    };
+    let detected = crate::project_root::detect_project_root(path_str(&root))?;
+    let Some(wider) = detected.workspace_root.as_deref().map(PathBuf::from) else {
+        return Ok(None);
+    };
+    let wider = wider.canonicalize().at(&wider)?;
+    if wider == root {
+        return Ok(None);
+    }
+    let kind = detected
+        .workspace_kind
+        .and_then(|k| serde_json::to_value(k).ok())
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| "project".into());
+    let message = format!(
+        "This folder is part of a {} workspace at {} ({} packages). Open the workspace root instead?",
+        kind,
+        path_str(&wider),
+        detected.members.len()
+    );
+    if !confirm(&app, "Open enclosing workspace", message) {
+        return Ok(None);
+    }
+    let opened = lock(&state)?.insert(&wider, read_only)?;
+    tracing::info!(target: "registry", root = %opened.root, from = %path_str(&root), "enclosing workspace opened");
+    Ok(Some(opened))
+}
+
+/// Switch a workspace between read-only and writable. Making it writable needs native
+/// confirmation; making it read-only does not.
+#[tauri::command]
+pub async fn workspace_set_read_only(
+    app: AppHandle,
+    workspace_root: String,
+    read_only: bool,
+    state: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<OpenedWorkspace> {
+    let (root, was_read_only) = {
+        let reg = lock(&state)?;
+        let (root, entry) = reg.entry(&workspace_root)?;
+        (root, entry.policy.read_only)
+    };
+    if was_read_only && !read_only {
+        let message = format!("Allow DevAssistant to change files in {}?", path_str(&root));
+        if !confirm(&app, "Allow writes", message) {
+            return Err(
+                AppError::new(ErrorKind::PermissionDenied, "writes were not allowed")
+                    .with("workspaceRoot", path_str(&root)),
+            );
+        }
+    }
+    let mut reg = lock(&state)?;
+    let entry = reg.opened.get_mut(&root).ok_or_else(|| {
+        AppError::new(ErrorKind::PermissionDenied, "workspace was closed")
+            .with("workspaceRoot", path_str(&root))
+    })?;
+    entry.policy.read_only = read_only;
+    tracing::info!(target: "registry", root = %path_str(&root), read_only, "workspace policy changed");
+    Ok(OpenedWorkspace {
+        root: path_str(&root),
+        policy: entry.policy.clone(),
+    })
+}
+
+/// The opened workspace and its policy.
+#[tauri::command]
+pub fn workspace_policy(
+    workspace_root: String,
+    state: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<OpenedWorkspace> {
+    let reg = lock(&state)?;
+    let (root, entry) = reg.entry(&workspace_root)?;
+    Ok(OpenedWorkspace {
+        root: path_str(&root),
+        policy: entry.policy.clone(),
+    })
+}
+
+/// Forget an opened workspace; its commands fail until it is opened again.
+#[tauri::command]
+pub fn workspace_close(
+    workspace_root: String,
+    state: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<bool> {
+    let root = canonical_root(&workspace_root)?;
+    Ok(lock(&state)?.opened.remove(&root).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh workspace root registered with `policy` (so no user settings are read).
+    struct Fixture {
+        base: PathBuf,
+        root: PathBuf,
+        reg: Mutex<WorkspaceRegistry>,
+    }
+
+    impl Fixture {
+        fn new(name: &str, read_only: bool, protected: &[&str]) -> Self {
+            let base =
+                std::env::temp_dir().join(format!("registry-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&base);
+            std::fs::create_dir_all(base.join("ws/src")).unwrap();
+            std::fs::create_dir_all(base.join("outside")).unwrap();
+            let root = base.join("ws").canonicalize().unwrap();
+            let mut reg = WorkspaceRegistry::default();
+            let policy = WorkspacePolicy {
+                read_only,
+                protected: protected.iter().map(|p| p.to_string()).collect(),
+            };
+            reg.insert_policy(&root, policy).unwrap();
+            Fixture {
+                base,
+                root,
+                reg: Mutex::new(reg),
+            }
+        }
+
+        fn write(&self, rel: &str) -> AppResult<()> {
+            check_write(&self.reg, &path_str(&self.root), &self.root.join(rel))
+        }
+
+        fn denied(&self, rel: &str) -> String {
+            let e = self
+                .write(rel)
+                .map(|_| panic!("{:?} was writable", rel))
+                .unwrap_err();
+            assert_eq!(e.kind, ErrorKind::PermissionDenied, "{}", rel);
+            e.message
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.base);
+        }
+    }
+
+    #[test]
+    fn unregistered_roots_are_refused() {
+        let f = Fixture::new("unregistered", false, &[]);
+        let other = f.base.join("outside");
+        let e = check_read(&f.reg, &path_str(&other)).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::PermissionDenied);
+        let e = check_write(&f.reg, &path_str(&other), &other.join("a.txt")).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::PermissionDenied);
+        assert_eq!(check_read(&f.reg, &path_str(&f.root)).unwrap(), f.root);
+    }
+
+    #[test]
+    fn paths_outside_the_root_are_refused() {
+        let f = Fixture::new("outside", false, &[]);
+        let e = check_write(&f.reg, &path_str(&f.root), &f.base.join("outside/a.txt")).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn read_only_allows_only_app_data() {
+        let f = Fixture::new("read-only", true, &[]);
+        assert!(f.denied("src/main.rs").contains("read-only"));
+        assert!(f.denied(".devassistantx/a.json").contains("read-only"));
+        f.write(".devassistant/settings.json").unwrap();
+        f.write(".devassistant/logs/app.log").unwrap();
+    }
+
+    #[test]
+    fn writable_workspace_allows_project_files() {
+        let f = Fixture::new("writable", false, &[]);
+        f.write("src/main.rs").unwrap();
+        f.write("new/dir/file.txt").unwrap();
+    }
+
+    #[test]
+    fn name_globs_match_anywhere_and_path_globs_from_the_root() {
+        let f = Fixture::new(
+            "globs",
+            false,
+            &[".env", "*.pem", ".git/**", "/secrets/*.json"],
+        );
+        for rel in [
+            ".env",
+            "src/.env",
+            "certs/key.pem",
+            ".git/config",
+            ".git/refs/heads/main",
+            "secrets/a.json",
+        ] {
+            assert!(f.denied(rel).contains("protected"), "{}", rel);
+        }
+        for rel in [
+            "src/.git/config",
+            "src/secrets/a.json",
+            "secrets/nested/a.json",
+            ".envrc",
+            "env",
+        ] {
+            f.write(rel)
+                .unwrap_or_else(|e| panic!("{}: {}", rel, e.message));
+        }
+    }
+
+    #[cfg(unix)]
+    mod symlinks {
+        use super::*;
+        use std::os::unix::fs::symlink;
+
+        #[test]
+        fn linked_app_dir_is_not_app_data() {
+            let f = Fixture::new("link-app-dir", true, &[]);
+            std::fs::create_dir_all(f.root.join("src/data")).unwrap();
+            symlink(f.root.join("src/data"), f.root.join(".devassistant")).unwrap();
+            assert!(f
+                .denied(".devassistant/settings.json")
+                .contains("read-only"));
+        }
+
+        #[test]
+        fn app_dir_linked_outside_is_not_app_data() {
+            let f = Fixture::new("link-app-out", true, &[]);
+            symlink(f.base.join("outside"), f.root.join(".devassistant")).unwrap();
+            assert!(f
+                .denied(".devassistant/settings.json")
+                .contains("read-only"));
+        }
+
+        #[test]
+        fn link_to_protected_target_is_protected() {
+            let f = Fixture::new("link-protected", false, &[".env", ".git/**"]);
+            std::fs::create_dir_all(f.root.join(".git")).unwrap();
+            std::fs::write(f.root.join(".env"), "KEY=1").unwrap();
+            symlink(f.root.join(".env"), f.root.join("src/config.txt")).unwrap();
+            symlink(f.root.join(".git"), f.root.join("meta")).unwrap();
+            assert!(f.denied("src/config.txt").contains("protected"));
+            assert!(f.denied("meta/config").contains("protected"));
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Repository map for prompt context: a ranked outline of files with their key signatures,
+//! trimmed to a token budget. Files are ranked by a cross-file reference graph (PageRank,
+//! biased toward focus paths and recent edits), falling back to recency when no file
+//! references another.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppResult;
+use crate::snapshot;
+use crate::symbols::{self, Symbol, SymbolIndexState, SymbolKind};
+use crate::tokens::estimate_tokens;
+
+const DEFAULT_TOKEN_BUDGET: usize = 1024;
+const MIN_TOKEN_BUDGET: usize = 64;
+const MAX_TOKEN_BUDGET: usize = 32 * 1024;
+/// Files larger than this are not scanned for references.
+const REF_SCAN_MAX_BYTES: u64 = 256 * 1024;
+/// Names this short, or defined in more files than this, are too ambiguous to count as references.
+const MIN_REF_NAME_LEN: usize = 3;
+const MAX_DEFINING_FILES: usize = 5;
+const PAGERANK_ITERATIONS: usize = 20;
+const DAMPING: f64 = 0.85;
+/// Personalization weight of a focus file relative to any other file.
+const FOCUS_WEIGHT: f64 = 50.0;
+/// Up to +50% score for the most recently modified file.
+const RECENCY_WEIGHT: f64 = 0.5;
+/// Symbols listed per file before the rest are summarized as "…".
+const MAX_SYMBOLS_PER_FILE: usize = 30;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoMapFile {
+    pub path: String,
+    pub score: f64,
+    /// Symbols written for this file (0 when only the path fit).
+    pub symbols: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoMapResult {
+    /// The outline, ready to paste into a prompt.
+    pub map: String,
+    /// Heuristic estimate; use runtime_count_tokens for the loaded model's exact count.
+    pub estimated_tokens: usize,
+    pub token_budget: usize,
+    /// Included files, best first.
+    pub files: Vec<RepoMapFile>,
+    /// Source files with a symbol outline in the workspace.
+    pub total_files: usize,
+    /// "references" or "recency".
+    pub ranking: String,
+    /// Some files did not fit in the budget.
+    pub truncated: bool,
+}
+
+fn identifiers(src: &str) -> HashSet<&str> {
+    src.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| w.len() >= MIN_REF_NAME_LEN && !w.starts_with(|c: char| c.is_ascii_digit()))
+        .collect()
+}
+
+/// Worth showing in the outline: public API, types and their members.
+fn is_key(s: &Symbol) -> bool {
+    s.exported || (s.kind != SymbolKind::Function && s.kind != SymbolKind::Constant)
+}
+
+fn is_focused(path: &str, focus: &[String]) -> bool {
+    focus.iter().any(|f| {
+        path == f || path.strip_prefix(f.as_str()).is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Weighted edges referencing file -> defining file, from identifier occurrences.
+fn reference_graph(
+    root: &Path,
+    outlines: &[(String, Vec<Symbol>)],
+    sizes: &HashMap<&str, u64>,
+) -> Vec<HashMap<usize, f64>> {
+    let mut defined_in: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, (_, syms)) in outlines.iter().enumerate() {
+        for s in syms.iter().filter(|s| s.exported && s.name.len() >= MIN_REF_NAME_LEN) {
+            let files = defined_in.entry(s.name.as_str()).or_default();
+            if files.last() != Some(&i) {
+                files.push(i);
+            }
+        }
+    }
+    defined_in.retain(|_, files| files.len() <= MAX_DEFINING_FILES);
+
+    let mut edges: Vec<HashMap<usize, f64>> = vec![HashMap::new(); outlines.len()];
+    for (i, (path, _)) in outlines.iter().enumerate() {
+        if sizes.get(path.as_str()).is_some_and(|&n| n > REF_SCAN_MAX_BYTES) {
+            continue;
+        }
+        let Ok(src) = std::fs::read_to_string(root.join(path)) else {
+            continue;
+        };
+        for ident in identifiers(&src) {
+            let Some(targets) = defined_in.get(ident) else {
+                continue;
+            };
+            let w = 1.0 / targets.len() as f64;
+            for &t in targets.iter().filter(|&&t| t != i) {
+                *edges[i].entry(t).or_insert(0.0) += w;
+            }
+        }
+    }
+    edges
+}
+
+/// Personalized PageRank; dangling mass goes back to the personalization vector.
+fn pagerank(edges: &[HashMap<usize, f64>], personal: &[f64]) -> Vec<f64> {
+    let n = edges.len();
+    let total: f64 = personal.iter().sum();
+    let p: Vec<f64> = personal.iter().map(|w| w / total).collect();
+    let out_weight: Vec<f64> = edges.iter().map(|e| e.values().sum()).collect();
+    let mut rank = p.clone();
+    for _ in 0..PAGERANK_ITERATIONS {
+        let mut next = vec![0.0; n];
+        let mut dangling = 0.0;
+        for (i, e) in edges.iter().enumerate() {
+            if out_weight[i] == 0.0 {
+                dangling += rank[i];
+                continue;
+            }
+            for (&t, &w) in e {
+                next[t] += DAMPING * rank[i] * w / out_weight[i];
+            }
+        }
+        for (i, v) in next.iter_mut().enumerate() {
+            *v += ((1.0 - DAMPING) + DAMPING * dangling) * p[i];
+        }
+        rank = next;
+    }
+    rank
+}
+
+fn render_file(path: &str, syms: &[Symbol]) -> (String, usize) {
+    let mut out = format!("{}:\n", path);
+    let key: Vec<&Symbol> = syms.iter().filter(|s| is_key(s)).collect();
+    for s in key.iter().take(MAX_SYMBOLS_PER_FILE) {
+        let indent = if s.container.is_some() { "    " } else { "  " };
+        out.push_str(indent);
+        out.push_str(&s.signature);
+        out.push('\n');
+    }
+    if key.len() > MAX_SYMBOLS_PER_FILE {
+        out.push_str("  …\n");
+    }
+    (out, key.len().min(MAX_SYMBOLS_PER_FILE))
+}
+
+fn build(
+    root: &Path,
+    token_budget: usize,
+    focus: &[String],
+    state: &Mutex<SymbolIndexState>,
+) -> Result<RepoMapResult, String> {
+    let walked = snapshot::walk_parallel(root)?;
+    let modified: HashMap<&str, i64> = walked
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.modified_ms))
+        .collect();
+    let sizes: HashMap<&str, u64> = walked
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.size_bytes))
+        .collect();
+    let outlines: Vec<(String, Vec<Symbol>)> = symbols::with_index(state, root, |files| {
+        files
+            .iter()
+            .filter(|(path, f)| !f.symbols.is_empty() && modified.contains_key(path.as_str()))
+            .map(|(path, f)| (path.clone(), f.symbols.clone()))
+            .collect()
+    })?;
+    let n = outlines.len();
+
+    // Recency in [0, 1]: 1 for the newest file.
+    let mut by_time: Vec<usize> = (0..n).collect();
+    by_time.sort_by_key(|&i| modified.get(outlines[i].0.as_str()).copied().unwrap_or(0));
+    let mut recency = vec![0.0; n];
+    for (pos, &i) in by_time.iter().enumerate() {
+        recency[i] = if n > 1 { pos as f64 / (n - 1) as f64 } else { 1.0 };
+    }
+
+    let edges = reference_graph(root, &outlines, &sizes);
+    let has_refs = edges.iter().any(|e| !e.is_empty());
+    let personal: Vec<f64> = outlines
+        .iter()
+        .map(|(path, _)| if is_focused(path, focus) { FOCUS_WEIGHT } else { 1.0 })
+        .collect();
+    let base = if has_refs {
+        pagerank(&edges, &personal)
+    } else {
+        personal.clone()
+    };
+    let mut order: Vec<(usize, f64)> = base
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (i, r * (1.0 + RECENCY_WEIGHT * recency[i])))
+        .collect();
+    order.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(outlines[a.0].0.cmp(&outlines[b.0].0))
+    });
+
+    let mut map = String::new();
+    let mut used = 0;
+    let mut files = Vec::new();
+    let mut truncated = false;
+    for (i, score) in order {
+        let (path, syms) = &outlines[i];
+        let (block, count) = render_file(path, syms);
+        let cost = estimate_tokens(&block);
+        let (block, count, cost) = if used + cost <= token_budget {
+            (block, count, cost)
+        } else {
+            // Fall back to listing just the path.
+            truncated = true;
+            let line = format!("{}\n", path);
+            let cost = estimate_tokens(&line);
+            if used + cost > token_budget {
+                break;
+            }
+            (line, 0, cost)
+        };
+        map.push_str(&block);
+        used += cost;
+        files.push(RepoMapFile {
+            path: path.clone(),
+            score,
+            symbols: count,
+        });
+    }
+    truncated |= files.len() < n;
+
+    Ok(RepoMapResult {
+        map,
+        estimated_tokens: used,
+        token_budget,
+        files,
+        total_files: n,
+        ranking: if has_refs { "references" } else { "recency" }.to_string(),
+        truncated,
+    })
+}
+
+/// Ranked outline of the workspace (paths plus key signatures) within token_budget
+/// (default 1024). focus_paths (files or directories, root-relative) and the files they
+/// reference rank first.
+#[tauri::command]
+pub async fn workspace_repo_map(
+    app: AppHandle,
+    workspace_root: String,
+    token_budget: Option<usize>,
+    focus_paths: Option<Vec<String>>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<RepoMapResult> {
+    let root = crate::registry::check_read(&registry, &workspace_root)?;
+    let budget = token_budget
+        .unwrap_or(DEFAULT_TOKEN_BUDGET)
+        .clamp(MIN_TOKEN_BUDGET, MAX_TOKEN_BUDGET);
+    let focus: Vec<String> = focus_paths
+        .unwrap_or_default()
+        .iter()
+        .map(|p| p.trim().replace('\\', "/").trim_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    crate::error::blocking(move || {
+        let state = app.state::<Mutex<SymbolIndexState>>();
+        build(&root, budget, &focus, state.inner())
+    })
+    .await
+}
@@ -1,17 +1,25 @@
 //! Local llama-server runtime: start/stop/status and generate via HTTP /completion.
 
 use std::net::TcpListener;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{AppError, AppResult, ErrorKind, IoResultExt};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::settings::RuntimeSettings;
+use crate::toolroot;
+
 #[derive(Default)]
 pub struct RuntimeState {
+    /// Port of the server in use: our child's, or one autostart attached to (no child).
     pub port: Option<u16>,
     pub child: Option<Child>,
+    /// --ctx-size passed at start; None when the server default is used.
+    pub ctx_size: Option<u32>,
 }
 
 #[derive(Default, Clone, Serialize, Deserialize)]
@@ -47,8 +55,6 @@ pub struct GenerateOptions {
     pub max_tokens: i32,
 }
 
-const DEFAULT_PORT: u16 = 11435;
-
 fn find_free_port() -> Option<u16> {
     for port in 8080u16..8100u16 {
         if TcpListener::bind(("127.0.0.1", port)).is_ok() {
@@ -67,21 +73,55 @@ fn llama_exe_rel() -> &'static str {
     "runtime/llama/llama-server"
 }
 
-fn resolve_llama_from_tool_root(tool_root: &str) -> Result<PathBuf, String> {
+fn resolve_llama_from_tool_root(tool_root: &str) -> AppResult<PathBuf> {
     let root = PathBuf::from(tool_root.trim().replace('\\', "/"));
     let exe = root.join(llama_exe_rel());
     if exe.is_file() {
-        return Ok(exe.canonicalize().map_err(|e| e.to_string())?);
+        return exe.canonicalize().at(&exe);
     }
-    Err(format!(
-        "Could not find {}. Expected under toolRoot/runtime/llama.",
-        llama_exe_rel()
-    ))
+    Err(AppError::new(
+        ErrorKind::RuntimeMissing,
+        format!("Could not find {}. Expected under toolRoot/runtime/llama.", llama_exe_rel()),
+    )
+    .with("path", path_str(&exe)))
+}
+
+fn lock(state: &Mutex<RuntimeState>) -> AppResult<std::sync::MutexGuard<'_, RuntimeState>> {
+    state.lock().map_err(|_| AppError::poisoned("runtime"))
+}
+
+fn not_started() -> AppError {
+    AppError::new(
+        ErrorKind::RuntimeNotStarted,
+        "Runtime not started. Start the runtime with a GGUF model first.",
+    )
+}
+
+/// Request to llama-server that got no response.
+fn request_error(e: reqwest::Error, url: &str) -> AppError {
+    let kind = if e.is_timeout() {
+        ErrorKind::Timeout
+    } else {
+        ErrorKind::ServerUnreachable
+    };
+    AppError::new(kind, format!("Request failed: {}", e)).with("endpoint", url)
+}
+
+/// Non-success response from llama-server; the body goes into details.
+async fn http_error(resp: reqwest::Response, url: &str) -> AppError {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    AppError::new(
+        ErrorKind::ServerHttp { status: status.as_u16() },
+        format!("llama-server error {}", status),
+    )
+    .with("endpoint", url)
+    .with("body", text)
 }
 
 /// Health check: GET http://127.0.0.1:port/health, return true if 200.
 #[tauri::command]
-pub async fn runtime_health_check(port: u16) -> Result<bool, String> {
+pub async fn runtime_health_check(port: u16) -> AppResult<bool> {
     let url = format!("http://127.0.0.1:{}/health", port);
     match reqwest::get(&url).await {
         Ok(resp) => Ok(resp.status().as_u16() == 200),
@@ -95,23 +135,36 @@ pub async fn runtime_start(
     tool_root: Option<String>,
     params: Option<RuntimeStartParams>,
     port_override: Option<u16>,
-    log_file_path: Option<String>,
+    workspace_root: Option<String>,
     state: tauri::State<'_, Mutex<RuntimeState>>,
-) -> Result<RuntimeStartResult, String> {
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<RuntimeStartResult> {
+    // Only an opened workspace may receive the server's log (under its .devassistant/logs).
+    let log_root = match workspace_root.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        Some(r) => {
+            let root = registry::check_read(&registry, r)?;
+            registry::check_write(&registry, r, &root.join(crate::logging::LOGS_REL))?;
+            Some(root)
+        }
+        None => None,
+    };
     let gguf_path = gguf_path.trim();
     if gguf_path.is_empty() {
-        return Err("GGUF model path is required.".to_string());
+        return Err(AppError::invalid("GGUF model path is required."));
     }
     let path_buf = PathBuf::from(gguf_path);
     if !path_buf.is_file() {
-        return Err(format!("Model file not found: {}", gguf_path));
+        return Err(
+            AppError::new(ErrorKind::ModelMissing, format!("Model file not found: {}", gguf_path))
+                .with("path", gguf_path),
+        );
     }
 
     let (server_path, port) = if let Some(tr) = &tool_root {
         let server_path = resolve_llama_from_tool_root(tr)?;
-        let port = port_override.unwrap_or(DEFAULT_PORT);
+        let port = port_override.unwrap_or_else(|| crate::settings::load(None).runtime.port);
         {
-            let mut s = state.lock().map_err(|e| e.to_string())?;
+            let mut s = lock(&state)?;
             if s.port == Some(port) {
                 if let Some(child) = s.child.as_mut() {
                     if child.try_wait().ok().flatten().is_none() {
@@ -128,63 +181,107 @@ pub async fn runtime_start(
         let exe_name = "llama-server.exe";
         #[cfg(not(windows))]
         let exe_name = "llama-server";
-        let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+        let cwd = std::env::current_dir().map_err(|e| AppError::io(Path::new("."), e))?;
         let candidate = cwd.join("runtime").join("llama").join(exe_name);
         if !candidate.is_file() {
-            return Err(format!(
-                "Could not find runtime/llama/{}. Expected under toolRoot. Use find_tool_root.",
-                exe_name
-            ));
+            return Err(AppError::new(
+                ErrorKind::RuntimeMissing,
+                format!(
+                    "Could not find runtime/llama/{}. Expected under toolRoot. Use find_tool_root.",
+                    exe_name
+                ),
+            )
+            .with("path", path_str(&candidate)));
         }
-        let server_path = candidate.canonicalize().map_err(|e| e.to_string())?;
+        let server_path = candidate.canonicalize().at(&candidate)?;
         let port = port_override
             .or_else(find_free_port)
-            .ok_or("No free port in 8080..8099.")?;
+            .ok_or_else(|| AppError::new(ErrorKind::Conflict, "No free port in 8080..8099."))?;
         (server_path, port)
     };
 
+    // Output goes to the app log, in workspace_root's log file when one was given.
+    if let Some(root) = log_root.as_deref() {
+        if let Err(e) = crate::logging::attach(root) {
+            tracing::warn!(target: "app", error = %e, "could not attach log");
+        }
+    }
+    let p = params.unwrap_or_default();
+    let port = launch(
+        &state,
+        Launch {
+            server_path: &server_path,
+            model: gguf_path,
+            host: "127.0.0.1",
+            port,
+            ctx_size: (p.context_length > 0).then_some(p.context_length as u32),
+            gpu_layers: None,
+            threads: None,
+        },
+    )
+    .await?;
+    Ok(RuntimeStartResult { port })
+}
+
+/// Arguments for one llama-server launch.
+struct Launch<'a> {
+    server_path: &'a Path,
+    model: &'a str,
+    host: &'a str,
+    port: u16,
+    ctx_size: Option<u32>,
+    gpu_layers: Option<i32>,
+    threads: Option<u32>,
+}
+
+/// Spawn llama-server (replacing any child we own) and wait up to 20s for /health.
+/// Its stdout/stderr go to the app log under target `llama_server`.
+async fn launch(state: &Mutex<RuntimeState>, l: Launch<'_>) -> AppResult<u16> {
+    let port = l.port;
     let mut args = vec![
         "--model".to_string(),
-        gguf_path.to_string(),
+        l.model.to_string(),
         "--host".to_string(),
-        "127.0.0.1".to_string(),
+        l.host.to_string(),
         "--port".to_string(),
         port.to_string(),
     ];
-    let p = params.unwrap_or_default();
-    if p.context_length > 0 {
+    if let Some(ctx) = l.ctx_size {
         args.push("--ctx-size".to_string());
-        args.push(p.context_length.to_string());
+        args.push(ctx.to_string());
     }
-
-    let (stdout, stderr) = if let Some(ref log_path) = log_file_path {
-        let p = PathBuf::from(log_path);
-        if let Some(parent) = p.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        let mut opts = std::fs::OpenOptions::new();
-        opts.create(true).append(true).write(true);
-        let f1 = opts.open(&p).map_err(|e| format!("Failed to open log file: {}", e))?;
-        let f2 = opts.open(&p).map_err(|e| format!("Failed to open log file: {}", e))?;
-        (Stdio::from(f1), Stdio::from(f2))
-    } else {
-        (Stdio::null(), Stdio::null())
-    };
-
-    let child = Command::new(&server_path)
+    if let Some(n) = l.gpu_layers {
+        args.push("--n-gpu-layers".to_string());
+        args.push(n.to_string());
+    }
+    if let Some(n) = l.threads {
+        args.push("--threads".to_string());
+        args.push(n.to_string());
+    }
+    let mut child = Command::new(l.server_path)
         .args(&args)
-        .stdout(stdout)
-        .stderr(stderr)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to start llama-server: {}", e))?;
+        .map_err(|e| {
+            tracing::error!(target: "runtime", server = %l.server_path.display(), error = %e, "spawn failed");
+            let mut err = AppError::io(l.server_path, e);
+            err.message = format!("Failed to start llama-server: {}", err.message);
+            err
+        })?;
+    let instance = format!("llama-server:{}:{}", port, child.id());
+    tracing::info!(target: "runtime", instance = %instance, model = l.model, args = ?args, "llama-server spawned");
+    crate::logging::pipe_server_output(child.stdout.take(), instance.clone(), "stdout");
+    crate::logging::pipe_server_output(child.stderr.take(), instance.clone(), "stderr");
 
     {
-        let mut s = state.lock().map_err(|e| e.to_string())?;
+        let mut s = lock(state)?;
         if let Some(ref mut old) = s.child {
             let _ = old.kill();
         }
         s.port = Some(port);
         s.child = Some(child);
+        s.ctx_size = l.ctx_size;
     }
 
     for _ in 0..40 {
@@ -192,54 +289,375 @@ pub async fn runtime_start(
         let url = format!("http://127.0.0.1:{}/health", port);
         if let Ok(resp) = reqwest::get(&url).await {
             if resp.status().as_u16() == 200 {
-                return Ok(RuntimeStartResult { port });
+                tracing::info!(target: "runtime", instance = %instance, "llama-server ready");
+                return Ok(port);
             }
         }
     }
 
-    let mut s = state.lock().map_err(|e| e.to_string())?;
+    let mut s = lock(state)?;
     if let Some(mut child) = s.child.take() {
         let _ = child.kill();
     }
     s.port = None;
-    Err("llama-server did not become ready within 20 seconds.".to_string())
+    tracing::error!(target: "runtime", instance = %instance, "llama-server not ready after 20s; killed");
+    Err(AppError::new(ErrorKind::Timeout, "llama-server did not become ready within 20 seconds.")
+        .with("port", port))
 }
 
-#[tauri::command]
-pub async fn runtime_status(
-    state: tauri::State<'_, Mutex<RuntimeState>>,
-) -> Result<RuntimeStatusResult, String> {
-    let mut s = state.lock().map_err(|e| e.to_string())?;
-    let running = if let Some(child) = s.child.as_mut() {
-        match child.try_wait() {
-            Ok(Some(_)) => {
+/// Port of the server in use: our child while it lives, or an attached server while its
+/// health check passes. Clears the state when either is gone.
+pub(crate) async fn live_port(state: &Mutex<RuntimeState>) -> AppResult<Option<u16>> {
+    let attached = {
+        let mut s = lock(state)?;
+        let Some(port) = s.port else {
+            return Ok(None);
+        };
+        match s.child.as_mut().map(|c| c.try_wait()) {
+            Some(Ok(None)) => return Ok(Some(port)),
+            Some(_) => {
                 s.child = None;
                 s.port = None;
-                false
+                return Ok(None);
             }
-            Ok(None) => true,
-            Err(_) => false,
+            None => port,
         }
-    } else {
-        false
     };
-    let port = if running { s.port } else { None };
-    Ok(RuntimeStatusResult { running, port })
+    if runtime_health_check(attached).await.unwrap_or(false) {
+        return Ok(Some(attached));
+    }
+    let mut s = lock(state)?;
+    if s.child.is_none() && s.port == Some(attached) {
+        s.port = None;
+    }
+    Ok(None)
+}
+
+#[tauri::command]
+pub async fn runtime_status(
+    state: tauri::State<'_, Mutex<RuntimeState>>,
+) -> AppResult<RuntimeStatusResult> {
+    let port = live_port(&state).await?;
+    Ok(RuntimeStatusResult {
+        running: port.is_some(),
+        port,
+    })
 }
 
 #[tauri::command]
 pub async fn runtime_stop(
     state: tauri::State<'_, Mutex<RuntimeState>>,
-) -> Result<(), String> {
-    let mut s = state.lock().map_err(|e| e.to_string())?;
+) -> AppResult<()> {
+    let mut s = lock(&state)?;
     if let Some(mut child) = s.child.take() {
         let _ = child.kill();
         let _ = child.wait();
+        tracing::info!(target: "runtime", port = ?s.port, "llama-server stopped");
     }
     s.port = None;
     Ok(())
 }
 
+const CONFIG_REL: &str = ".devassistant/runtime_config.json";
+
+/// .devassistant/runtime_config.json: what the last autostart resolved and ran.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfig {
+    pub llama_server_path: String,
+    pub model_path: String,
+    pub host: String,
+    pub port: u16,
+    pub ctx: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threads: Option<u32>,
+    #[serde(default)]
+    pub gpu_layers: i32,
+    pub created_at: String,
+}
+
+/// Matches StartLocalModelStatus in runtimeConfig.ts.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutostartStatus {
+    Started,
+    AlreadyRunning,
+    MissingModel,
+    MissingRuntime,
+    Error,
+}
+
+impl AutostartStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            AutostartStatus::Started => "started",
+            AutostartStatus::AlreadyRunning => "already_running",
+            AutostartStatus::MissingModel => "missing_model",
+            AutostartStatus::MissingRuntime => "missing_runtime",
+            AutostartStatus::Error => "error",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeAutostartResult {
+    pub status: AutostartStatus,
+    pub details: Option<String>,
+    /// Port the server is (or would be) on.
+    pub port: u16,
+    /// The config written back to runtime_config.json.
+    pub config: RuntimeConfig,
+}
+
+/// Where the runtime and model would be taken from, without starting anything.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeDiscovery {
+    pub workspace_root: String,
+    /// First ancestor with runtime/llama + models/ (see find_tool_root).
+    pub tool_root: Option<String>,
+    /// llama-server for this platform, if present.
+    pub server_path: Option<String>,
+    /// settings runtime.modelPath when it exists, else the newest models/*.gguf.
+    pub model_path: Option<String>,
+    pub had_multiple_models: bool,
+    pub config_path: String,
+    pub log_file_path: String,
+}
+
+fn path_str(p: &Path) -> String {
+    p.to_string_lossy().replace('\\', "/")
+}
+
+fn discover(root: &Path, rt: &RuntimeSettings) -> AppResult<RuntimeDiscovery> {
+    let root_str = path_str(root);
+    let tool_root = toolroot::find_tool_root(root_str.clone())?;
+    let base = tool_root.as_deref().map(PathBuf::from).unwrap_or_else(|| root.to_path_buf());
+    let server = base.join(llama_exe_rel());
+
+    // runtime.modelPath is toolRoot-relative unless absolute.
+    let configured = rt
+        .model_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(|m| base.join(m))
+        .filter(|m| m.is_file());
+    let (model_path, had_multiple_models) = match configured {
+        Some(m) => (Some(m), false),
+        None => match toolroot::scan_models_for_gguf_by_mtime(path_str(&base))? {
+            Some(scan) => (Some(base.join(scan.path)), scan.had_multiple),
+            None => (None, false),
+        },
+    };
+
+    Ok(RuntimeDiscovery {
+        workspace_root: root_str.clone(),
+        tool_root,
+        server_path: server.is_file().then(|| path_str(&server)),
+        model_path: model_path.as_deref().map(path_str),
+        had_multiple_models,
+        config_path: path_str(&root.join(CONFIG_REL)),
+        log_file_path: path_str(&crate::logging::log_file_path(root)),
+    })
+}
+
+/// Tool root, llama-server and model that runtime_autostart would use.
+#[tauri::command]
+pub fn runtime_discover(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<RuntimeDiscovery> {
+    let root = registry::check_read(&registry, &workspace_root)?;
+    let rt = crate::settings::load(Some(&root)).runtime;
+    discover(&root, &rt)
+}
+
+/// Whether a saved llama_server_path may be spawned: a llama-server binary inside the
+/// discovered tool root. Anything else in runtime_config.json is ignored.
+fn trusted_server(path: &str, tool_root: Option<&str>) -> bool {
+    let (Ok(exe), Some(Ok(tool_root))) = (
+        Path::new(path).canonicalize(),
+        tool_root.map(|t| Path::new(t).canonicalize()),
+    ) else {
+        return false;
+    };
+    exe.is_file()
+        && exe.starts_with(&tool_root)
+        && exe.file_name() == Path::new(llama_exe_rel()).file_name()
+}
+
+fn read_config(root: &Path) -> Option<RuntimeConfig> {
+    let raw = std::fs::read_to_string(root.join(CONFIG_REL)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_config(root: &Path, config: &RuntimeConfig) -> AppResult<()> {
+    let path = root.join(CONFIG_REL);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).at(dir)?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| AppError::new(ErrorKind::Parse, e.to_string()))?;
+    std::fs::write(&path, json).at(&path)
+}
+
+/// Start or attach to the local model for workspace_root. Reuses the model from
+/// runtime_config.json while it still exists, and its llama-server only while that is a
+/// llama-server binary inside the tool root; otherwise both are discovered there. When a
+/// healthy server already answers on the port, it is attached (port recorded, no child).
+/// Host, port, ctx, GPU layers and threads come from settings. The resolved config is
+/// written back and the outcome logged (target `runtime`) whatever it is.
+#[tauri::command]
+pub async fn runtime_autostart(
+    workspace_root: String,
+    state: tauri::State<'_, Mutex<RuntimeState>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<RuntimeAutostartResult> {
+    let root = registry::check_read(&registry, &workspace_root)?;
+    registry::check_write(&registry, &workspace_root, &root.join(CONFIG_REL))?;
+    registry::check_write(&registry, &workspace_root, &root.join(crate::logging::LOGS_REL))?;
+    let rt = crate::settings::load(Some(&root)).runtime;
+    let found = discover(&root, &rt)?;
+    crate::logging::attach(&root)?;
+    let saved = read_config(&root);
+    let still_file = |p: &str| !p.is_empty() && Path::new(p).is_file();
+
+    let server = saved
+        .as_ref()
+        .map(|c| c.llama_server_path.clone())
+        .filter(|p| trusted_server(p, found.tool_root.as_deref()))
+        .or(found.server_path.clone());
+    // An explicit runtime.modelPath wins over the saved model; a scanned one does not.
+    let explicit_model = rt.model_path.as_deref().is_some_and(|m| !m.trim().is_empty());
+    let saved_model = saved
+        .as_ref()
+        .map(|c| c.model_path.clone())
+        .filter(|p| still_file(p));
+    let model = if explicit_model && found.model_path.is_some() {
+        found.model_path.clone()
+    } else {
+        saved_model.or(found.model_path.clone())
+    };
+
+    let config = RuntimeConfig {
+        llama_server_path: server.clone().unwrap_or_default(),
+        model_path: model.clone().unwrap_or_default(),
+        host: rt.host.clone(),
+        port: rt.port,
+        ctx: rt.ctx,
+        threads: rt.threads,
+        gpu_layers: rt.gpu_layers,
+        created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+    };
+    let port = rt.port;
+    let base = found.tool_root.as_deref().unwrap_or(&found.workspace_root);
+
+    let (status, details) = match (&server, &model) {
+        (None, _) => (
+            AutostartStatus::MissingRuntime,
+            Some(format!("{} not found under {}.", llama_exe_rel(), base)),
+        ),
+        (_, None) => (
+            AutostartStatus::MissingModel,
+            Some(format!("No .gguf in {}/models.", base)),
+        ),
+        (Some(server), Some(model)) => {
+            let attached = {
+                let mut s = lock(&state)?;
+                s.port == Some(port)
+                    && s.child
+                        .as_mut()
+                        .is_some_and(|c| c.try_wait().ok().flatten().is_none())
+            };
+            if attached {
+                (AutostartStatus::AlreadyRunning, Some(format!("Running on port {}.", port)))
+            } else if runtime_health_check(port).await.unwrap_or(false) {
+                // Attach: remember the port so status and token counting use this server.
+                let mut s = lock(&state)?;
+                if let Some(mut old) = s.child.take() {
+                    let _ = old.kill();
+                }
+                s.port = Some(port);
+                s.ctx_size = None;
+                (
+                    AutostartStatus::AlreadyRunning,
+                    Some(format!("Port {} in use (health OK).", port)),
+                )
+            } else {
+                let launched = launch(
+                    &state,
+                    Launch {
+                        server_path: Path::new(server),
+                        model,
+                        host: &rt.host,
+                        port,
+                        ctx_size: Some(rt.ctx),
+                        gpu_layers: Some(rt.gpu_layers),
+                        threads: rt.threads,
+                    },
+                )
+                .await;
+                match launched {
+                    Ok(_) => (AutostartStatus::Started, None),
+                    Err(e) => (AutostartStatus::Error, Some(e.message)),
+                }
+            }
+        }
+    };
+
+    write_config(&root, &config)?;
+    let model_str = model.as_deref().unwrap_or("none");
+    match status {
+        AutostartStatus::Error => {
+            tracing::error!(target: "runtime", status = status.as_str(), model = model_str, port, details = ?details, "autostart")
+        }
+        _ => tracing::info!(target: "runtime", status = status.as_str(), model = model_str, port, details = ?details, "autostart"),
+    }
+    Ok(RuntimeAutostartResult {
+        status,
+        details,
+        port,
+        config,
+    })
+}
+
+#[derive(serde::Deserialize)]
+struct PropsResponse {
+    default_generation_settings: Option<PropsGenerationSettings>,
+}
+
+#[derive(serde::Deserialize)]
+struct PropsGenerationSettings {
+    n_ctx: Option<u32>,
+}
+
+/// Context size of the running server: the --ctx-size it was started with, else what
+/// /props reports. None when no server is running.
+pub(crate) async fn context_size(state: &Mutex<RuntimeState>) -> AppResult<Option<u32>> {
+    let (port, known) = {
+        let s = lock(state)?;
+        match s.port {
+            Some(port) => (port, s.ctx_size),
+            None => return Ok(None),
+        }
+    };
+    if known.is_some() {
+        return Ok(known);
+    }
+    let url = format!("http://127.0.0.1:{}/props", port);
+    let Ok(resp) = reqwest::get(&url).await else {
+        return Ok(None);
+    };
+    Ok(resp
+        .json::<PropsResponse>()
+        .await
+        .ok()
+        .and_then(|p| p.default_generation_settings)
+        .and_then(|g| g.n_ctx))
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct CompletionRequest {
     prompt: String,
@@ -294,20 +712,16 @@ pub struct ChatOptions {
     pub temperature: f64,
 }
 
-/// Try /v1/chat/completions first; on failure try /completion. Returns assistant content or error.
+/// Try /v1/chat/completions first; on failure try /completion. Returns assistant content or error;
+/// errors from the server carry the endpoint in details.
 #[tauri::command]
 pub async fn runtime_chat(
     system_prompt: String,
     user_prompt: String,
     options: Option<ChatOptions>,
     state: tauri::State<'_, Mutex<RuntimeState>>,
-) -> Result<String, String> {
-    let port = {
-        let s = state.lock().map_err(|e| e.to_string())?;
-        s.port.ok_or_else(|| {
-            "Runtime not started. Start the runtime with a GGUF model first.\nEndpoint: n/a (runtime not started)".to_string()
-        })?
-    };
+) -> AppResult<String> {
+    let port = lock(&state)?.port.ok_or_else(not_started)?;
 
     let opt = options.unwrap_or_default();
     let max_tokens = if opt.max_tokens > 0 { opt.max_tokens } else { 512 };
@@ -362,18 +776,15 @@ pub async fn runtime_chat(
         .json(&body_completion)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}\nEndpoint: {} (no response)", e, url_completion))?;
+        .map_err(|e| request_error(e, &url_completion))?;
 
     if !resp.status().is_success() {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!(
-            "llama-server error {}: {}\nEndpoint: {} HTTP {}",
-            status, text, url_completion, status
-        ));
+        return Err(http_error(resp, &url_completion).await);
     }
 
-    let json: CompletionResponse = resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+    let json: CompletionResponse = resp.json().await.map_err(|e| {
+        AppError::new(ErrorKind::Parse, format!("Parse error: {}", e)).with("endpoint", &url_completion)
+    })?;
     Ok(json.content.unwrap_or_default().trim().to_string())
 }
 
@@ -383,11 +794,8 @@ pub async fn runtime_generate(
     stream: bool,
     options: Option<GenerateOptions>,
     state: tauri::State<'_, Mutex<RuntimeState>>,
-) -> Result<String, String> {
-    let port = {
-        let s = state.lock().map_err(|e| e.to_string())?;
-        s.port.ok_or("Runtime not started. Start the runtime with a GGUF model first.")?
-    };
+) -> AppResult<String> {
+    let port = lock(&state)?.port.ok_or_else(not_started)?;
 
     let opt = options.unwrap_or_default();
     let temperature = if opt.temperature != 0.0 {
@@ -417,14 +825,14 @@ pub async fn runtime_generate(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(|e| request_error(e, &url))?;
 
     if !resp.status().is_success() {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("Server error {}: {}", status, text));
+        return Err(http_error(resp, &url).await);
     }
 
-    let json: CompletionResponse = resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+    let json: CompletionResponse = resp.json().await.map_err(|e| {
+        AppError::new(ErrorKind::Parse, format!("Parse error: {}", e)).with("endpoint", &url)
+    })?;
     Ok(json.content.unwrap_or_default())
 }
@@ -0,0 +1,342 @@
+//! Full-text content search (ripgrep-style): grep-regex/grep-searcher over a parallel
+//! walk with the shared ignore rules. Results can be streamed as events for large repos.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{
+    BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkMatch,
+};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkState;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppResult;
+use crate::walker;
+
+pub const RESULTS_EVENT: &str = "workspace://search-results";
+
+const DEFAULT_MAX_PER_FILE: usize = 50;
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+const MAX_CONTEXT_LINES: usize = 10;
+const PREVIEW_MAX_CHARS: usize = 300;
+/// Larger files are skipped (generated bundles, logs, data dumps).
+const MAX_FILE_BYTES: u64 = 8 * 1024 * 1024;
+/// Matches per streamed event.
+const STREAM_BATCH: usize = 50;
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseMode {
+    /// Case-insensitive unless the query has an uppercase letter.
+    #[default]
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentSearchOptions {
+    /// Treat query as a regex; default is a literal string.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case: CaseMode,
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Only search files matching one of these globs (e.g. "src/**/*.ts").
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Skip files matching any of these globs.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub context_lines: Option<usize>,
+    pub max_matches_per_file: Option<usize>,
+    pub max_results: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    /// When set, matches are also emitted in batches as `workspace://search-results`.
+    pub search_id: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentMatch {
+    pub path: String,
+    /// 1-based.
+    pub line: u64,
+    /// 1-based, in characters.
+    pub column: usize,
+    pub preview: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentSearchResult {
+    /// Sorted by path, then line.
+    pub matches: Vec<ContentMatch>,
+    pub files_searched: usize,
+    pub files_with_matches: usize,
+    /// max_results was reached; more matches may exist.
+    pub truncated: bool,
+    pub timed_out: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResultsEvent {
+    search_id: String,
+    matches: Vec<ContentMatch>,
+}
+
+fn preview(bytes: &[u8]) -> String {
+    let s = String::from_utf8_lossy(bytes);
+    let s = s.trim_end_matches(['\r', '\n']);
+    match s.char_indices().nth(PREVIEW_MAX_CHARS) {
+        Some((i, _)) => format!("{}…", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+/// Collects one file's matches plus their context lines.
+struct FileSink<'a> {
+    matcher: &'a RegexMatcher,
+    path: &'a str,
+    limit: usize,
+    before: Vec<String>,
+    out: Vec<ContentMatch>,
+}
+
+impl Sink for FileSink<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _: &Searcher, m: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        let bytes = m.bytes();
+        let column = match self.matcher.find(bytes) {
+            Ok(Some(found)) => {
+                String::from_utf8_lossy(&bytes[..found.start()])
+                    .chars()
+                    .count()
+                    + 1
+            }
+            _ => 1,
+        };
+        self.out.push(ContentMatch {
+            path: self.path.to_string(),
+            line: m.line_number().unwrap_or(0),
+            column,
+            preview: preview(bytes),
+            context_before: std::mem::take(&mut self.before),
+            context_after: Vec::new(),
+        });
+        Ok(self.out.len() < self.limit)
+    }
+
+    fn context(&mut self, _: &Searcher, c: &SinkContext<'_>) -> Result<bool, Self::Error> {
+        let line = preview(c.bytes());
+        match c.kind() {
+            SinkContextKind::Before => self.before.push(line),
+            SinkContextKind::After => {
+                if let Some(last) = self.out.last_mut() {
+                    last.context_after.push(line);
+                }
+            }
+            SinkContextKind::Other => {}
+        }
+        Ok(true)
+    }
+}
+
+fn build_matcher(query: &str, opts: &ContentSearchOptions) -> Result<RegexMatcher, String> {
+    let mut b = RegexMatcherBuilder::new();
+    b.fixed_strings(!opts.regex)
+        .word(opts.whole_word)
+        .line_terminator(Some(b'\n'));
+    match opts.case {
+        CaseMode::Smart => b.case_smart(true),
+        CaseMode::Sensitive => b.case_insensitive(false),
+        CaseMode::Insensitive => b.case_insensitive(true),
+    };
+    b.build(query)
+        .map_err(|e| format!("invalid search pattern: {}", e))
+}
+
+/// Search file contents under workspace_root. Literal by default; honors .gitignore,
+/// .devassistantignore and settings, plus include/exclude globs. Stops at max_results
+/// or timeout_ms and says so in the result.
+#[tauri::command]
+pub async fn workspace_search_content(
+    app: AppHandle,
+    workspace_root: String,
+    query: String,
+    options: Option<ContentSearchOptions>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<ContentSearchResult> {
+    let root = crate::registry::check_read(&registry, &workspace_root)?;
+    let opts = options.unwrap_or_default();
+    crate::error::blocking(move || search_content(&app, &root, &query, opts)).await
+}
+
+fn search_content(
+    app: &AppHandle,
+    root: &Path,
+    query: &str,
+    opts: ContentSearchOptions,
+) -> Result<ContentSearchResult, String> {
+    let started = Instant::now();
+    if query.is_empty() {
+        return Err("search query is empty".into());
+    }
+    let matcher = build_matcher(query, &opts)?;
+    let context = opts.context_lines.unwrap_or(0).min(MAX_CONTEXT_LINES);
+    let per_file = opts
+        .max_matches_per_file
+        .unwrap_or(DEFAULT_MAX_PER_FILE)
+        .max(1);
+    let max_results = opts
+        .max_results
+        .unwrap_or_else(|| crate::settings::load(Some(root)).limits.search_max_results)
+        .max(1);
+    let deadline = started + Duration::from_millis(opts.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+
+    let mut ov = OverrideBuilder::new(root);
+    for g in &opts.include {
+        ov.add(g)
+            .map_err(|e| format!("invalid include glob {:?}: {}", g, e))?;
+    }
+    for g in &opts.exclude {
+        ov.add(&format!("!{}", g))
+            .map_err(|e| format!("invalid exclude glob {:?}: {}", g, e))?;
+    }
+    let overrides = ov.build().map_err(|e| e.to_string())?;
+
+    let mut searcher = SearcherBuilder::new();
+    searcher
+        .line_number(true)
+        .before_context(context)
+        .after_context(context)
+        .binary_detection(BinaryDetection::quit(b'\x00'));
+
+    let stop = AtomicBool::new(false);
+    let timed_out = AtomicBool::new(false);
+    let files_searched = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel::<Vec<ContentMatch>>();
+
+    let mut wb = walker::builder(root, &walker::WalkOptions::default())?;
+    wb.overrides(overrides);
+    let walk = wb.build_parallel();
+
+    let mut matches = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            walk.run(|| {
+                let tx = tx.clone();
+                let matcher = &matcher;
+                let mut searcher = searcher.build();
+                let (stop, timed_out, files_searched) = (&stop, &timed_out, &files_searched);
+                Box::new(move |res| {
+                    if stop.load(Ordering::Relaxed) {
+                        return WalkState::Quit;
+                    }
+                    if Instant::now() >= deadline {
+                        timed_out.store(true, Ordering::Relaxed);
+                        stop.store(true, Ordering::Relaxed);
+                        return WalkState::Quit;
+                    }
+                    let Ok(e) = res else {
+                        return WalkState::Continue;
+                    };
+                    if !e.file_type().is_some_and(|t| t.is_file()) {
+                        return WalkState::Continue;
+                    }
+                    if e.metadata()
+                        .map(|m| m.len() > MAX_FILE_BYTES)
+                        .unwrap_or(true)
+                    {
+                        return WalkState::Continue;
+                    }
+                    let Some(rel) = walker::rel_path(root, e.path()) else {
+                        return WalkState::Continue;
+                    };
+                    files_searched.fetch_add(1, Ordering::Relaxed);
+                    let mut sink = FileSink {
+                        matcher,
+                        path: &rel,
+                        limit: per_file,
+                        before: Vec::new(),
+                        out: Vec::new(),
+                    };
+                    let _ = searcher.search_path(matcher, e.path(), &mut sink);
+                    if !sink.out.is_empty() && tx.send(sink.out).is_err() {
+                        return WalkState::Quit;
+                    }
+                    WalkState::Continue
+                })
+            });
+            drop(tx);
+        });
+
+        let mut all: Vec<ContentMatch> = Vec::new();
+        let mut pending: Vec<ContentMatch> = Vec::new();
+        let emit = |batch: &mut Vec<ContentMatch>| {
+            if let Some(id) = &opts.search_id {
+                if !batch.is_empty() {
+                    let _ = app.emit(
+                        RESULTS_EVENT,
+                        SearchResultsEvent {
+                            search_id: id.clone(),
+                            matches: std::mem::take(batch),
+                        },
+                    );
+                }
+            }
+        };
+        for file_matches in rx {
+            if all.len() >= max_results {
+                stop.store(true, Ordering::Relaxed);
+                continue;
+            }
+            let take = (max_results - all.len()).min(file_matches.len());
+            if take < file_matches.len() {
+                stop.store(true, Ordering::Relaxed);
+            }
+            for m in file_matches.into_iter().take(take) {
+                if opts.search_id.is_some() {
+                    pending.push(m.clone());
+                }
+                all.push(m);
+            }
+            if pending.len() >= STREAM_BATCH {
+                emit(&mut pending);
+            }
+        }
+        emit(&mut pending);
+        all
+    });
+
+    matches.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    let mut files_with_matches = 0;
+    let mut last: Option<&str> = None;
+    for m in &matches {
+        if last != Some(m.path.as_str()) {
+            files_with_matches += 1;
+            last = Some(m.path.as_str());
+        }
+    }
+    let timed_out = timed_out.load(Ordering::Relaxed);
+    Ok(ContentSearchResult {
+        truncated: stop.load(Ordering::Relaxed) && !timed_out,
+        files_searched: files_searched.load(Ordering::Relaxed),
+        files_with_matches,
+        matches,
+        timed_out,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
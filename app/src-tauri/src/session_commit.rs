@@ -0,0 +1,379 @@
+//! Commit an applied session: stage exactly the files in its `filesChanged` and commit them
+//! with a message built from the prompt and explanation, optionally on a new branch. Refuses
+//! when other changes are already staged unless the caller overrides.
+//! Commits are made with libgit2, so git hooks (pre-commit, commit-msg) do not run.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use git2::{DiffOptions, ErrorCode};
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::git::{err, short, Repo};
+use crate::registry::{self, WorkspaceRegistry};
+
+const SESSIONS_REL: &str = ".devassistant/sessions.json";
+const SUBJECT_MAX_CHARS: usize = 72;
+const BODY_WIDTH: usize = 72;
+const BRANCH_PREFIX: &str = "devassistant/";
+const BRANCH_SLUG_MAX_CHARS: usize = 40;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCommitPlan {
+    pub session_id: String,
+    /// Generated commit message (subject, blank line, wrapped explanation, Session trailer).
+    pub message: String,
+    /// Workspace-relative files that would be committed.
+    pub files: Vec<String>,
+    /// Repository paths staged now that are not part of the session.
+    pub unrelated_staged: Vec<String>,
+    /// Suggested feature branch name.
+    pub branch_suggestion: String,
+    /// Current branch (None when detached).
+    pub current_branch: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCommitResult {
+    pub commit: String,
+    pub short_id: String,
+    /// Branch the commit was made on (None when detached).
+    pub branch: Option<String>,
+    pub created_branch: bool,
+    pub message: String,
+    pub files: Vec<String>,
+}
+
+struct SessionInfo {
+    prompt: String,
+    explanation: String,
+    files: Vec<String>,
+}
+
+fn read_session(
+    workspace_root: &str,
+    session_id: &str,
+    allow_external_symlinks: bool,
+) -> AppResult<SessionInfo> {
+    let path = crate::workspace::resolve(workspace_root, SESSIONS_REL, allow_external_symlinks)?;
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(&path, e).with("reason", "no sessions"))?;
+    let sessions: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| AppError::new(ErrorKind::Parse, e.to_string()).with("path", SESSIONS_REL))?;
+    let session = sessions
+        .as_array()
+        .and_then(|list| {
+            list.iter()
+                .find(|s| s.get("id").and_then(|v| v.as_str()) == Some(session_id))
+        })
+        .ok_or_else(|| AppError::not_found(format!("session not found: {}", session_id)))?;
+    let status = session.get("status").and_then(|v| v.as_str()).unwrap_or("");
+    if status != "applied" {
+        return Err(AppError::new(
+            ErrorKind::Conflict,
+            format!(
+                "session {} is {}, only applied sessions can be committed",
+                session_id, status
+            ),
+        ));
+    }
+    let text = |key: &str| {
+        session
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+    let files: BTreeSet<String> = session
+        .get("filesChanged")
+        .and_then(|v| v.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|f| f.get("path").and_then(|p| p.as_str()))
+                .map(|p| p.replace('\\', "/").trim_start_matches('/').to_string())
+                .filter(|p| !p.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Conflict,
+            format!("session {} changed no files", session_id),
+        ));
+    }
+    Ok(SessionInfo {
+        prompt: text("userPrompt"),
+        explanation: text("explanation"),
+        files: files.into_iter().collect(),
+    })
+}
+
+fn wrap(text: &str, width: usize) -> String {
+    let mut out = Vec::new();
+    for para in text.lines() {
+        if para.trim().is_empty() || para.starts_with(char::is_whitespace) || para.starts_with('-')
+        {
+            // Blank lines, indented blocks and bullets are kept as written.
+            out.push(para.trim_end().to_string());
+            continue;
+        }
+        let mut line = String::new();
+        for word in para.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                out.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        out.push(line);
+    }
+    out.join("\n")
+}
+
+fn commit_message(session_id: &str, s: &SessionInfo) -> String {
+    let first = s
+        .prompt
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("Apply assistant changes")
+        .trim()
+        .trim_end_matches('.');
+    let mut subject: String = first.chars().take(SUBJECT_MAX_CHARS - 1).collect();
+    if first.chars().count() > SUBJECT_MAX_CHARS {
+        // Cut at a word boundary when there is one.
+        if let Some(space) = subject.rfind(' ') {
+            subject.truncate(space);
+        }
+        subject.push('…');
+    } else {
+        subject = first.to_string();
+    }
+    let mut chars = subject.chars();
+    let subject = match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => subject,
+    };
+    let mut message = subject;
+    message.push_str("\n\n");
+    if !s.explanation.is_empty() {
+        message.push_str(&wrap(&s.explanation, BODY_WIDTH));
+        message.push_str("\n\n");
+    }
+    message.push_str(&format!("Session: {}\n", session_id));
+    message
+}
+
+fn branch_suggestion(prompt: &str) -> String {
+    let mut slug = String::new();
+    for c in prompt.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() > BRANCH_SLUG_MAX_CHARS {
+            // Drop the partial word.
+            if let Some(dash) = slug.rfind('-') {
+                slug.truncate(dash);
+            }
+            break;
+        }
+    }
+    let slug = slug.trim_matches('-');
+    format!(
+        "{}{}",
+        BRANCH_PREFIX,
+        if slug.is_empty() { "changes" } else { slug }
+    )
+}
+
+/// Repository paths staged (index differs from HEAD) that are not in `session_paths`.
+fn unrelated_staged(r: &Repo, session_paths: &BTreeSet<String>) -> AppResult<Vec<String>> {
+    let head_tree = match r.repo.head() {
+        Ok(h) => Some(h.peel_to_tree().map_err(err)?),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(err(e)),
+    };
+    let diff = r
+        .repo
+        .diff_tree_to_index(head_tree.as_ref(), None, Some(&mut DiffOptions::new()))
+        .map_err(err)?;
+    let mut out = BTreeSet::new();
+    for d in diff.deltas() {
+        for f in [d.old_file(), d.new_file()] {
+            if let Some(p) = f.path() {
+                let p = p.to_string_lossy().replace('\\', "/");
+                if !session_paths.contains(&p) {
+                    out.insert(p);
+                }
+            }
+        }
+    }
+    Ok(out.into_iter().collect())
+}
+
+fn current_branch(r: &Repo) -> Option<String> {
+    if r.repo.head_detached().unwrap_or(false) {
+        return None;
+    }
+    let head = r.repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    target.strip_prefix("refs/heads/").map(str::to_string)
+}
+
+/// Session file paths in repository form.
+fn repo_paths(r: &Repo, files: &[String]) -> AppResult<BTreeSet<String>> {
+    files.iter().map(|f| r.to_repo(f)).collect()
+}
+
+/// What committing the session would do: generated message, files, suggested branch and
+/// any unrelated staged changes that would block it.
+#[tauri::command]
+pub fn git_session_commit_plan(
+    workspace_root: String,
+    session_id: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<SessionCommitPlan> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let session = read_session(
+        &workspace_root,
+        &session_id,
+        crate::workspace::allow_external_symlinks(),
+    )?;
+    let paths = repo_paths(&r, &session.files)?;
+    Ok(SessionCommitPlan {
+        message: commit_message(&session_id, &session),
+        unrelated_staged: unrelated_staged(&r, &paths)?,
+        branch_suggestion: branch_suggestion(&session.prompt),
+        current_branch: current_branch(&r),
+        files: session.files,
+        session_id,
+    })
+}
+
+/// Stage exactly the session's files and commit them (message defaults to the generated one).
+/// With `branch`, a new branch is created at HEAD and checked out first (the working tree is
+/// unchanged since it points at the same commit). Fails when other changes are staged unless
+/// allow_unrelated_staged, in which case they are committed too. Each file must be writable
+/// under the workspace policy.
+#[tauri::command]
+pub fn git_session_commit(
+    workspace_root: String,
+    session_id: String,
+    message: Option<String>,
+    branch: Option<String>,
+    allow_unrelated_staged: Option<bool>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<SessionCommitResult> {
+    registry::check_read(&registry, &workspace_root)?;
+    let r = Repo::require(&workspace_root)?;
+    let external = crate::workspace::allow_external_symlinks();
+    let session = read_session(&workspace_root, &session_id, external)?;
+    // Committing a file is writing it as far as the workspace policy goes.
+    for f in &session.files {
+        let full = crate::workspace::resolve(&workspace_root, f, external)?;
+        registry::check_write(&registry, &workspace_root, &full)?;
+    }
+    let paths = repo_paths(&r, &session.files)?;
+    if !allow_unrelated_staged.unwrap_or(false) {
+        let unrelated = unrelated_staged(&r, &paths)?;
+        if !unrelated.is_empty() {
+            return Err(AppError::new(
+                ErrorKind::Conflict,
+                format!(
+                    "Refusing to commit: unrelated changes are staged ({}). Unstage them or allow unrelated staged changes.",
+                    unrelated.join(", ")
+                ),
+            )
+            .with("staged", &unrelated));
+        }
+    }
+    let message = message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .map(|m| format!("{}\n", m))
+        .unwrap_or_else(|| commit_message(&session_id, &session));
+    let sig = r.repo.signature().map_err(|_| {
+        AppError::invalid("git user.name and user.email are not configured for this repository")
+    })?;
+    let parent = match r.repo.head() {
+        Ok(h) => Some(h.peel_to_commit().map_err(err)?),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(err(e)),
+    };
+
+    let new_branch = match branch
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+    {
+        Some(name) if current_branch(&r).as_deref() != Some(name.as_str()) => {
+            let full = format!("refs/heads/{}", name);
+            if !git2::Reference::is_valid_name(&full) {
+                return Err(AppError::invalid(format!("invalid branch name: {}", name)));
+            }
+            if r.repo.find_reference(&full).is_ok() {
+                return Err(AppError::new(
+                    ErrorKind::Conflict,
+                    format!("branch already exists: {}", name),
+                ));
+            }
+            Some((name, full))
+        }
+        _ => None,
+    };
+
+    // Staged in memory first; the user's index is only written once the commit is made.
+    let workdir = r
+        .repo
+        .workdir()
+        .ok_or_else(|| AppError::not_found("not a git repository"))?
+        .to_path_buf();
+    let mut index = r.repo.index().map_err(err)?;
+    for p in &paths {
+        let rel = Path::new(p);
+        if workdir.join(rel).is_file() {
+            index.add_path(rel).map_err(err)?;
+        } else {
+            let _ = index.remove_path(rel);
+        }
+    }
+    let tree_id = index.write_tree().map_err(err)?;
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree_id) {
+        return Err(AppError::new(
+            ErrorKind::Conflict,
+            "nothing to commit: the session's files match HEAD",
+        ));
+    }
+
+    let created_branch = new_branch.is_some();
+    if let Some((name, full)) = new_branch {
+        if let Some(p) = &parent {
+            r.repo.branch(&name, p, false).map_err(err)?;
+        }
+        r.repo.set_head(&full).map_err(err)?;
+    }
+    let tree = r.repo.find_tree(tree_id).map_err(err)?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = r
+        .repo
+        .commit(Some("HEAD"), &sig, &sig, &message, &tree, &parents)
+        .map_err(err)?;
+    index.write().map_err(err)?;
+
+    Ok(SessionCommitResult {
+        commit: oid.to_string(),
+        short_id: short(oid),
+        branch: current_branch(&r),
+        created_branch,
+        message,
+        files: session.files,
+    })
+}
@@ -0,0 +1,885 @@
+//! Layered settings: built-in defaults, then the user's config
+//! (`<config dir>/devassistant/settings.json`), then the workspace's
+//! `.devassistant/settings.json`, then `DEVASSISTANT_*` environment variables.
+//! Files are migrated to the current version when read and validated before they are
+//! written; an invalid file is skipped with a warning rather than failing every caller.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult, ErrorKind, IoResultExt};
+use crate::registry::{self, WorkspaceRegistry};
+
+pub const CHANGED_EVENT: &str = "settings://changed";
+
+/// Version written to settings files. Older files are upgraded by MIGRATIONS on read.
+pub const SETTINGS_VERSION: u32 = 2;
+const FILE_NAME: &str = "settings.json";
+const WORKSPACE_REL: &str = ".devassistant/settings.json";
+const USER_DIR: &str = "devassistant";
+/// `DEVASSISTANT_RUNTIME__PORT=8080` sets runtime.port; `__` separates nesting levels.
+const ENV_PREFIX: &str = "DEVASSISTANT_";
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Keys read only from the user settings file and environment: a workspace's own file
+/// cannot set them and settings_set cannot change them, so neither an opened repository nor
+/// the frontend can widen its own access.
+const USER_ONLY: &[&str] = &[
+    "runtime.host",
+    "security.allowExternalSymlinks",
+    "security.protectedPaths",
+];
+
+/// Never written through workspace commands unless security.protectedPaths says otherwise.
+/// Patterns without a slash match the file name at any depth.
+const DEFAULT_PROTECTED: &[&str] = &[
+    ".env",
+    ".env.*",
+    "*.pem",
+    "*.key",
+    "*.p12",
+    "*.pfx",
+    "id_rsa",
+    "id_ed25519",
+    ".git/**",
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+];
+
+/// Serializes read-modify-write of settings files.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub auto_packs_enabled: bool,
+    pub enabled_packs: Vec<String>,
+    /// Extra gitignore-style patterns for the workspace walker.
+    pub extra_ignore: Vec<String>,
+    /// Checkpoint before applying patches.
+    pub checkpoints: bool,
+    pub runtime: RuntimeSettings,
+    pub limits: Limits,
+    pub logging: LoggingSettings,
+    pub security: SecuritySettings,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuntimeSettings {
+    /// Address llama-server binds to. Loopback only, and user settings only (see USER_ONLY):
+    /// the app's own health, props and chat calls go to 127.0.0.1.
+    pub host: String,
+    pub port: u16,
+    pub model_path: Option<String>,
+    pub ctx: u32,
+    pub gpu_layers: i32,
+    pub threads: Option<u32>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Limits {
+    /// Levels walked up from the workspace looking for runtime/llama + models/.
+    pub tool_root_max_levels: u32,
+    /// Default cap on content search results when the request sets none.
+    pub search_max_results: usize,
+    /// Files listed in a walk snapshot (the change index is not affected).
+    pub snapshot_max_files: usize,
+    /// Files returned by a search by name when the request sets no limit.
+    pub name_search_default_results: usize,
+    /// Cap on a requested search by name limit.
+    pub name_search_max_results: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    /// tracing filter, e.g. "info" or "info,llama_server=warn". RUST_LOG overrides it.
+    pub level: String,
+    /// The active log file is rotated past this size, and at the start of each day.
+    pub max_file_mb: u32,
+    /// Log files kept per workspace, the active one included.
+    pub max_files: u32,
+    /// Rotated files older than this are deleted.
+    pub retention_days: u32,
+}
+
+/// User settings and environment only (see USER_ONLY).
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SecuritySettings {
+    /// Let workspace paths follow symlinks to targets outside the workspace root.
+    pub allow_external_symlinks: bool,
+    /// Globs workspace commands refuse to write, relative to the workspace root.
+    pub protected_paths: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            auto_packs_enabled: true,
+            enabled_packs: Vec::new(),
+            extra_ignore: Vec::new(),
+            checkpoints: true,
+            runtime: RuntimeSettings::default(),
+            limits: Limits::default(),
+            logging: LoggingSettings::default(),
+            security: SecuritySettings::default(),
+        }
+    }
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        RuntimeSettings {
+            host: "127.0.0.1".into(),
+            port: 11435,
+            model_path: None,
+            ctx: 4096,
+            gpu_layers: 0,
+            threads: None,
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            tool_root_max_levels: 8,
+            search_max_results: 500,
+            snapshot_max_files: 2000,
+            name_search_default_results: 20,
+            name_search_max_results: 500,
+        }
+    }
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        SecuritySettings {
+            allow_external_symlinks: false,
+            protected_paths: DEFAULT_PROTECTED.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        LoggingSettings {
+            level: "info".into(),
+            max_file_mb: 10,
+            max_files: 10,
+            retention_days: 14,
+        }
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    let host = host.trim();
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+impl Settings {
+    /// Range checks serde cannot express. Empty when valid.
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, msg: &str| {
+            if !ok {
+                errors.push(msg.to_string());
+            }
+        };
+        check(
+            self.version <= SETTINGS_VERSION,
+            "version is newer than this app supports",
+        );
+        check(
+            is_loopback(&self.runtime.host),
+            "runtime.host must be a loopback address",
+        );
+        check(self.runtime.port != 0, "runtime.port must be 1-65535");
+        check(
+            (256..=1_048_576).contains(&self.runtime.ctx),
+            "runtime.ctx must be 256-1048576",
+        );
+        check(
+            self.runtime.gpu_layers >= -1,
+            "runtime.gpuLayers must be -1 (all) or more",
+        );
+        check(
+            self.runtime.threads.is_none_or(|t| t > 0),
+            "runtime.threads must be positive",
+        );
+        check(
+            (1..=64).contains(&self.limits.tool_root_max_levels),
+            "limits.toolRootMaxLevels must be 1-64",
+        );
+        check(
+            (1..=100_000).contains(&self.limits.search_max_results),
+            "limits.searchMaxResults must be 1-100000",
+        );
+        check(
+            (1..=200_000).contains(&self.limits.snapshot_max_files),
+            "limits.snapshotMaxFiles must be 1-200000",
+        );
+        check(
+            (1..=100_000).contains(&self.limits.name_search_max_results),
+            "limits.nameSearchMaxResults must be 1-100000",
+        );
+        check(
+            (1..=self.limits.name_search_max_results)
+                .contains(&self.limits.name_search_default_results),
+            "limits.nameSearchDefaultResults must be 1 to limits.nameSearchMaxResults",
+        );
+        check(
+            tracing_subscriber::EnvFilter::try_new(&self.logging.level).is_ok(),
+            "logging.level is not a valid filter",
+        );
+        check(
+            (1..=1024).contains(&self.logging.max_file_mb),
+            "logging.maxFileMb must be 1-1024",
+        );
+        check(
+            (1..=100).contains(&self.logging.max_files),
+            "logging.maxFiles must be 1-100",
+        );
+        check(
+            (1..=365).contains(&self.logging.retention_days),
+            "logging.retentionDays must be 1-365",
+        );
+        for p in &self.security.protected_paths {
+            if globset::Glob::new(p).is_err() {
+                errors.push(format!("security.protectedPaths: invalid glob {:?}", p));
+            }
+        }
+        errors
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    Default,
+    User,
+    Workspace,
+    Env,
+}
+
+/// Effective settings and where each value came from.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedSettings {
+    pub settings: Settings,
+    /// Dotted key ("runtime.port") -> layer that set it.
+    pub sources: BTreeMap<String, Layer>,
+    /// Skipped layers, unknown keys and migrations, for display.
+    pub warnings: Vec<String>,
+    pub user_path: Option<String>,
+    pub workspace_path: Option<String>,
+}
+
+/// Migrations indexed by source version: MIGRATIONS[0] upgrades version 1 to 2.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v1];
+
+/// Version 1 (unversioned) kept `port` and `modelPath` at the top level.
+fn migrate_v1(obj: &mut Map<String, Value>) {
+    let port = obj
+        .remove("port")
+        .filter(|p| p.as_u64().is_some_and(|n| n > 0));
+    let model = obj
+        .remove("modelPath")
+        .filter(|m| m.as_str().is_some_and(|s| !s.trim().is_empty()));
+    if port.is_none() && model.is_none() {
+        return;
+    }
+    let runtime = obj
+        .entry("runtime")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Some(runtime) = runtime.as_object_mut() {
+        if let Some(p) = port {
+            runtime.entry("port").or_insert(p);
+        }
+        if let Some(m) = model {
+            runtime.entry("modelPath").or_insert(m);
+        }
+    }
+}
+
+/// Upgrade a settings object to SETTINGS_VERSION. Returns whether anything changed.
+fn migrate(obj: &mut Map<String, Value>) -> Result<bool, String> {
+    let from = obj.get("version").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+    if from > SETTINGS_VERSION {
+        return Err(format!(
+            "version {} is newer than this app supports ({})",
+            from, SETTINGS_VERSION
+        ));
+    }
+    for m in MIGRATIONS.iter().skip(from.saturating_sub(1) as usize) {
+        m(obj);
+    }
+    obj.insert("version".into(), Value::from(SETTINGS_VERSION));
+    Ok(from < SETTINGS_VERSION)
+}
+
+/// Deep merge: objects merge key by key, anything else replaces. Nulls are skipped.
+fn merge(base: &mut Value, layer: &Value) {
+    match (base, layer) {
+        (Value::Object(b), Value::Object(l)) => {
+            for (k, v) in l {
+                if v.is_null() {
+                    continue;
+                }
+                match b.get_mut(k) {
+                    Some(existing) if existing.is_object() && v.is_object() => merge(existing, v),
+                    _ => {
+                        b.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+        }
+        (b, l) if !l.is_null() => *b = l.clone(),
+        _ => {}
+    }
+}
+
+/// RFC 7396 merge patch: null removes the key.
+fn apply_patch(target: &mut Map<String, Value>, patch: &Map<String, Value>) {
+    for (k, v) in patch {
+        match v {
+            Value::Null => {
+                target.remove(k);
+            }
+            Value::Object(p) => {
+                let entry = target
+                    .entry(k.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                if let Some(t) = entry.as_object_mut() {
+                    apply_patch(t, p);
+                }
+            }
+            _ => {
+                target.insert(k.clone(), v.clone());
+            }
+        }
+    }
+}
+
+/// Dotted paths of the leaves (scalars and arrays) in `v`. Nulls count only with `keep_null`.
+fn leaves(v: &Value, prefix: &str, keep_null: bool, out: &mut Vec<String>) {
+    match v.as_object() {
+        Some(obj) => {
+            for (k, child) in obj {
+                let path = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                if child.is_object() {
+                    leaves(child, &path, keep_null, out);
+                } else if keep_null || !child.is_null() {
+                    out.push(path);
+                }
+            }
+        }
+        None => out.push(prefix.to_string()),
+    }
+}
+
+/// Remove a dotted key from `value`. Returns whether it was present.
+fn remove_key(value: &mut Value, dotted: &str) -> bool {
+    let mut node = value;
+    let mut segs = dotted.split('.').peekable();
+    while let Some(seg) = segs.next() {
+        let Some(obj) = node.as_object_mut() else {
+            return false;
+        };
+        if segs.peek().is_none() {
+            return obj.remove(seg).is_some();
+        }
+        match obj.get_mut(seg) {
+            Some(next) => node = next,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Keys in `layer` that the schema does not have. Null values (removals) are not reported.
+fn unknown_keys(layer: &Value, schema: &Value, prefix: &str, out: &mut Vec<String>) {
+    let (Some(l), Some(s)) = (layer.as_object(), schema.as_object()) else {
+        return;
+    };
+    for (k, v) in l {
+        let path = if prefix.is_empty() {
+            k.clone()
+        } else {
+            format!("{}.{}", prefix, k)
+        };
+        match s.get(k) {
+            None if !v.is_null() => out.push(path),
+            None => {}
+            Some(sv) if sv.is_object() => unknown_keys(v, sv, &path, out),
+            Some(_) => {}
+        }
+    }
+}
+
+fn defaults_value() -> Value {
+    serde_json::to_value(Settings::default()).unwrap_or(Value::Null)
+}
+
+/// Type and range check of `layer` applied over the defaults.
+fn check_layer(layer: &Value) -> Result<Settings, String> {
+    let mut merged = defaults_value();
+    merge(&mut merged, layer);
+    let settings: Settings = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+    let errors = settings.validate();
+    if errors.is_empty() {
+        Ok(settings)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+pub(crate) fn user_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(USER_DIR).join(FILE_NAME))
+}
+
+fn workspace_path(root: &Path) -> PathBuf {
+    root.join(WORKSPACE_REL)
+}
+
+fn path_str(p: &Path) -> String {
+    p.to_string_lossy().replace('\\', "/")
+}
+
+/// A settings file's object and whether it was migrated from an older version.
+type FileLayer = (Map<String, Value>, bool);
+
+/// A settings file, migrated. None when the file does not exist.
+fn read_layer(path: &Path) -> Result<Option<FileLayer>, String> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    if raw.trim().is_empty() {
+        return Ok(Some((Map::new(), false)));
+    }
+    let value: Value = serde_json::from_str(&raw).map_err(|e| format!("invalid JSON: {}", e))?;
+    let Value::Object(mut obj) = value else {
+        return Err("expected a JSON object".into());
+    };
+    let migrated = migrate(&mut obj)?;
+    Ok(Some((obj, migrated)))
+}
+
+/// "RUNTIME__GPU_LAYERS" -> ["runtime", "gpuLayers"].
+fn env_key_path(key: &str) -> Vec<String> {
+    key.split("__")
+        .map(|seg| {
+            let mut out = String::new();
+            for (i, word) in seg.split('_').filter(|w| !w.is_empty()).enumerate() {
+                let lower = word.to_ascii_lowercase();
+                if i == 0 {
+                    out.push_str(&lower);
+                } else {
+                    let mut chars = lower.chars();
+                    if let Some(c) = chars.next() {
+                        out.push(c.to_ascii_uppercase());
+                        out.extend(chars);
+                    }
+                }
+            }
+            out
+        })
+        .collect()
+}
+
+/// Settings from DEVASSISTANT_* variables. Values are read as JSON when they parse
+/// (numbers, booleans, arrays) and as strings otherwise. Names outside the schema are ignored.
+fn env_layer(schema: &Value) -> Value {
+    let mut out = Map::new();
+    for (name, raw) in std::env::vars() {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let path = env_key_path(key);
+        let mut node = schema;
+        let mut known = true;
+        for seg in &path {
+            match node.get(seg) {
+                Some(next) => node = next,
+                None => {
+                    known = false;
+                    break;
+                }
+            }
+        }
+        if !known || node.is_object() || path.first().is_some_and(|p| p == "version") {
+            continue;
+        }
+        let value = serde_json::from_str::<Value>(&raw).unwrap_or(Value::String(raw));
+        let mut patch = Map::new();
+        let mut cursor = &mut patch;
+        for (i, seg) in path.iter().enumerate() {
+            if i + 1 == path.len() {
+                cursor.insert(seg.clone(), value.clone());
+            } else {
+                cursor = cursor
+                    .entry(seg.clone())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .expect("just inserted an object");
+            }
+        }
+        let mut merged = Value::Object(std::mem::take(&mut out));
+        merge(&mut merged, &Value::Object(patch));
+        if let Value::Object(m) = merged {
+            out = m;
+        }
+    }
+    Value::Object(out)
+}
+
+/// Resolve all layers. Invalid layers are skipped and reported in `warnings`.
+pub(crate) fn resolve(workspace_root: Option<&Path>) -> ResolvedSettings {
+    let schema = defaults_value();
+    let mut merged = schema.clone();
+    let mut sources = BTreeMap::new();
+    let mut warnings = Vec::new();
+    let mut paths = Vec::new();
+    leaves(&schema, "", true, &mut paths);
+    for p in paths {
+        sources.insert(p, Layer::Default);
+    }
+
+    let user = user_path();
+    let workspace = workspace_root.map(workspace_path);
+    let mut layers: Vec<(Layer, Value)> = Vec::new();
+    for (layer, path) in [(Layer::User, &user), (Layer::Workspace, &workspace)] {
+        let Some(path) = path else { continue };
+        match read_layer(path) {
+            Ok(Some((obj, migrated))) => {
+                if migrated {
+                    warnings.push(format!(
+                        "{} uses an older settings version; it is upgraded on next save",
+                        path_str(path)
+                    ));
+                }
+                layers.push((layer, Value::Object(obj)));
+            }
+            Ok(None) => {}
+            Err(e) => warnings.push(format!("{} ignored: {}", path_str(path), e)),
+        }
+    }
+    layers.push((Layer::Env, env_layer(&schema)));
+
+    for (layer, mut value) in layers {
+        let name = match layer {
+            Layer::User => "user settings",
+            Layer::Workspace => "workspace settings",
+            _ => "environment settings",
+        };
+        if layer == Layer::Workspace {
+            let dropped: Vec<&str> = USER_ONLY
+                .iter()
+                .copied()
+                .filter(|k| remove_key(&mut value, k))
+                .collect();
+            if !dropped.is_empty() {
+                warnings.push(format!(
+                    "{}: {} can only be set in user settings",
+                    name,
+                    dropped.join(", ")
+                ));
+            }
+        }
+        let mut unknown = Vec::new();
+        unknown_keys(&value, &schema, "", &mut unknown);
+        if !unknown.is_empty() {
+            warnings.push(format!("{}: unknown keys {}", name, unknown.join(", ")));
+        }
+        // Each layer must be valid on its own and on top of the layers below it.
+        let mut candidate = merged.clone();
+        merge(&mut candidate, &value);
+        let check = check_layer(&value).and_then(|_| check_layer(&candidate));
+        if let Err(e) = check {
+            warnings.push(format!("{} ignored: {}", name, e));
+            continue;
+        }
+        merged = candidate;
+        let mut set = Vec::new();
+        leaves(&value, "", false, &mut set);
+        for p in set {
+            if p != "version" && sources.contains_key(&p) {
+                sources.insert(p, layer);
+            }
+        }
+    }
+
+    ResolvedSettings {
+        settings: serde_json::from_value(merged).unwrap_or_default(),
+        sources,
+        warnings,
+        user_path: user.as_deref().map(path_str),
+        workspace_path: workspace.as_deref().map(path_str),
+    }
+}
+
+/// Effective settings for a workspace, or user-level only when there is none.
+pub(crate) fn load(workspace_root: Option<&Path>) -> Settings {
+    resolve(workspace_root).settings
+}
+
+/// Canonical root of an opened workspace (registry::check_read), or None when omitted and
+/// only user-level settings apply.
+fn opened_root(
+    registry: &Mutex<WorkspaceRegistry>,
+    workspace_root: Option<&str>,
+) -> AppResult<Option<PathBuf>> {
+    let Some(root) = workspace_root.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(None);
+    };
+    Ok(Some(registry::check_read(registry, root)?))
+}
+
+/// Effective settings for workspace_root (user-level only when omitted), with the layer each
+/// value came from and any warnings.
+#[tauri::command]
+pub fn settings_get(
+    workspace_root: Option<String>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<ResolvedSettings> {
+    let root = opened_root(&registry, workspace_root.as_deref())?;
+    Ok(resolve(root.as_deref()))
+}
+
+/// Merge `patch` into the user or workspace settings file (null removes a key). The file is
+/// migrated, checked against the schema and ranges, and written only if valid.
+#[tauri::command]
+pub fn settings_set(
+    workspace_root: Option<String>,
+    scope: Layer,
+    patch: Value,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<ResolvedSettings> {
+    let root = opened_root(&registry, workspace_root.as_deref())?;
+    let path = match scope {
+        Layer::User => user_path().ok_or_else(|| {
+            AppError::new(
+                ErrorKind::Unsupported,
+                "no user config directory on this system",
+            )
+        })?,
+        Layer::Workspace => {
+            let root = root
+                .as_deref()
+                .ok_or_else(|| AppError::invalid("workspace_root is required"))?;
+            let path = crate::workspace::resolve(
+                &path_str(root),
+                WORKSPACE_REL,
+                crate::workspace::allow_external_symlinks(),
+            )?;
+            registry::check_write(&registry, &path_str(root), &path)?;
+            path
+        }
+        Layer::Default | Layer::Env => {
+            return Err(AppError::invalid(
+                "only user and workspace settings can be changed",
+            ))
+        }
+    };
+    let Value::Object(patch) = patch else {
+        return Err(AppError::invalid("patch must be a JSON object"));
+    };
+
+    // Unknown keys already in the file are kept (read reports them); new ones are rejected.
+    let mut unknown = Vec::new();
+    unknown_keys(
+        &Value::Object(patch.clone()),
+        &defaults_value(),
+        "",
+        &mut unknown,
+    );
+    if !unknown.is_empty() {
+        return Err(
+            AppError::invalid(format!("unknown settings: {}", unknown.join(", ")))
+                .with("keys", &unknown),
+        );
+    }
+    let mut set = Vec::new();
+    leaves(&Value::Object(patch.clone()), "", true, &mut set);
+    let denied: Vec<&str> = USER_ONLY
+        .iter()
+        .copied()
+        .filter(|k| set.iter().any(|s| s == k || k.starts_with(&format!("{}.", s))))
+        .collect();
+    if !denied.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::PermissionDenied,
+            format!(
+                "{} can only be changed by editing the user settings file",
+                denied.join(", ")
+            ),
+        )
+        .with("keys", &denied));
+    }
+
+    let _guard = WRITE_LOCK
+        .lock()
+        .map_err(|_| AppError::poisoned("settings write"))?;
+    let mut obj = read_layer(&path)
+        .map_err(|e| AppError::new(ErrorKind::Parse, e).with("path", path_str(&path)))?
+        .map(|(o, _)| o)
+        .unwrap_or_default();
+    apply_patch(&mut obj, &patch);
+    obj.insert("version".into(), Value::from(SETTINGS_VERSION));
+    let value = Value::Object(obj);
+    check_layer(&value).map_err(|e| AppError::invalid(format!("invalid settings: {}", e)))?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).at(dir)?;
+    }
+    let text = serde_json::to_string_pretty(&value)
+        .map_err(|e| AppError::new(ErrorKind::Parse, e.to_string()))?;
+    std::fs::write(&path, text).at(&path)?;
+    Ok(resolve(root.as_deref()))
+}
+
+#[derive(Default)]
+pub struct SettingsWatchState {
+    /// Keyed by workspace root ("" for user settings only). Dropping one stops its thread.
+    watchers: HashMap<String, RecommendedWatcher>,
+}
+
+/// Payload of `settings://changed`.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChangedEvent {
+    workspace_root: Option<String>,
+    resolved: ResolvedSettings,
+}
+
+/// Watch the user and workspace settings files; emits `settings://changed` with the newly
+/// resolved settings after each change. Returns false when already watching.
+#[tauri::command]
+pub fn settings_watch(
+    app: AppHandle,
+    workspace_root: Option<String>,
+    state: tauri::State<'_, Mutex<SettingsWatchState>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<bool> {
+    let root = opened_root(&registry, workspace_root.as_deref())?;
+    let key = root.as_deref().map(path_str).unwrap_or_default();
+    let mut s = state
+        .lock()
+        .map_err(|_| AppError::poisoned("settings watch"))?;
+    if s.watchers.contains_key(&key) {
+        return Ok(false);
+    }
+
+    // Watch the containing directories: editors replace files rather than writing in place.
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = user_path().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        dirs.push(dir);
+    }
+    if let Some(r) = root.as_deref() {
+        // Created below when missing, so it must pass the workspace write policy.
+        if let Some(dir) = workspace_path(r).parent() {
+            registry::check_write(&registry, &path_str(r), dir)?;
+            dirs.push(dir.to_path_buf());
+        }
+    }
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })
+    .map_err(|e| AppError::new(ErrorKind::Other, format!("Failed to start watcher: {}", e)))?;
+    for dir in &dirs {
+        std::fs::create_dir_all(dir).at(dir)?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                AppError::new(ErrorKind::Other, e.to_string()).with("path", path_str(dir))
+            })?;
+    }
+
+    let is_settings = |ev: &notify::Event| {
+        ev.paths
+            .iter()
+            .any(|p| p.file_name().is_some_and(|n| n == FILE_NAME))
+    };
+    let thread_root = root.clone();
+    std::thread::Builder::new()
+        .name("settings-watcher".into())
+        .spawn(move || {
+            // Ends when the watcher (and its sender) is dropped.
+            while let Ok(first) = rx.recv() {
+                let mut changed = first.as_ref().is_ok_and(is_settings);
+                loop {
+                    match rx.recv_timeout(WATCH_DEBOUNCE) {
+                        Ok(ev) => changed |= ev.as_ref().is_ok_and(is_settings),
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                if changed {
+                    let _ = app.emit(
+                        CHANGED_EVENT,
+                        SettingsChangedEvent {
+                            workspace_root: thread_root.as_deref().map(path_str),
+                            resolved: resolve(thread_root.as_deref()),
+                        },
+                    );
+                }
+            }
+        })
+        .map_err(|e| AppError::new(ErrorKind::Other, e.to_string()))?;
+
+    s.watchers.insert(key, watcher);
+    Ok(true)
+}
+
+/// Stop a settings watch started by settings_watch. Returns false if there was none.
+#[tauri::command]
+pub fn settings_unwatch(
+    workspace_root: Option<String>,
+    state: tauri::State<'_, Mutex<SettingsWatchState>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<bool> {
+    let key = opened_root(&registry, workspace_root.as_deref())?
+        .as_deref()
+        .map(path_str)
+        .unwrap_or_default();
+    let mut s = state
+        .lock()
+        .map_err(|_| AppError::poisoned("settings watch"))?;
+    Ok(s.watchers.remove(&key).is_some())
+}
@@ -0,0 +1,355 @@
+//! Workspace snapshot: parallel walk (shared ignore rules), persisted under
+//! .devassistant/snapshots/ so later calls can report only what changed.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+
+use chrono::{TimeZone, Utc};
+use ignore::WalkState;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppResult;
+use crate::walker;
+
+const SNAPSHOT_MAX_DEPTH: usize = 26;
+/// Files kept in the persisted index. The command result lists `limits.snapshotMaxFiles`.
+const SNAPSHOT_INDEX_MAX_FILES: usize = 200_000;
+const SNAPSHOT_MAX_FILE_BYTES: u64 = 2 * 1024 * 1024; // 2MB
+const SNAPSHOTS_DIR: &str = ".devassistant/snapshots";
+/// Persisted snapshots kept per workspace; older ones are pruned.
+const SNAPSHOTS_KEEP: usize = 10;
+const STORED_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotFileEntry {
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified_at: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkSnapshotResult {
+    /// Id of the persisted snapshot (pass to workspace_snapshot_changes later); None unless
+    /// `save` was requested.
+    pub snapshot_id: Option<String>,
+    pub total_files: u64,
+    pub total_dirs: u64,
+    pub files: Vec<SnapshotFileEntry>,
+    pub top_level: Vec<String>,
+    /// True when `files` (or the persisted index) hit its cap.
+    pub truncated: bool,
+    /// Files over 2MB, counted in total_files but not listed.
+    pub skipped_large: u64,
+    /// Entries that could not be read (permissions, races).
+    pub skipped_unreadable: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotChangesResult {
+    pub from_id: String,
+    /// Id of the new snapshot when the caller asked to save one.
+    pub to_id: Option<String>,
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Either snapshot hit the index cap, so changes beyond it are not reported.
+    pub truncated: bool,
+}
+
+/// One file in a persisted snapshot. Modification time is kept in ms for exact comparison.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IndexedFile {
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified_ms: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredSnapshot {
+    version: u32,
+    id: String,
+    created_at: String,
+    truncated: bool,
+    files: Vec<IndexedFile>,
+}
+
+enum Found {
+    Dir { rel: String, depth: usize },
+    File { rel: String, depth: usize, size: u64, modified_ms: i64 },
+    Unreadable,
+}
+
+pub(crate) struct Walked {
+    total_files: u64,
+    total_dirs: u64,
+    top_level: Vec<String>,
+    /// Every file, sorted by path.
+    pub files: Vec<IndexedFile>,
+    skipped_unreadable: u64,
+}
+
+pub(crate) fn modified_ms(meta: &std::fs::Metadata) -> i64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn iso_from_ms(ms: i64) -> String {
+    Utc.timestamp_millis_opt(ms)
+        .single()
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+}
+
+/// Parallel walk; one metadata call per file (from the walker's entry).
+pub(crate) fn walk_parallel(root: &Path) -> Result<Walked, String> {
+    let opts = walker::WalkOptions {
+        max_depth: Some(SNAPSHOT_MAX_DEPTH),
+        ..Default::default()
+    };
+    let (tx, rx) = mpsc::channel::<Found>();
+    walker::builder(root, &opts)?.build_parallel().run(|| {
+        let tx = tx.clone();
+        let root = root.to_path_buf();
+        Box::new(move |res| {
+            let found = match res {
+                Ok(e) => {
+                    let Some(rel) = walker::rel_path(&root, e.path()) else {
+                        return WalkState::Continue;
+                    };
+                    match e.file_type() {
+                        Some(ft) if ft.is_dir() => Found::Dir { rel, depth: e.depth() },
+                        Some(ft) if ft.is_file() => match e.metadata() {
+                            Ok(meta) => Found::File {
+                                rel,
+                                depth: e.depth(),
+                                size: meta.len(),
+                                modified_ms: modified_ms(&meta),
+                            },
+                            Err(_) => Found::Unreadable,
+                        },
+                        _ => return WalkState::Continue,
+                    }
+                }
+                Err(_) => Found::Unreadable,
+            };
+            if tx.send(found).is_err() {
+                return WalkState::Quit;
+            }
+            WalkState::Continue
+        })
+    });
+    drop(tx);
+
+    let mut w = Walked {
+        total_files: 0,
+        total_dirs: 0,
+        top_level: Vec::new(),
+        files: Vec::new(),
+        skipped_unreadable: 0,
+    };
+    for found in rx {
+        match found {
+            Found::Dir { rel, depth } => {
+                w.total_dirs += 1;
+                if depth == 1 {
+                    w.top_level.push(rel);
+                }
+            }
+            Found::File { rel, depth, size, modified_ms } => {
+                w.total_files += 1;
+                if depth == 1 {
+                    w.top_level.push(rel.clone());
+                }
+                w.files.push(IndexedFile {
+                    path: rel,
+                    size_bytes: size,
+                    modified_ms,
+                });
+            }
+            Found::Unreadable => w.skipped_unreadable += 1,
+        }
+    }
+    w.top_level.sort();
+    w.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(w)
+}
+
+fn snapshots_dir(root: &Path) -> PathBuf {
+    root.join(SNAPSHOTS_DIR)
+}
+
+/// Ids are UTC timestamps, so lexical order is creation order.
+fn new_snapshot_id() -> String {
+    Utc::now().format("%Y%m%dT%H%M%S%3fZ").to_string()
+}
+
+fn valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Save snap and prune all but the newest SNAPSHOTS_KEEP, never removing `keep` (a baseline
+/// still in use).
+fn persist(root: &Path, snap: &StoredSnapshot, keep: Option<&str>) -> Result<(), String> {
+    let dir = snapshots_dir(root);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_vec(snap).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(format!("{}.json", snap.id)), json).map_err(|e| e.to_string())?;
+
+    let mut ids: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.strip_suffix(".json").map(str::to_string))
+        .filter(|id| valid_id(id) && Some(id.as_str()) != keep)
+        .collect();
+    ids.sort();
+    let excess = ids.len().saturating_sub(SNAPSHOTS_KEEP);
+    for old in &ids[..excess] {
+        let _ = std::fs::remove_file(dir.join(format!("{}.json", old)));
+    }
+    Ok(())
+}
+
+fn load(root: &Path, id: &str) -> Result<StoredSnapshot, String> {
+    if !valid_id(id) {
+        return Err(format!("invalid snapshot id: {}", id));
+    }
+    let path = snapshots_dir(root).join(format!("{}.json", id));
+    let raw = std::fs::read(&path).map_err(|_| format!("snapshot not found: {}", id))?;
+    let snap: StoredSnapshot =
+        serde_json::from_slice(&raw).map_err(|e| format!("snapshot {} unreadable: {}", id, e))?;
+    if snap.version != STORED_VERSION {
+        return Err(format!("snapshot {} has unsupported version {}", id, snap.version));
+    }
+    Ok(snap)
+}
+
+/// Walk and return the index with the walk counts. Not persisted.
+fn take_snapshot(root: &Path) -> Result<(StoredSnapshot, Walked), String> {
+    let mut w = walk_parallel(root)?;
+    let mut files = std::mem::take(&mut w.files);
+    let truncated = files.len() > SNAPSHOT_INDEX_MAX_FILES;
+    files.truncate(SNAPSHOT_INDEX_MAX_FILES);
+    let snap = StoredSnapshot {
+        version: STORED_VERSION,
+        id: new_snapshot_id(),
+        created_at: Utc::now().to_rfc3339(),
+        truncated,
+        files,
+    };
+    Ok((snap, w))
+}
+
+/// Snapshot the workspace (parallel walk, shared ignore rules); with `save` it is also
+/// persisted under .devassistant/snapshots/ as a baseline for workspace_snapshot_changes.
+/// Only saved snapshots count towards SNAPSHOTS_KEEP. Truncation and skipped files are
+/// reported, not silent.
+#[tauri::command]
+pub async fn workspace_walk_snapshot(
+    workspace_root: String,
+    save: Option<bool>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<WalkSnapshotResult> {
+    let root = crate::registry::check_read(&registry, &workspace_root)?;
+    let save = save.unwrap_or(false);
+    if save {
+        crate::registry::check_write(&registry, &workspace_root, &snapshots_dir(&root))?;
+    }
+    crate::error::blocking(move || walk_snapshot(&root, save)).await
+}
+
+fn walk_snapshot(root: &Path, save: bool) -> Result<WalkSnapshotResult, String> {
+    let (snap, walked) = take_snapshot(root)?;
+    if save {
+        persist(root, &snap, None)?;
+    }
+
+    let mut skipped_large = 0u64;
+    let mut files = Vec::new();
+    let mut truncated = snap.truncated;
+    let max_files = crate::settings::load(Some(root)).limits.snapshot_max_files;
+    for f in &snap.files {
+        if f.size_bytes > SNAPSHOT_MAX_FILE_BYTES {
+            skipped_large += 1;
+        } else if files.len() < max_files {
+            files.push(SnapshotFileEntry {
+                path: f.path.clone(),
+                size_bytes: f.size_bytes,
+                modified_at: iso_from_ms(f.modified_ms),
+            });
+        } else {
+            truncated = true;
+        }
+    }
+
+    Ok(WalkSnapshotResult {
+        snapshot_id: save.then_some(snap.id),
+        total_files: walked.total_files,
+        total_dirs: walked.total_dirs,
+        files,
+        top_level: walked.top_level,
+        truncated,
+        skipped_large,
+        skipped_unreadable: walked.skipped_unreadable,
+    })
+}
+
+/// Files added/modified/deleted since `since_id`, compared against a fresh walk. A file
+/// counts as modified when its size or modification time changed. With `save` the walk is
+/// persisted as a new snapshot (its id in toId); `since_id` itself is never pruned here.
+#[tauri::command]
+pub fn workspace_snapshot_changes(
+    workspace_root: String,
+    since_id: String,
+    save: Option<bool>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<SnapshotChangesResult> {
+    let root = crate::registry::check_read(&registry, &workspace_root)?;
+    let save = save.unwrap_or(false);
+    if save {
+        crate::registry::check_write(&registry, &workspace_root, &snapshots_dir(&root))?;
+    }
+    let before = load(&root, since_id.trim())?;
+    let (after, _) = take_snapshot(&root)?;
+    if save {
+        persist(&root, &after, Some(&before.id))?;
+    }
+
+    let old: HashMap<&str, &IndexedFile> =
+        before.files.iter().map(|f| (f.path.as_str(), f)).collect();
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for f in &after.files {
+        match old.get(f.path.as_str()) {
+            None => added.push(f.path.clone()),
+            Some(o) if o.size_bytes != f.size_bytes || o.modified_ms != f.modified_ms => {
+                modified.push(f.path.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    let current: HashSet<&str> = after.files.iter().map(|f| f.path.as_str()).collect();
+    let deleted = before
+        .files
+        .iter()
+        .filter(|f| !current.contains(f.path.as_str()))
+        .map(|f| f.path.clone())
+        .collect();
+
+    Ok(SnapshotChangesResult {
+        from_id: before.id,
+        to_id: save.then_some(after.id),
+        added,
+        modified,
+        deleted,
+        truncated: before.truncated || after.truncated,
+    })
+}
@@ -0,0 +1,801 @@
+//! Symbol index: tree-sitter outlines (functions, methods, types, exports with line ranges)
+//! for Rust, TypeScript/JavaScript, Python, Go and Java. Persisted under
+//! .devassistant/symbols/ and refreshed incrementally by file size and mtime.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tree_sitter::{Language, Node, Parser};
+
+use crate::error::{AppError, AppResult, IoResultExt};
+use crate::snapshot::modified_ms;
+use crate::walker;
+use crate::workspace::{allow_external_symlinks, canonical_root, resolve};
+
+const INDEX_REL: &str = ".devassistant/symbols/index.json";
+const INDEX_VERSION: u32 = 1;
+/// Larger files are recorded with no symbols (generated or vendored code).
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_FILES: usize = 50_000;
+const WALK_MAX_DEPTH: usize = 26;
+/// Queries within this window reuse the last refresh instead of re-statting the tree.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_TREE_DEPTH: usize = 200;
+const SIGNATURE_MAX_CHARS: usize = 200;
+const FIND_DEFAULT_LIMIT: usize = 50;
+const FIND_MAX_LIMIT: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Trait,
+    Interface,
+    Impl,
+    Module,
+    Type,
+    Constant,
+}
+
+impl SymbolKind {
+    /// Functions declared directly inside these become methods.
+    fn is_type(self) -> bool {
+        matches!(
+            self,
+            Self::Class | Self::Struct | Self::Enum | Self::Trait | Self::Interface | Self::Impl
+        )
+    }
+
+    /// Symbols whose bodies are searched for nested symbols.
+    fn is_container(self) -> bool {
+        self.is_type() || self == Self::Module
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Root-relative, forward slashes.
+    pub path: String,
+    /// 1-based, inclusive.
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Enclosing class, impl, trait or module, if any.
+    pub container: Option<String>,
+    /// Public API: exported (JS/TS), `pub` (Rust), capitalized (Go), public (Java),
+    /// no leading underscore (Python).
+    pub exported: bool,
+    /// Declaration up to its body, whitespace collapsed.
+    pub signature: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Lang {
+    Rust,
+    TypeScript,
+    Tsx,
+    JavaScript,
+    Python,
+    Go,
+    Java,
+}
+
+impl Lang {
+    fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        Some(match ext.as_str() {
+            "rs" => Self::Rust,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "js" | "mjs" | "cjs" | "jsx" => Self::JavaScript,
+            "py" | "pyi" => Self::Python,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            _ => return None,
+        })
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Java => tree_sitter_java::LANGUAGE.into(),
+        }
+    }
+}
+
+/// True if the file's extension has a grammar.
+pub(crate) fn is_supported(path: &str) -> bool {
+    Lang::from_path(path).is_some()
+}
+
+struct Found<'t> {
+    kind: SymbolKind,
+    name: String,
+    /// Container name passed to nested symbols (e.g. the impl's type, without generics).
+    scope: String,
+    exported: bool,
+    /// Overrides the lexical parent as container (Go methods belong to their receiver).
+    container: Option<String>,
+    /// Node whose span is reported (e.g. the whole `const x = () => ...` declaration).
+    range: Node<'t>,
+}
+
+fn text<'a>(n: Node, src: &'a [u8]) -> &'a str {
+    n.utf8_text(src).unwrap_or("")
+}
+
+fn field_text<'a>(n: Node, field: &str, src: &'a [u8]) -> Option<&'a str> {
+    n.child_by_field_name(field)
+        .map(|c| text(c, src))
+        .filter(|s| !s.is_empty())
+}
+
+fn has_child_kind(n: Node, kind: &str) -> bool {
+    let mut c = n.walk();
+    let found = n.named_children(&mut c).any(|ch| ch.kind() == kind);
+    found
+}
+
+fn first_descendant<'t>(n: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut stack = vec![n];
+    while let Some(cur) = stack.pop() {
+        if cur.kind() == kind {
+            return Some(cur);
+        }
+        let mut c = cur.walk();
+        let mut kids: Vec<Node> = cur.named_children(&mut c).collect();
+        kids.reverse();
+        stack.extend(kids);
+    }
+    None
+}
+
+fn strip_generics(s: &str) -> String {
+    s.split('<').next().unwrap_or(s).trim().to_string()
+}
+
+fn found<'t>(kind: SymbolKind, name: &str, exported: bool, range: Node<'t>) -> Option<Found<'t>> {
+    Some(Found {
+        kind,
+        name: name.to_string(),
+        scope: name.to_string(),
+        exported,
+        container: None,
+        range,
+    })
+}
+
+fn classify_rust<'t>(n: Node<'t>, src: &[u8]) -> Option<Found<'t>> {
+    let kind = match n.kind() {
+        "function_item" | "function_signature_item" => SymbolKind::Function,
+        "struct_item" | "union_item" => SymbolKind::Struct,
+        "enum_item" => SymbolKind::Enum,
+        "trait_item" => SymbolKind::Trait,
+        "mod_item" => SymbolKind::Module,
+        "type_item" => SymbolKind::Type,
+        "const_item" | "static_item" => SymbolKind::Constant,
+        "impl_item" => {
+            let ty = field_text(n, "type", src)?;
+            let name = match field_text(n, "trait", src) {
+                Some(tr) => format!("{} for {}", tr, ty),
+                None => ty.to_string(),
+            };
+            return Some(Found {
+                kind: SymbolKind::Impl,
+                name,
+                scope: strip_generics(ty),
+                exported: false,
+                container: None,
+                range: n,
+            });
+        }
+        _ => return None,
+    };
+    let name = field_text(n, "name", src)?;
+    found(kind, name, has_child_kind(n, "visibility_modifier"), n)
+}
+
+fn js_exported(n: Node) -> bool {
+    let mut p = n.parent();
+    // declarator -> lexical_declaration -> export_statement
+    for _ in 0..2 {
+        match p {
+            Some(x) if x.kind() == "export_statement" => return true,
+            Some(x) if x.kind() == "lexical_declaration" || x.kind() == "variable_declaration" => {
+                p = x.parent()
+            }
+            _ => return false,
+        }
+    }
+    false
+}
+
+fn js_member_public(n: Node, src: &[u8]) -> bool {
+    let mut c = n.walk();
+    let private = n.children(&mut c).any(|ch| {
+        ch.kind() == "accessibility_modifier" && text(ch, src) != "public"
+            || ch.kind() == "private_property_identifier"
+    });
+    !private
+}
+
+fn classify_js<'t>(n: Node<'t>, src: &[u8]) -> Option<Found<'t>> {
+    let kind = match n.kind() {
+        "function_declaration" | "generator_function_declaration" | "function_signature" => {
+            SymbolKind::Function
+        }
+        "class_declaration" | "abstract_class_declaration" => SymbolKind::Class,
+        "interface_declaration" => SymbolKind::Interface,
+        "type_alias_declaration" => SymbolKind::Type,
+        "enum_declaration" => SymbolKind::Enum,
+        "internal_module" | "module" => SymbolKind::Module,
+        "method_definition" | "method_signature" | "abstract_method_signature" => {
+            let name = field_text(n, "name", src)?;
+            return found(SymbolKind::Method, name, js_member_public(n, src), n);
+        }
+        "public_field_definition" | "field_definition" => {
+            let value = n.child_by_field_name("value")?;
+            if !matches!(value.kind(), "arrow_function" | "function_expression" | "function") {
+                return None;
+            }
+            let name = field_text(n, "name", src).or_else(|| field_text(n, "property", src))?;
+            return found(SymbolKind::Method, name, js_member_public(n, src), n);
+        }
+        "variable_declarator" => {
+            let name_node = n.child_by_field_name("name")?;
+            if name_node.kind() != "identifier" {
+                return None;
+            }
+            let exported = js_exported(n);
+            let is_fn = n.child_by_field_name("value").is_some_and(|v| {
+                matches!(
+                    v.kind(),
+                    "arrow_function" | "function_expression" | "function" | "generator_function"
+                )
+            });
+            let kind = match (is_fn, exported) {
+                (true, _) => SymbolKind::Function,
+                (false, true) => SymbolKind::Constant,
+                (false, false) => return None,
+            };
+            let range = n.parent().unwrap_or(n);
+            return found(kind, text(name_node, src), exported, range);
+        }
+        _ => return None,
+    };
+    let exported = n.parent().is_some_and(|p| p.kind() == "export_statement");
+    // `export default function () {}` has no name.
+    let name = field_text(n, "name", src).unwrap_or("default");
+    found(kind, name, exported, n)
+}
+
+fn classify_python<'t>(n: Node<'t>, src: &[u8]) -> Option<Found<'t>> {
+    let kind = match n.kind() {
+        "function_definition" => SymbolKind::Function,
+        "class_definition" => SymbolKind::Class,
+        _ => return None,
+    };
+    let name = field_text(n, "name", src)?;
+    let dunder = name.starts_with("__") && name.ends_with("__");
+    found(kind, name, dunder || !name.starts_with('_'), n)
+}
+
+fn classify_go<'t>(n: Node<'t>, src: &[u8]) -> Option<Found<'t>> {
+    let go_exported = |name: &str| name.chars().next().is_some_and(char::is_uppercase);
+    match n.kind() {
+        "function_declaration" => {
+            let name = field_text(n, "name", src)?;
+            found(SymbolKind::Function, name, go_exported(name), n)
+        }
+        "method_declaration" => {
+            let name = field_text(n, "name", src)?;
+            let receiver = n
+                .child_by_field_name("receiver")
+                .and_then(|r| first_descendant(r, "type_identifier"))
+                .map(|t| text(t, src).to_string());
+            Some(Found {
+                kind: SymbolKind::Method,
+                name: name.to_string(),
+                scope: name.to_string(),
+                exported: go_exported(name),
+                container: receiver,
+                range: n,
+            })
+        }
+        "type_spec" => {
+            let name = field_text(n, "name", src)?;
+            let kind = match n.child_by_field_name("type").map(|t| t.kind()) {
+                Some("struct_type") => SymbolKind::Struct,
+                Some("interface_type") => SymbolKind::Interface,
+                _ => SymbolKind::Type,
+            };
+            // Report `type X struct {...}` rather than just the spec when it stands alone.
+            let range = n
+                .parent()
+                .filter(|p| p.kind() == "type_declaration" && p.named_child_count() == 1)
+                .unwrap_or(n);
+            found(kind, name, go_exported(name), range)
+        }
+        _ => None,
+    }
+}
+
+fn classify_java<'t>(n: Node<'t>, src: &[u8]) -> Option<Found<'t>> {
+    let kind = match n.kind() {
+        "class_declaration" | "record_declaration" => SymbolKind::Class,
+        "interface_declaration" | "annotation_type_declaration" => SymbolKind::Interface,
+        "enum_declaration" => SymbolKind::Enum,
+        "method_declaration" | "constructor_declaration" => SymbolKind::Method,
+        _ => return None,
+    };
+    let name = field_text(n, "name", src)?;
+    let mut c = n.walk();
+    let public = n
+        .named_children(&mut c)
+        .any(|ch| ch.kind() == "modifiers" && text(ch, src).split_whitespace().any(|m| m == "public"));
+    found(kind, name, public, n)
+}
+
+fn classify<'t>(lang: Lang, n: Node<'t>, src: &[u8]) -> Option<Found<'t>> {
+    match lang {
+        Lang::Rust => classify_rust(n, src),
+        Lang::TypeScript | Lang::Tsx | Lang::JavaScript => classify_js(n, src),
+        Lang::Python => classify_python(n, src),
+        Lang::Go => classify_go(n, src),
+        Lang::Java => classify_java(n, src),
+    }
+}
+
+/// Declaration text before the body (or its first line), whitespace collapsed.
+fn signature(n: Node, src: &[u8]) -> String {
+    let end = n
+        .child_by_field_name("body")
+        .map(|b| b.start_byte())
+        .unwrap_or(n.end_byte());
+    let raw = String::from_utf8_lossy(&src[n.start_byte()..end.max(n.start_byte())]);
+    let head = if n.child_by_field_name("body").is_some() {
+        raw.as_ref()
+    } else {
+        raw.lines().next().unwrap_or("")
+    };
+    let sig = head.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sig = sig.trim_end_matches(['{', ':']).trim_end().to_string();
+    match sig.char_indices().nth(SIGNATURE_MAX_CHARS) {
+        Some((i, _)) => format!("{}…", &sig[..i]),
+        None => sig,
+    }
+}
+
+fn visit(
+    lang: Lang,
+    node: Node,
+    src: &[u8],
+    rel: &str,
+    parent: Option<(&str, SymbolKind)>,
+    depth: usize,
+    out: &mut Vec<Symbol>,
+) {
+    if depth > MAX_TREE_DEPTH {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some(f) = classify(lang, child, src) else {
+            visit(lang, child, src, rel, parent, depth + 1, out);
+            continue;
+        };
+        let kind = match (f.kind, parent) {
+            (SymbolKind::Function, Some((_, pk))) if pk.is_type() => SymbolKind::Method,
+            (k, _) => k,
+        };
+        out.push(Symbol {
+            name: f.name,
+            kind,
+            path: rel.to_string(),
+            start_line: f.range.start_position().row as u32 + 1,
+            end_line: f.range.end_position().row as u32 + 1,
+            container: f
+                .container
+                .or_else(|| parent.map(|(name, _)| name.to_string()))
+                .filter(|s| !s.is_empty()),
+            exported: f.exported,
+            signature: signature(f.range, src),
+        });
+        // Function bodies are not searched: locals and closures are noise for an outline.
+        if kind.is_container() {
+            visit(lang, child, src, rel, Some((&f.scope, kind)), depth + 1, out);
+        }
+    }
+}
+
+/// Symbols in one source file, in source order. Empty for unsupported or unparsable files.
+pub(crate) fn extract(rel: &str, src: &[u8]) -> Vec<Symbol> {
+    let Some(lang) = Lang::from_path(rel) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&lang.language()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(src, None) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    visit(lang, tree.root_node(), src, rel, None, 0, &mut out);
+    out
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IndexedFile {
+    size_bytes: u64,
+    modified_ms: i64,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoredIndex {
+    version: u32,
+    files: BTreeMap<String, IndexedFile>,
+}
+
+struct LoadedIndex {
+    index: StoredIndex,
+    refreshed: Option<Instant>,
+    /// A refresh is walking and parsing outside the lock; other callers use the index as is.
+    refreshing: bool,
+    /// Single-file updates not yet on disk; saved with the next refresh.
+    dirty: bool,
+}
+
+#[derive(Default)]
+pub struct SymbolIndexState {
+    roots: HashMap<PathBuf, LoadedIndex>,
+}
+
+struct Candidate {
+    rel: String,
+    abs: PathBuf,
+    size: u64,
+    modified_ms: i64,
+}
+
+fn index_file(c: &Candidate) -> IndexedFile {
+    let symbols = if c.size > MAX_FILE_BYTES {
+        Vec::new()
+    } else {
+        std::fs::read(&c.abs)
+            .map(|src| extract(&c.rel, &src))
+            .unwrap_or_default()
+    };
+    IndexedFile {
+        size_bytes: c.size,
+        modified_ms: c.modified_ms,
+        symbols,
+    }
+}
+
+fn load(root: &Path) -> StoredIndex {
+    std::fs::read(root.join(INDEX_REL))
+        .ok()
+        .and_then(|raw| serde_json::from_slice::<StoredIndex>(&raw).ok())
+        .filter(|i| i.version == INDEX_VERSION)
+        .unwrap_or(StoredIndex {
+            version: INDEX_VERSION,
+            files: BTreeMap::new(),
+        })
+}
+
+fn persist(root: &Path, json: &[u8]) -> Result<(), String> {
+    let path = root.join(INDEX_REL);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // Write-then-rename so a crash never leaves a half-written index.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+/// Size and mtime of every indexed file, so a refresh can run without the index.
+type Stamps = HashMap<String, (u64, i64)>;
+
+fn stamps(index: &StoredIndex) -> Stamps {
+    index
+        .files
+        .iter()
+        .map(|(rel, f)| (rel.clone(), (f.size_bytes, f.modified_ms)))
+        .collect()
+}
+
+/// Files still present and the ones re-parsed because their size or mtime changed.
+struct Scanned {
+    seen: HashSet<String>,
+    parsed: Vec<(String, IndexedFile)>,
+}
+
+/// Walk the workspace and parse changed files. Runs without the state lock.
+fn scan(root: &Path, stamps: &Stamps) -> Result<Scanned, String> {
+    let opts = walker::WalkOptions {
+        max_depth: Some(WALK_MAX_DEPTH),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut changed = Vec::new();
+    for e in walker::walk(root, &opts)?
+        .filter(|e| !e.is_dir && is_supported(&e.rel_path))
+        .take(MAX_FILES)
+    {
+        let Ok(meta) = std::fs::metadata(&e.abs_path) else {
+            continue;
+        };
+        let (size, mtime) = (meta.len(), modified_ms(&meta));
+        let fresh = stamps.get(&e.rel_path) == Some(&(size, mtime));
+        seen.insert(e.rel_path.clone());
+        if !fresh {
+            changed.push(Candidate {
+                rel: e.rel_path,
+                abs: e.abs_path,
+                size,
+                modified_ms: mtime,
+            });
+        }
+    }
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8);
+    let chunk = changed.len().div_ceil(threads).max(1);
+    let parsed: Vec<IndexedFile> = std::thread::scope(|s| {
+        let handles: Vec<_> = changed
+            .chunks(chunk)
+            .map(|part| s.spawn(move || part.iter().map(index_file).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_default())
+            .collect()
+    });
+    Ok(Scanned {
+        seen,
+        parsed: changed.into_iter().map(|c| c.rel).zip(parsed).collect(),
+    })
+}
+
+/// Swap scanned results into the index; true when anything changed.
+fn apply(index: &mut StoredIndex, scanned: Scanned) -> bool {
+    let before = index.files.len();
+    index.files.retain(|path, _| scanned.seen.contains(path));
+    let changed = before != index.files.len() || !scanned.parsed.is_empty();
+    index.files.extend(scanned.parsed);
+    changed
+}
+
+fn lock_state(
+    state: &Mutex<SymbolIndexState>,
+) -> Result<std::sync::MutexGuard<'_, SymbolIndexState>, String> {
+    state.lock().map_err(|e| e.to_string())
+}
+
+/// root's index, loading it from disk (outside the lock) the first time.
+fn lock_loaded<'a>(
+    state: &'a Mutex<SymbolIndexState>,
+    root: &Path,
+) -> Result<std::sync::MutexGuard<'a, SymbolIndexState>, String> {
+    if !lock_state(state)?.roots.contains_key(root) {
+        let index = load(root);
+        lock_state(state)?
+            .roots
+            .entry(root.to_path_buf())
+            .or_insert(LoadedIndex {
+                index,
+                refreshed: None,
+                refreshing: false,
+                dirty: false,
+            });
+    }
+    lock_state(state)
+}
+
+fn entry<'a>(s: &'a mut SymbolIndexState, root: &Path) -> Result<&'a mut LoadedIndex, String> {
+    s.roots
+        .get_mut(root)
+        .ok_or_else(|| "symbol index not loaded".to_string())
+}
+
+/// Run f against root's index, refreshing it first unless it was refreshed recently.
+/// The walk and parse run without the lock, so other symbol commands are not blocked;
+/// the index is saved once per refresh.
+pub(crate) fn with_index<R>(
+    state: &Mutex<SymbolIndexState>,
+    root: &Path,
+    f: impl FnOnce(&BTreeMap<String, IndexedFile>) -> R,
+) -> Result<R, String> {
+    let stale = {
+        let mut s = lock_loaded(state, root)?;
+        let loaded = entry(&mut s, root)?;
+        let due = loaded.refreshed.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL);
+        if due && !loaded.refreshing {
+            loaded.refreshing = true;
+            Some(stamps(&loaded.index))
+        } else {
+            None
+        }
+    };
+    let scanned = stale.map(|stamps| scan(root, &stamps));
+
+    let (result, json) = {
+        let mut s = lock_state(state)?;
+        let loaded = entry(&mut s, root)?;
+        let mut json = None;
+        if let Some(scanned) = scanned {
+            loaded.refreshing = false;
+            let changed = apply(&mut loaded.index, scanned?);
+            loaded.refreshed = Some(Instant::now());
+            if changed | std::mem::take(&mut loaded.dirty) {
+                json = Some(serde_json::to_vec(&loaded.index).map_err(|e| e.to_string())?);
+            }
+        }
+        (f(&loaded.index.files), json)
+    };
+    if let Some(json) = json {
+        persist(root, &json)?;
+    }
+    Ok(result)
+}
+
+/// Symbols of one file, re-parsing it (outside the lock) if it changed since it was indexed.
+fn file_symbols(
+    state: &Mutex<SymbolIndexState>,
+    workspace_root: &str,
+    path: &str,
+) -> AppResult<Vec<Symbol>> {
+    let root = canonical_root(workspace_root)?;
+    let full = resolve(workspace_root, path, allow_external_symlinks())?;
+    let rel = walker::rel_path(&root, &full)
+        .ok_or_else(|| AppError::invalid("path is the workspace root"))?;
+    if !is_supported(&rel) {
+        return Ok(Vec::new());
+    }
+    let meta = std::fs::metadata(&full).at(&full)?;
+    let candidate = Candidate {
+        rel,
+        abs: full,
+        size: meta.len(),
+        modified_ms: modified_ms(&meta),
+    };
+
+    {
+        let mut s = lock_loaded(state, &root)?;
+        let hit = entry(&mut s, &root)?.index.files.get(&candidate.rel).filter(|f| {
+            f.size_bytes == candidate.size && f.modified_ms == candidate.modified_ms
+        });
+        if let Some(f) = hit {
+            return Ok(f.symbols.clone());
+        }
+    }
+    let f = index_file(&candidate);
+    let symbols = f.symbols.clone();
+    let mut s = lock_state(state)?;
+    let loaded = entry(&mut s, &root)?;
+    loaded.index.files.insert(candidate.rel, f);
+    loaded.dirty = true;
+    Ok(symbols)
+}
+
+/// Find symbols by name across the workspace: exact match first, then case-insensitive
+/// exact, prefix and substring; public symbols before private ones. Optional kind filter.
+#[tauri::command]
+pub async fn workspace_find_symbol(
+    app: AppHandle,
+    workspace_root: String,
+    query: String,
+    kind: Option<SymbolKind>,
+    limit: Option<usize>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<Vec<Symbol>> {
+    let root = crate::registry::check_read(&registry, &workspace_root)?;
+    crate::error::blocking(move || {
+        let state = app.state::<Mutex<SymbolIndexState>>();
+        find_symbol(state.inner(), &root, &query, kind, limit)
+    })
+    .await
+}
+
+fn find_symbol(
+    state: &Mutex<SymbolIndexState>,
+    root: &Path,
+    query: &str,
+    kind: Option<SymbolKind>,
+    limit: Option<usize>,
+) -> Result<Vec<Symbol>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(FIND_DEFAULT_LIMIT).min(FIND_MAX_LIMIT);
+    let q_lower = query.to_lowercase();
+    let mut hits = with_index(state, root, |files| {
+        let mut hits: Vec<(u8, Symbol)> = Vec::new();
+        for s in files.values().flat_map(|f| &f.symbols) {
+            if kind.is_some_and(|k| k != s.kind) {
+                continue;
+            }
+            let name_lower = s.name.to_lowercase();
+            let tier = if s.name == query {
+                0
+            } else if name_lower == q_lower {
+                1
+            } else if name_lower.starts_with(&q_lower) {
+                2
+            } else if name_lower.contains(&q_lower) {
+                3
+            } else {
+                continue;
+            };
+            hits.push((tier, s.clone()));
+        }
+        hits
+    })?;
+    hits.sort_by(|(ta, a), (tb, b)| {
+        ta.cmp(tb)
+            .then(b.exported.cmp(&a.exported))
+            .then(a.path.cmp(&b.path))
+            .then(a.start_line.cmp(&b.start_line))
+    });
+    Ok(hits.into_iter().take(limit).map(|(_, s)| s).collect())
+}
+
+/// Outline of one file in source order (empty for languages without a grammar).
+#[tauri::command]
+pub async fn workspace_symbols_in_file(
+    app: AppHandle,
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<Vec<Symbol>> {
+    crate::registry::check_read(&registry, &workspace_root)?;
+    crate::error::blocking(move || {
+        let state = app.state::<Mutex<SymbolIndexState>>();
+        file_symbols(state.inner(), &workspace_root, &path)
+    })
+    .await
+}
+
+/// Innermost symbol whose line range contains `line` (1-based), e.g. the method around
+/// a diagnostic, so callers can quote the whole function.
+#[tauri::command]
+pub async fn workspace_enclosing_symbol(
+    app: AppHandle,
+    workspace_root: String,
+    path: String,
+    line: u32,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<Option<Symbol>> {
+    crate::registry::check_read(&registry, &workspace_root)?;
+    let symbols = crate::error::blocking(move || {
+        let state = app.state::<Mutex<SymbolIndexState>>();
+        file_symbols(state.inner(), &workspace_root, &path)
+    })
+    .await?;
+    Ok(symbols
+        .into_iter()
+        .filter(|s| s.start_line <= line && line <= s.end_line)
+        .min_by_key(|s| s.end_line - s.start_line))
+}
@@ -0,0 +1,579 @@
+//! Text file decoding/encoding: binary sniffing, encoding detection (BOM, BOM-less UTF-16
+//! by its NUL pattern, UTF-8, Latin-1 fallback) and line-ending detection, so rewrites keep a file's style.
+//! Also ranged/size-capped reads so large files never have to be loaded whole.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Bytes inspected when deciding whether a file is binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-8-bom")]
+    Utf8Bom,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// UTF-16 without a BOM, recognized by its NUL-byte pattern; written back without one.
+    #[serde(rename = "utf-16le-nobom")]
+    Utf16LeNoBom,
+    #[serde(rename = "utf-16be-nobom")]
+    Utf16BeNoBom,
+    #[serde(rename = "latin-1")]
+    Latin1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    /// More than one style present.
+    Mixed,
+    /// No line breaks at all.
+    None,
+}
+
+impl LineEnding {
+    fn as_str(self) -> Option<&'static str> {
+        match self {
+            LineEnding::Lf => Some("\n"),
+            LineEnding::Crlf => Some("\r\n"),
+            LineEnding::Cr => Some("\r"),
+            LineEnding::Mixed | LineEnding::None => None,
+        }
+    }
+}
+
+/// Result of decoding raw file bytes. `text` is None for binary files.
+pub struct Decoded {
+    pub text: Option<String>,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub trailing_newline: bool,
+}
+
+/// NUL bytes in the first few KB mark a file as binary (UTF-16 is handled by BOM first).
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    let n = bytes.len().min(BINARY_SNIFF_BYTES);
+    bytes[..n].contains(&0)
+}
+
+/// BOM-less UTF-16: NULs only ever in the high byte of a code unit, and in at least half of
+/// them (mostly-ASCII text). Non-Latin text without a BOM still reads as binary.
+fn sniff_utf16(bytes: &[u8]) -> Option<TextEncoding> {
+    let n = bytes.len().min(BINARY_SNIFF_BYTES) / 2 * 2;
+    if n < 4 {
+        return None;
+    }
+    let (mut le, mut be) = (0usize, 0usize);
+    for u in bytes[..n].chunks_exact(2) {
+        match (u[0], u[1]) {
+            (0, 0) => return None,
+            (_, 0) => le += 1,
+            (0, _) => be += 1,
+            _ => {}
+        }
+    }
+    let half = n / 4;
+    match (le, be) {
+        (le, 0) if le >= half.max(1) => Some(TextEncoding::Utf16LeNoBom),
+        (0, be) if be >= half.max(1) => Some(TextEncoding::Utf16BeNoBom),
+        _ => None,
+    }
+}
+
+/// What the first bytes of a file say about it. `fixed_encoding` is set when a BOM or the
+/// UTF-16 NUL pattern fixes the encoding; otherwise UTF-8 vs Latin-1 is decided per slice.
+pub struct Sniff {
+    pub fixed_encoding: Option<TextEncoding>,
+    pub bom_len: u64,
+    pub binary: bool,
+}
+
+fn is_utf16_le(enc: Option<TextEncoding>) -> bool {
+    matches!(enc, Some(TextEncoding::Utf16Le | TextEncoding::Utf16LeNoBom))
+}
+
+fn is_utf16_be(enc: Option<TextEncoding>) -> bool {
+    matches!(enc, Some(TextEncoding::Utf16Be | TextEncoding::Utf16BeNoBom))
+}
+
+impl Sniff {
+    /// Code unit width in bytes (2 for UTF-16).
+    fn unit(&self) -> usize {
+        if is_utf16_le(self.fixed_encoding) || is_utf16_be(self.fixed_encoding) {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn is_newline(&self, unit: &[u8]) -> bool {
+        if is_utf16_le(self.fixed_encoding) {
+            unit == [b'\n', 0]
+        } else if is_utf16_be(self.fixed_encoding) {
+            unit == [0, b'\n']
+        } else {
+            unit[0] == b'\n'
+        }
+    }
+}
+
+pub fn sniff(head: &[u8]) -> Sniff {
+    let (bom_encoding, bom_len) = if head.starts_with(UTF8_BOM) {
+        (Some(TextEncoding::Utf8Bom), UTF8_BOM.len())
+    } else if head.starts_with(UTF16LE_BOM) {
+        (Some(TextEncoding::Utf16Le), UTF16LE_BOM.len())
+    } else if head.starts_with(UTF16BE_BOM) {
+        (Some(TextEncoding::Utf16Be), UTF16BE_BOM.len())
+    } else {
+        (None, 0)
+    };
+    let mut binary = bom_encoding.is_none() && looks_binary(head);
+    let fixed_encoding = match bom_encoding {
+        None if binary => sniff_utf16(head),
+        enc => enc,
+    };
+    binary &= fixed_encoding.is_none();
+    Sniff {
+        fixed_encoding,
+        bom_len: bom_len as u64,
+        binary,
+    }
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| {
+            if little_endian {
+                u16::from_le_bytes([c[0], c[1]])
+            } else {
+                u16::from_be_bytes([c[0], c[1]])
+            }
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Decode bytes after any BOM. `fixed_encoding` None means strict UTF-8, else Latin-1,
+/// which maps every byte to a char so decoding never fails.
+fn decode_body(bytes: &[u8], fixed_encoding: Option<TextEncoding>) -> (String, TextEncoding) {
+    match fixed_encoding {
+        Some(enc) if is_utf16_le(Some(enc)) => (decode_utf16(bytes, true), enc),
+        Some(enc) if is_utf16_be(Some(enc)) => (decode_utf16(bytes, false), enc),
+        Some(enc) => (String::from_utf8_lossy(bytes).into_owned(), enc),
+        None => match std::str::from_utf8(bytes) {
+            Ok(s) => (s.to_string(), TextEncoding::Utf8),
+            Err(_) => (bytes.iter().map(|&b| b as char).collect(), TextEncoding::Latin1),
+        },
+    }
+}
+
+fn decoded_text(text: String, encoding: TextEncoding) -> Decoded {
+    Decoded {
+        line_ending: detect_line_ending(&text),
+        trailing_newline: text.ends_with('\n') || text.ends_with('\r'),
+        text: Some(text),
+        encoding,
+    }
+}
+
+fn decoded_binary() -> Decoded {
+    Decoded {
+        text: None,
+        encoding: TextEncoding::Utf8,
+        line_ending: LineEnding::None,
+        trailing_newline: false,
+    }
+}
+
+/// Detect encoding and decode a whole file's bytes.
+pub fn decode(bytes: &[u8]) -> Decoded {
+    let s = sniff(bytes);
+    if s.binary {
+        return decoded_binary();
+    }
+    let (text, encoding) = decode_body(&bytes[s.bom_len as usize..], s.fixed_encoding);
+    decoded_text(text, encoding)
+}
+
+// --- Ranged reads ---
+
+const SCAN_CHUNK: usize = 64 * 1024;
+
+/// Which part of a file to read. Byte offsets are absolute, end exclusive;
+/// lines are 1-based, end inclusive.
+pub enum ReadRange {
+    Whole,
+    Bytes(u64, Option<u64>),
+    Lines(u64, Option<u64>),
+}
+
+/// Which end of the range survives when it exceeds max_bytes.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CutMode {
+    #[default]
+    Head,
+    Tail,
+}
+
+pub struct Window {
+    pub decoded: Decoded,
+    pub byte_start: u64,
+    pub byte_end: u64,
+    pub truncated: bool,
+}
+
+/// Fill buf from the reader until full or EOF. Returns bytes read.
+fn read_full(r: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match r.read(&mut buf[n..])? {
+            0 => break,
+            k => n += k,
+        }
+    }
+    Ok(n)
+}
+
+/// Byte span covering lines first..=last (1-based), including the last line's newline.
+fn line_span(
+    file: &mut File,
+    sniff: &Sniff,
+    size: u64,
+    first: u64,
+    last: Option<u64>,
+) -> std::io::Result<(u64, u64)> {
+    let unit = sniff.unit();
+    let first = first.max(1);
+    let mut line = 1u64;
+    let mut start = if first == 1 { Some(sniff.bom_len) } else { None };
+    if last.is_some_and(|l| l < first) {
+        let at = start.unwrap_or(size);
+        return Ok((at, at));
+    }
+    file.seek(SeekFrom::Start(sniff.bom_len))?;
+    let mut pos = sniff.bom_len;
+    let mut buf = vec![0u8; SCAN_CHUNK];
+    loop {
+        let n = read_full(file, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for (i, u) in buf[..n].chunks_exact(unit).enumerate() {
+            if !sniff.is_newline(u) {
+                continue;
+            }
+            let after = pos + ((i + 1) * unit) as u64;
+            line += 1;
+            if line == first {
+                start = Some(after);
+            }
+            if last.is_some_and(|l| line == l + 1) {
+                return Ok((start.unwrap_or(after), after));
+            }
+        }
+        pos += n as u64;
+    }
+    Ok((start.unwrap_or(size), size))
+}
+
+/// Position in buf to cut at so no line (or, failing that, no char) is split.
+/// Head keeps buf[..cut]; tail keeps buf[cut..].
+fn snap_cut(buf: &[u8], sniff: &Sniff, mode: CutMode) -> usize {
+    let unit = sniff.unit();
+    let units = buf.len() / unit;
+    let newline_at = |k: usize| sniff.is_newline(&buf[k * unit..(k + 1) * unit]);
+    let by_line = match mode {
+        CutMode::Head => (0..units).rev().find(|&k| newline_at(k)).map(|k| (k + 1) * unit),
+        CutMode::Tail => (0..units).find(|&k| newline_at(k)).map(|k| (k + 1) * unit),
+    };
+    if let Some(cut) = by_line.filter(|&c| c > 0 && c < buf.len()) {
+        return cut;
+    }
+    if unit == 2 {
+        return match mode {
+            CutMode::Head => units * unit,
+            CutMode::Tail => buf.len() % 2,
+        };
+    }
+    match mode {
+        CutMode::Head => utf8_head_end(buf),
+        CutMode::Tail => utf8_tail_start(buf),
+    }
+}
+
+fn is_utf8_cont(b: u8) -> bool {
+    b & 0xC0 == 0x80
+}
+
+/// End of buf without an incomplete multi-byte sequence at the end.
+fn utf8_head_end(buf: &[u8]) -> usize {
+    let lead = buf.iter().rposition(|&b| !is_utf8_cont(b)).unwrap_or(0);
+    let need = match buf.get(lead) {
+        Some(0xF0..=0xFF) => 4,
+        Some(0xE0..=0xEF) => 3,
+        Some(0xC0..=0xDF) => 2,
+        _ => 1,
+    };
+    if buf.len() - lead < need {
+        lead
+    } else {
+        buf.len()
+    }
+}
+
+/// Start of the first whole char in buf, skipping continuation bytes of a split one.
+fn utf8_tail_start(buf: &[u8]) -> usize {
+    buf.iter().position(|&b| !is_utf8_cont(b)).unwrap_or(buf.len())
+}
+
+/// Read part of a file, decoding only that part. When the range exceeds max_bytes it is
+/// cut to the head or tail, on a line boundary where possible, and marked truncated.
+pub fn read_window(
+    path: &Path,
+    range: ReadRange,
+    max_bytes: Option<u64>,
+    mode: CutMode,
+) -> std::io::Result<Window> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut head = vec![0u8; BINARY_SNIFF_BYTES];
+    let n = read_full(&mut file, &mut head)?;
+    let sniff = sniff(&head[..n]);
+    if sniff.binary {
+        return Ok(Window {
+            decoded: decoded_binary(),
+            byte_start: 0,
+            byte_end: 0,
+            truncated: false,
+        });
+    }
+    let align = |off: u64| off - (off - sniff.bom_len) % sniff.unit() as u64;
+    let by_bytes = matches!(range, ReadRange::Bytes(..));
+    let (mut start, mut end) = match range {
+        ReadRange::Whole => (sniff.bom_len, size),
+        ReadRange::Bytes(s, e) => {
+            // Align end first: an odd-length UTF-16 file ends mid unit, and start must not
+            // pass the aligned end.
+            let end = align(e.unwrap_or(size).min(size).max(sniff.bom_len));
+            (align(s.max(sniff.bom_len)).min(end), end)
+        }
+        ReadRange::Lines(first, last) => line_span(&mut file, &sniff, size, first, last)?,
+    };
+    let truncated = max_bytes.is_some_and(|max| end - start > max);
+    if let Some(max) = max_bytes.filter(|_| truncated) {
+        match mode {
+            CutMode::Head => end = align(start + max),
+            CutMode::Tail => start = align(end - max),
+        }
+    }
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = vec![0u8; (end - start) as usize];
+    let n = read_full(&mut file, &mut buf)?;
+    buf.truncate(n);
+    if truncated {
+        let cut = snap_cut(&buf, &sniff, mode);
+        match mode {
+            CutMode::Head => buf.truncate(cut),
+            CutMode::Tail => {
+                buf.drain(..cut);
+                start += cut as u64;
+            }
+        }
+    }
+    if by_bytes && sniff.unit() == 1 {
+        // Byte offsets may fall inside a UTF-8 char; move them to char boundaries unless
+        // the bytes are not UTF-8 anyway (then they decode as Latin-1 below).
+        let (lo, hi) = (utf8_tail_start(&buf), utf8_head_end(&buf));
+        if lo <= hi && (lo, hi) != (0, buf.len()) && std::str::from_utf8(&buf[lo..hi]).is_ok() {
+            buf.truncate(hi);
+            buf.drain(..lo);
+            start += lo as u64;
+        }
+    }
+    end = start + buf.len() as u64;
+    let (text, encoding) = decode_body(&buf, sniff.fixed_encoding);
+    Ok(Window {
+        decoded: decoded_text(text, encoding),
+        byte_start: start,
+        byte_end: end,
+        truncated,
+    })
+}
+
+pub struct FileStats {
+    pub size_bytes: u64,
+    pub line_count: u64,
+    pub is_binary: bool,
+    /// From the BOM, else guessed from the first few KB.
+    pub encoding: TextEncoding,
+}
+
+/// Size and line count in one streaming pass, without decoding. Lines are counted like an
+/// editor would: a final line without a newline still counts.
+pub fn stats(path: &Path) -> std::io::Result<FileStats> {
+    let mut file = File::open(path)?;
+    let size_bytes = file.metadata()?.len();
+    let mut buf = vec![0u8; SCAN_CHUNK];
+    let n = read_full(&mut file, &mut buf)?;
+    let sniff = sniff(&buf[..n]);
+    let encoding = sniff.fixed_encoding.unwrap_or_else(|| match std::str::from_utf8(&buf[..n]) {
+        Ok(_) => TextEncoding::Utf8,
+        Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+        Err(_) => TextEncoding::Latin1,
+    });
+    if sniff.binary {
+        return Ok(FileStats {
+            size_bytes,
+            line_count: 0,
+            is_binary: true,
+            encoding,
+        });
+    }
+    let unit = sniff.unit();
+    let mut newlines = 0u64;
+    let mut last_was_newline = false;
+    let (mut lo, mut hi) = (sniff.bom_len as usize, n);
+    while hi > lo {
+        for u in buf[lo..hi].chunks_exact(unit) {
+            last_was_newline = sniff.is_newline(u);
+            newlines += last_was_newline as u64;
+        }
+        (lo, hi) = (0, read_full(&mut file, &mut buf)?);
+    }
+    let has_body = size_bytes > sniff.bom_len;
+    Ok(FileStats {
+        size_bytes,
+        line_count: newlines + (has_body && !last_was_newline) as u64,
+        is_binary: false,
+        encoding,
+    })
+}
+
+pub fn detect_line_ending(text: &str) -> LineEnding {
+    let (mut lf, mut crlf, mut cr) = (0usize, 0usize, 0usize);
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                crlf += 1;
+                i += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    match (lf > 0, crlf > 0, cr > 0) {
+        (false, false, false) => LineEnding::None,
+        (true, false, false) => LineEnding::Lf,
+        (false, true, false) => LineEnding::Crlf,
+        (false, false, true) => LineEnding::Cr,
+        _ => LineEnding::Mixed,
+    }
+}
+
+/// Rewrite every line break in `text` to `ending`. Mixed/None leave text unchanged.
+pub fn convert_line_endings(text: &str, ending: LineEnding) -> String {
+    let Some(sep) = ending.as_str() else {
+        return text.to_string();
+    };
+    let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+    if sep == "\n" {
+        normalized
+    } else {
+        normalized.replace('\n', sep)
+    }
+}
+
+/// Add or strip a single trailing line break, using `ending` when one is added.
+pub fn set_trailing_newline(text: &str, want: bool, ending: LineEnding) -> String {
+    let trimmed = text.trim_end_matches(['\r', '\n']);
+    if want {
+        format!("{}{}", trimmed, ending.as_str().unwrap_or("\n"))
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Encode text for disk. Fails for Latin-1 when a char is outside U+0000..=U+00FF.
+pub fn encode(text: &str, encoding: TextEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        TextEncoding::Utf8 => Ok(text.as_bytes().to_vec()),
+        TextEncoding::Utf8Bom => {
+            let mut out = UTF8_BOM.to_vec();
+            out.extend_from_slice(text.as_bytes());
+            Ok(out)
+        }
+        TextEncoding::Utf16Le
+        | TextEncoding::Utf16Be
+        | TextEncoding::Utf16LeNoBom
+        | TextEncoding::Utf16BeNoBom => {
+            let le = is_utf16_le(Some(encoding));
+            let mut out = match encoding {
+                TextEncoding::Utf16Le => UTF16LE_BOM.to_vec(),
+                TextEncoding::Utf16Be => UTF16BE_BOM.to_vec(),
+                _ => Vec::new(),
+            };
+            for u in text.encode_utf16() {
+                out.extend_from_slice(&if le { u.to_le_bytes() } else { u.to_be_bytes() });
+            }
+            Ok(out)
+        }
+        TextEncoding::Latin1 => text
+            .chars()
+            .map(|c| {
+                u8::try_from(u32::from(c)).map_err(|_| {
+                    format!(
+                        "character {:?} cannot be written as latin-1; pass encoding \"utf-8\" to convert the file",
+                        c
+                    )
+                })
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("textfile-{}-{}", name, std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn byte_range_past_end_of_odd_length_utf16_is_empty() {
+        // BOM, "ab", then a stray byte: 7 bytes, so the last whole unit ends at 6.
+        let path = temp_file("utf16-odd", &[0xFF, 0xFE, b'a', 0, b'b', 0, b'c']);
+        for (s, e) in [(7, None), (100, None), (7, Some(7)), (6, Some(100))] {
+            let w = read_window(&path, ReadRange::Bytes(s, e), None, CutMode::Head).unwrap();
+            assert_eq!((w.byte_start, w.byte_end), (6, 6), "{}..{:?}", s, e);
+            assert_eq!(w.decoded.text.as_deref(), Some(""));
+        }
+        let w = read_window(&path, ReadRange::Bytes(0, None), None, CutMode::Head).unwrap();
+        assert_eq!((w.byte_start, w.byte_end), (2, 6));
+        assert_eq!(w.decoded.text.as_deref(), Some("ab"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
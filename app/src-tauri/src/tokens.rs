@@ -0,0 +1,274 @@
+//! Token counting for prompt budgeting: llama-server's /tokenize when a server is running
+//! (exact for the loaded model), otherwise a character-class heuristic.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::runtime::RuntimeState;
+use crate::textfile;
+use crate::workspace::{allow_external_symlinks, resolve};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Files larger than this are estimated instead of sent to the server.
+const MAX_EXACT_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenSource {
+    /// llama-server /tokenize with the loaded model's vocabulary.
+    Server,
+    /// Estimate; no server running or the server failed.
+    Heuristic,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenizeResult {
+    pub count: usize,
+    /// Token ids; only when counted by the server.
+    pub tokens: Option<Vec<i64>>,
+    /// Text of each token, when requested and counted by the server.
+    pub pieces: Option<Vec<String>>,
+    pub source: TokenSource,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCount {
+    /// Set for file items; None for text items.
+    pub path: Option<String>,
+    pub tokens: usize,
+    /// Counted by the server (false = heuristic estimate).
+    pub exact: bool,
+    /// Why a file was not counted (unreadable, binary).
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountTokensResult {
+    /// Texts first, in order, then paths, in order.
+    pub items: Vec<TokenCount>,
+    pub total: usize,
+    /// Every counted item came from the server.
+    pub exact: bool,
+}
+
+#[derive(Serialize)]
+struct TokenizeRequest<'a> {
+    content: &'a str,
+    add_special: bool,
+    with_pieces: bool,
+}
+
+#[derive(Deserialize)]
+struct TokenizeResponse {
+    tokens: Vec<serde_json::Value>,
+}
+
+/// Rough count for BPE-style vocabularies: ~4 characters per token for ASCII words,
+/// one per punctuation mark, non-ASCII character and newline; other whitespace is free.
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word = 0usize;
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word += 1;
+            continue;
+        }
+        tokens += word.div_ceil(4);
+        word = 0;
+        if c == '\n' || !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + word.div_ceil(4)
+}
+
+/// Port of the llama-server in use: our live child, or an attached server that passes its
+/// health check.
+async fn running_port(state: &Mutex<RuntimeState>) -> AppResult<Option<u16>> {
+    crate::runtime::live_port(state).await
+}
+
+fn client() -> AppResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::new(ErrorKind::Other, e.to_string()))
+}
+
+/// Counts with the running server until it fails once, then estimates for the rest.
+pub(crate) struct Counter {
+    client: reqwest::Client,
+    port: Option<u16>,
+}
+
+impl Counter {
+    pub(crate) async fn new(state: &Mutex<RuntimeState>) -> AppResult<Self> {
+        Ok(Self {
+            client: client()?,
+            port: running_port(state).await?,
+        })
+    }
+
+    /// Token count and whether it came from the server.
+    pub(crate) async fn count(&mut self, text: &str) -> (usize, bool) {
+        if let Some(p) = self.port.filter(|_| text.len() <= MAX_EXACT_BYTES) {
+            match server_tokenize(&self.client, p, text, false).await {
+                Ok(tokens) => return (tokens.len(), true),
+                Err(_) => self.port = None,
+            }
+        }
+        (estimate_tokens(text), false)
+    }
+}
+
+async fn server_tokenize(
+    client: &reqwest::Client,
+    port: u16,
+    text: &str,
+    with_pieces: bool,
+) -> Result<Vec<serde_json::Value>, String> {
+    let url = format!("http://127.0.0.1:{}/tokenize", port);
+    let resp = client
+        .post(&url)
+        .json(&TokenizeRequest {
+            content: text,
+            add_special: false,
+            with_pieces,
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("llama-server /tokenize returned {}", resp.status()));
+    }
+    let json: TokenizeResponse = resp
+        .json()
+        .await
+        .map_err(|e| format!("Parse error: {}", e))?;
+    Ok(json.tokens)
+}
+
+/// Piece text from `{"id", "piece"}`; the server sends bytes as an array when the
+/// piece is not valid UTF-8 on its own.
+fn piece_text(v: &serde_json::Value) -> String {
+    match v.get("piece") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(bytes)) => {
+            let raw: Vec<u8> = bytes
+                .iter()
+                .filter_map(|b| b.as_u64().map(|b| b as u8))
+                .collect();
+            String::from_utf8_lossy(&raw).into_owned()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Tokenize text with the running model (ids and optional pieces), or estimate the count
+/// when no server is running.
+#[tauri::command]
+pub async fn runtime_tokenize(
+    text: String,
+    with_pieces: Option<bool>,
+    state: tauri::State<'_, Mutex<RuntimeState>>,
+) -> AppResult<TokenizeResult> {
+    let with_pieces = with_pieces.unwrap_or(false);
+    if let Some(port) = running_port(&state).await? {
+        if let Ok(tokens) = server_tokenize(&client()?, port, &text, with_pieces).await {
+            let ids: Vec<i64> = tokens
+                .iter()
+                .filter_map(|t| t.as_i64().or_else(|| t.get("id").and_then(|id| id.as_i64())))
+                .collect();
+            let pieces = with_pieces.then(|| tokens.iter().map(piece_text).collect());
+            return Ok(TokenizeResult {
+                count: ids.len(),
+                tokens: Some(ids),
+                pieces,
+                source: TokenSource::Server,
+            });
+        }
+    }
+    Ok(TokenizeResult {
+        count: estimate_tokens(&text),
+        tokens: None,
+        pieces: None,
+        source: TokenSource::Heuristic,
+    })
+}
+
+/// Count tokens for many texts and/or workspace files in one call. Uses the running server
+/// when available; after the first server failure the rest of the batch is estimated.
+#[tauri::command]
+pub async fn runtime_count_tokens(
+    texts: Option<Vec<String>>,
+    workspace_root: Option<String>,
+    paths: Option<Vec<String>>,
+    state: tauri::State<'_, Mutex<RuntimeState>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<CountTokensResult> {
+    if let Some(root) = &workspace_root {
+        registry::check_read(&registry, root)?;
+    }
+    let paths = paths.unwrap_or_default();
+    if !paths.is_empty() && workspace_root.is_none() {
+        return Err(AppError::invalid(
+            "workspace_root is required when counting files",
+        ));
+    }
+    let mut counter = Counter::new(&state).await?;
+
+    // (path, text or error) per item, texts first.
+    let mut inputs: Vec<(Option<String>, Result<String, String>)> = texts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| (None, Ok(t)))
+        .collect();
+    let external = allow_external_symlinks();
+    for p in paths {
+        let text = resolve(workspace_root.as_deref().unwrap_or(""), &p, external)
+            .map_err(|e| e.message)
+            .and_then(|full| std::fs::read(&full).map_err(|e| e.to_string()))
+            .and_then(|bytes| {
+                textfile::decode(&bytes)
+                    .text
+                    .ok_or_else(|| "binary file".to_string())
+            });
+        inputs.push((Some(p), text));
+    }
+
+    let mut items = Vec::with_capacity(inputs.len());
+    for (path, text) in inputs {
+        let text = match text {
+            Ok(t) => t,
+            Err(e) => {
+                items.push(TokenCount {
+                    path,
+                    tokens: 0,
+                    exact: false,
+                    error: Some(e),
+                });
+                continue;
+            }
+        };
+        let (tokens, exact) = counter.count(&text).await;
+        items.push(TokenCount {
+            path,
+            tokens,
+            exact,
+            error: None,
+        });
+    }
+
+    Ok(CountTokensResult {
+        total: items.iter().map(|i| i.tokens).sum(),
+        exact: items.iter().filter(|i| i.error.is_none()).all(|i| i.exact),
+        items,
+    })
+}
@@ -2,7 +2,7 @@
 
 use std::path::Path;
 
-const MAX_LEVELS: u32 = 8;
+use crate::error::{AppResult, IoResultExt};
 
 #[cfg(windows)]
 const LLAMA_EXE: &str = "runtime/llama/llama-server.exe";
@@ -18,15 +18,17 @@ fn name_preferred(name: &str) -> bool {
     PREFER_PATTERN.iter().any(|p| lower.contains(p))
 }
 
-/// Walk up from workspace_root (up to 8 levels). First dir containing BOTH
+/// Walk up from workspace_root (up to `limits.toolRootMaxLevels` levels, default 8).
+/// First dir containing BOTH
 /// runtime/llama/llama-server.exe and models/ (folder) is toolRoot.
 /// Returns absolute path as string, or None.
 #[tauri::command]
-pub fn find_tool_root(workspace_root: String) -> Result<Option<String>, String> {
+pub fn find_tool_root(workspace_root: String) -> AppResult<Option<String>> {
     let mut dir = Path::new(&workspace_root)
         .canonicalize()
-        .map_err(|e| format!("workspace_root invalid: {}", e))?;
-    for _ in 0..MAX_LEVELS {
+        .at(Path::new(&workspace_root))?;
+    let max_levels = crate::settings::load(Some(&dir)).limits.tool_root_max_levels;
+    for _ in 0..max_levels {
         let exe = dir.join(LLAMA_EXE);
         let models = dir.join(MODELS_DIR);
         if exe.is_file() && models.is_dir() {
@@ -44,7 +46,7 @@ pub fn find_tool_root(workspace_root: String) -> Result<Option<String>, String>
 
 /// Check that tool_root/rel_path exists (file or dir).
 #[tauri::command]
-pub fn tool_root_exists(tool_root: String, rel_path: String) -> Result<bool, String> {
+pub fn tool_root_exists(tool_root: String, rel_path: String) -> AppResult<bool> {
     let rel = rel_path.trim().trim_start_matches(|c| c == '/' || c == '\\');
     let full = Path::new(&tool_root).join(rel);
     Ok(full.try_exists().unwrap_or(false))
@@ -53,14 +55,14 @@ pub fn tool_root_exists(tool_root: String, rel_path: String) -> Result<bool, Str
 /// Scan tool_root/models for *.gguf. Returns toolRoot-relative path (e.g. models/foo.gguf) or None.
 /// If exactly 1 => pick it. If multiple => prefer filename containing coder|code|instruct, then largest size.
 #[tauri::command]
-pub fn scan_models_for_gguf(tool_root: String) -> Result<Option<String>, String> {
+pub fn scan_models_for_gguf(tool_root: String) -> AppResult<Option<String>> {
     let models_dir = Path::new(&tool_root).join(MODELS_DIR);
     if !models_dir.is_dir() {
         return Ok(None);
     }
     let mut ggufs: Vec<(String, u64, bool)> = Vec::new();
-    for e in std::fs::read_dir(&models_dir).map_err(|e| e.to_string())? {
-        let e = e.map_err(|e| e.to_string())?;
+    for e in std::fs::read_dir(&models_dir).at(&models_dir)? {
+        let e = e.at(&models_dir)?;
         let name = e.file_name().to_string_lossy().into_owned();
         if e.file_type().map(|t| t.is_dir()).unwrap_or(true) {
             continue;
@@ -98,14 +100,14 @@ pub struct ScanModelsByMtimeResult {
 /// Scan tool_root/models for *.gguf. Pick by most recently modified.
 /// If multiple, pick newest and set had_multiple.
 #[tauri::command]
-pub fn scan_models_for_gguf_by_mtime(tool_root: String) -> Result<Option<ScanModelsByMtimeResult>, String> {
+pub fn scan_models_for_gguf_by_mtime(tool_root: String) -> AppResult<Option<ScanModelsByMtimeResult>> {
     let models_dir = Path::new(&tool_root).join(MODELS_DIR);
     if !models_dir.is_dir() {
         return Ok(None);
     }
     let mut ggufs: Vec<(String, std::time::SystemTime)> = Vec::new();
-    for e in std::fs::read_dir(&models_dir).map_err(|e| e.to_string())? {
-        let e = e.map_err(|e| e.to_string())?;
+    for e in std::fs::read_dir(&models_dir).at(&models_dir)? {
+        let e = e.at(&models_dir)?;
         let name = e.file_name().to_string_lossy().into_owned();
         if e.file_type().map(|t| t.is_dir()).unwrap_or(true) {
             continue;
@@ -0,0 +1,163 @@
+//! Shared workspace walker: one ignore policy for search, snapshot and any indexer.
+//! Honors .gitignore, .git/info/exclude, the global gitignore and .devassistantignore,
+//! plus built-in defaults and `extraIgnore` from .devassistant/settings.json.
+
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+
+/// Project-level ignore file, gitignore syntax, for paths the assistant should skip
+/// but git should still track.
+pub const DEVASSISTANT_IGNORE_FILE: &str = ".devassistantignore";
+
+/// Skipped everywhere, even without any ignore file: VCS/internal dirs, dependency
+/// folders and common build output.
+const DEFAULT_IGNORED: &[&str] = &[
+    ".git/",
+    ".devassistant/",
+    "node_modules/",
+    "target/",
+    "dist/",
+    "build/",
+    "out/",
+    ".next/",
+    ".turbo/",
+    ".cache/",
+    "coverage/",
+    ".venv/",
+    "venv/",
+    "__pycache__/",
+    ".DS_Store",
+];
+
+#[derive(Default, Clone)]
+pub struct WalkOptions {
+    /// Levels below root to descend (1 = direct children only). None = unlimited.
+    pub max_depth: Option<usize>,
+    /// Extra gitignore-style patterns on top of the workspace settings.
+    pub extra_ignore: Vec<String>,
+}
+
+pub struct WalkEntry {
+    /// Root-relative, forward slashes.
+    pub rel_path: String,
+    pub abs_path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// `extraIgnore` patterns from the resolved settings (user, workspace and env layers).
+pub fn settings_extra_ignore(root: &Path) -> Vec<String> {
+    crate::settings::load(Some(root))
+        .extra_ignore
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Matcher for defaults + settings + caller patterns, rooted at root.
+fn extra_matcher(root: &Path, opts: &WalkOptions) -> Result<Gitignore, String> {
+    let mut b = GitignoreBuilder::new(root);
+    let settings = settings_extra_ignore(root);
+    let patterns = DEFAULT_IGNORED
+        .iter()
+        .copied()
+        .chain(settings.iter().map(String::as_str))
+        .chain(opts.extra_ignore.iter().map(String::as_str));
+    for p in patterns {
+        b.add_line(None, p)
+            .map_err(|e| format!("invalid ignore pattern {:?}: {}", p, e))?;
+    }
+    b.build().map_err(|e| e.to_string())
+}
+
+/// Configured WalkBuilder for root. Symlinks are not followed; hidden files are included
+/// (ignore rules decide). Use this directly when a parallel walk is needed.
+pub fn builder(root: &Path, opts: &WalkOptions) -> Result<WalkBuilder, String> {
+    let matcher = extra_matcher(root, opts)?;
+    let mut b = WalkBuilder::new(root);
+    b.hidden(false)
+        .follow_links(false)
+        .parents(true)
+        .git_ignore(true)
+        .git_exclude(true)
+        .git_global(true)
+        .require_git(false)
+        .add_custom_ignore_filename(DEVASSISTANT_IGNORE_FILE)
+        .max_depth(opts.max_depth)
+        .filter_entry(move |e| {
+            let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            !matcher.matched(e.path(), is_dir).is_ignore()
+        });
+    Ok(b)
+}
+
+/// Ignore check for single paths (e.g. watcher events), outside a walk. Uses the root's
+/// .gitignore, .git/info/exclude, .devassistantignore, defaults and settings; nested
+/// .gitignore files are only honored by a real walk.
+pub struct PathFilter {
+    root: PathBuf,
+    extra: Gitignore,
+    root_files: Gitignore,
+}
+
+impl PathFilter {
+    pub fn new(root: &Path, opts: &WalkOptions) -> Result<Self, String> {
+        let mut b = GitignoreBuilder::new(root);
+        for name in [".gitignore", ".git/info/exclude", DEVASSISTANT_IGNORE_FILE] {
+            let p = root.join(name);
+            if p.is_file() {
+                if let Some(e) = b.add(&p) {
+                    return Err(format!("{}: {}", p.display(), e));
+                }
+            }
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            extra: extra_matcher(root, opts)?,
+            root_files: b.build().map_err(|e| e.to_string())?,
+        })
+    }
+
+    /// True if path (absolute, under root) or any parent directory is ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if !path.starts_with(&self.root) {
+            return true;
+        }
+        if path == self.root {
+            return false;
+        }
+        self.extra.matched_path_or_any_parents(path, is_dir).is_ignore()
+            || self.root_files.matched_path_or_any_parents(path, is_dir).is_ignore()
+    }
+}
+
+/// Root-relative forward-slash path, or None for the root itself.
+pub fn rel_path(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    if rel.as_os_str().is_empty() {
+        return None;
+    }
+    Some(rel.to_string_lossy().replace('\\', "/"))
+}
+
+/// Sequential walk in file-name order. Yields regular files and directories only;
+/// unreadable entries and symlinks are skipped.
+pub fn walk(root: &Path, opts: &WalkOptions) -> Result<impl Iterator<Item = WalkEntry>, String> {
+    let root = root.to_path_buf();
+    let mut b = builder(&root, opts)?;
+    b.sort_by_file_name(|a, b| a.cmp(b));
+    Ok(b.build().filter_map(move |e| {
+        let e = e.ok()?;
+        let ft = e.file_type()?;
+        if !ft.is_dir() && !ft.is_file() {
+            return None;
+        }
+        Some(WalkEntry {
+            rel_path: rel_path(&root, e.path())?,
+            abs_path: e.path().to_path_buf(),
+            is_dir: ft.is_dir(),
+        })
+    }))
+}
@@ -0,0 +1,314 @@
+//! Filesystem watcher per workspace root (notify: inotify on Linux). Watches only
+//! non-ignored directories, debounces raw events and emits batched `workspace://changed`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::AppResult;
+use crate::fuzzy::{self, FileListCache};
+use crate::walker::{self, PathFilter};
+
+pub const CHANGED_EVENT: &str = "workspace://changed";
+
+/// Quiet period that closes a batch.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// A batch is flushed after this long even if events keep arriving.
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(2);
+/// Directories watched per root (one inotify watch each). Deeper trees are partially watched.
+const MAX_WATCHED_DIRS: usize = 8000;
+
+#[derive(Default)]
+pub struct WatcherState {
+    watchers: HashMap<String, WatchHandle>,
+}
+
+struct WatchHandle {
+    /// Debounce thread holds only a Weak; dropping this stops the thread.
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedPath {
+    pub from: String,
+    pub to: String,
+}
+
+/// Payload of `workspace://changed`. Paths are root-relative with forward slashes.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceChangedEvent {
+    pub workspace_root: String,
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<RenamedPath>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStartResult {
+    pub workspace_root: String,
+    pub watched_dirs: usize,
+    /// True when MAX_WATCHED_DIRS was reached and some directories are not watched.
+    pub capped: bool,
+    /// False when the root was already being watched.
+    pub started: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Change {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// Accumulates one debounce window, coalescing repeated events per path.
+#[derive(Default)]
+struct Batch {
+    changes: HashMap<PathBuf, Change>,
+    renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Batch {
+    fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.renamed.is_empty()
+    }
+
+    fn record(&mut self, path: PathBuf, change: Change) {
+        let prev = self.changes.get(&path).copied();
+        let next = match (prev, change) {
+            (Some(Change::Created), Change::Removed) => None, // transient file
+            (Some(Change::Created), _) => Some(Change::Created),
+            (Some(Change::Removed), Change::Created) => Some(Change::Modified),
+            (_, c) => Some(c),
+        };
+        match next {
+            Some(c) => self.changes.insert(path, c),
+            None => self.changes.remove(&path),
+        };
+    }
+
+    fn add(&mut self, ev: Event) {
+        match ev.kind {
+            EventKind::Create(_) => ev.paths.into_iter().for_each(|p| self.record(p, Change::Created)),
+            EventKind::Remove(_) => ev.paths.into_iter().for_each(|p| self.record(p, Change::Removed)),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if ev.paths.len() == 2 => {
+                let (from, to) = (ev.paths[0].clone(), ev.paths[1].clone());
+                // The From/To halves of this rename were already recorded; report it once.
+                if self.changes.get(&from) == Some(&Change::Removed) {
+                    self.changes.remove(&from);
+                }
+                if self.changes.get(&to) == Some(&Change::Created) {
+                    self.changes.remove(&to);
+                }
+                self.renamed.push((from, to));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                ev.paths.into_iter().for_each(|p| self.record(p, Change::Removed))
+            }
+            EventKind::Modify(ModifyKind::Name(_)) => ev.paths.into_iter().for_each(|p| {
+                let c = if p.exists() { Change::Created } else { Change::Removed };
+                self.record(p, c)
+            }),
+            EventKind::Modify(_) => ev.paths.into_iter().for_each(|p| self.record(p, Change::Modified)),
+            EventKind::Access(_) | EventKind::Any | EventKind::Other => {}
+        }
+    }
+
+    /// Convert to the event payload, dropping ignored paths.
+    fn into_event(self, root: &Path, filter: &PathFilter) -> WorkspaceChangedEvent {
+        let rel = |p: &Path| {
+            if filter.is_ignored(p, p.is_dir()) {
+                None
+            } else {
+                walker::rel_path(root, p)
+            }
+        };
+        let mut ev = WorkspaceChangedEvent {
+            workspace_root: root.to_string_lossy().replace('\\', "/"),
+            ..Default::default()
+        };
+        for (path, change) in self.changes {
+            let Some(r) = rel(&path) else { continue };
+            match change {
+                Change::Created => ev.created.push(r),
+                Change::Modified => ev.modified.push(r),
+                Change::Removed => ev.removed.push(r),
+            }
+        }
+        for (from, to) in self.renamed {
+            match (rel(&from), rel(&to)) {
+                (Some(from), Some(to)) => ev.renamed.push(RenamedPath { from, to }),
+                (Some(from), None) => ev.removed.push(from),
+                (None, Some(to)) => ev.created.push(to),
+                (None, None) => {}
+            }
+        }
+        ev.created.sort();
+        ev.modified.sort();
+        ev.removed.sort();
+        ev
+    }
+
+    /// New directories that should get their own watch.
+    fn created_dirs(&self) -> Vec<PathBuf> {
+        let renamed_to = self.renamed.iter().map(|(_, to)| to);
+        self.changes
+            .iter()
+            .filter(|(_, c)| **c == Change::Created)
+            .map(|(p, _)| p)
+            .chain(renamed_to)
+            .filter(|p| p.is_dir())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Watch every non-ignored directory under start (inclusive) until the cap. Returns how many.
+fn watch_tree(w: &mut RecommendedWatcher, root: &Path, start: &Path, budget: usize) -> usize {
+    if budget == 0 || w.watch(start, RecursiveMode::NonRecursive).is_err() {
+        return 0;
+    }
+    let mut n = 1;
+    let Ok(entries) = walker::walk(start, &walker::WalkOptions::default()) else {
+        return n;
+    };
+    let filter = PathFilter::new(root, &walker::WalkOptions::default()).ok();
+    for e in entries.filter(|e| e.is_dir) {
+        if n >= budget {
+            break;
+        }
+        if filter.as_ref().is_some_and(|f| f.is_ignored(&e.abs_path, true)) {
+            continue;
+        }
+        if w.watch(&e.abs_path, RecursiveMode::NonRecursive).is_ok() {
+            n += 1;
+        }
+    }
+    n
+}
+
+fn run_debouncer(
+    app: AppHandle,
+    root: PathBuf,
+    filter: PathFilter,
+    rx: mpsc::Receiver<notify::Result<Event>>,
+    watcher: Weak<Mutex<RecommendedWatcher>>,
+    mut watched: usize,
+) {
+    // Blocks until the first event of a batch; ends when the watcher (and its sender) is dropped.
+    while let Ok(first) = rx.recv() {
+        let mut batch = Batch::default();
+        if let Ok(ev) = first {
+            batch.add(ev);
+        }
+        let opened = Instant::now();
+        loop {
+            let left = MAX_BATCH_DELAY.saturating_sub(opened.elapsed());
+            if left.is_zero() {
+                break;
+            }
+            match rx.recv_timeout(DEBOUNCE.min(left)) {
+                Ok(Ok(ev)) => batch.add(ev),
+                Ok(Err(_)) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if batch.is_empty() {
+            continue;
+        }
+        if let Some(w) = watcher.upgrade() {
+            if let Ok(mut w) = w.lock() {
+                for dir in batch.created_dirs() {
+                    if watched < MAX_WATCHED_DIRS && !filter.is_ignored(&dir, true) {
+                        watched += watch_tree(&mut w, &root, &dir, MAX_WATCHED_DIRS - watched);
+                    }
+                }
+            }
+        }
+        let ev = batch.into_event(&root, &filter);
+        if !(ev.created.is_empty() && ev.removed.is_empty() && ev.renamed.is_empty()) {
+            if let Some(cache) = app.try_state::<Mutex<FileListCache>>() {
+                fuzzy::invalidate(&cache, &root);
+            }
+        }
+        if !(ev.created.is_empty() && ev.modified.is_empty() && ev.removed.is_empty() && ev.renamed.is_empty()) {
+            let _ = app.emit(CHANGED_EVENT, ev);
+        }
+    }
+}
+
+/// Start watching workspace_root. Emits `workspace://changed` batches until stopped.
+/// Idempotent: a second start for the same root is a no-op.
+#[tauri::command]
+pub fn workspace_watch_start(
+    app: AppHandle,
+    workspace_root: String,
+    state: tauri::State<'_, Mutex<WatcherState>>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<WatchStartResult> {
+    let root = crate::registry::check_read(&registry, &workspace_root)?;
+    let key = root.to_string_lossy().replace('\\', "/");
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    if s.watchers.contains_key(&key) {
+        return Ok(WatchStartResult {
+            workspace_root: key,
+            watched_dirs: 0,
+            capped: false,
+            started: false,
+        });
+    }
+
+    let filter = PathFilter::new(&root, &walker::WalkOptions::default())?;
+    let (tx, rx) = mpsc::channel();
+    let mut w = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })
+    .map_err(|e| format!("Failed to start watcher: {}", e))?;
+    let watched = watch_tree(&mut w, &root, &root, MAX_WATCHED_DIRS);
+
+    let watcher = Arc::new(Mutex::new(w));
+    let weak = Arc::downgrade(&watcher);
+    let thread_root = root.clone();
+    std::thread::Builder::new()
+        .name("workspace-watcher".into())
+        .spawn(move || run_debouncer(app, thread_root, filter, rx, weak, watched))
+        .map_err(|e| e.to_string())?;
+
+    s.watchers.insert(key.clone(), WatchHandle { _watcher: watcher });
+    Ok(WatchStartResult {
+        workspace_root: key,
+        watched_dirs: watched,
+        capped: watched >= MAX_WATCHED_DIRS,
+        started: true,
+    })
+}
+
+/// Stop watching workspace_root. Returns false if it was not being watched.
+#[tauri::command]
+pub fn workspace_watch_stop(
+    workspace_root: String,
+    state: tauri::State<'_, Mutex<WatcherState>>,
+    registry: tauri::State<'_, Mutex<crate::registry::WorkspaceRegistry>>,
+) -> AppResult<bool> {
+    let key = match crate::workspace::canonical_root(&workspace_root) {
+        Ok(r) => {
+            crate::registry::check_read(&registry, &workspace_root)?;
+            r.to_string_lossy().replace('\\', "/")
+        }
+        // A root deleted while watched can still be unwatched; nothing is left to read.
+        Err(_) => workspace_root.replace('\\', "/"),
+    };
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    Ok(s.watchers.remove(&key).is_some())
+}
@@ -1,54 +1,169 @@
 //! Workspace-scoped filesystem operations. All paths validated against root; no writes outside.
 
-use chrono::Utc;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::{AppError, AppResult, ErrorKind, IoResultExt};
+use crate::fuzzy::{self, FileListCache};
+use crate::registry::{self, WorkspaceRegistry};
+use crate::textfile::{self, CutMode, LineEnding, ReadRange, TextEncoding};
+
+/// Symlinks followed while resolving one path before giving up (Linux uses 40).
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// True when `s` starts with a drive (`C:`), UNC (`\\server\share`) or verbatim (`\\?\`)
+/// prefix. Checked on the string so Windows-style input is treated alike on every platform.
+fn has_windows_prefix(s: &str) -> bool {
+    let b = s.as_bytes();
+    (b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':') || s.starts_with("\\\\")
+}
+
+/// Strip the `\\?\` form canonicalize produces on Windows so `C:\ws` and `\\?\C:\ws` compare equal.
+fn simplify(p: &Path) -> PathBuf {
+    let s = p.to_string_lossy();
+    if let Some(unc) = s.strip_prefix("\\\\?\\UNC\\") {
+        return PathBuf::from(format!("\\\\{}", unc));
+    }
+    match s.strip_prefix("\\\\?\\") {
+        Some(rest) if has_windows_prefix(rest) => PathBuf::from(rest),
+        _ => p.to_path_buf(),
+    }
+}
 
-fn normalize_rel(s: &str) -> PathBuf {
-    let p = Path::new(s);
+/// `rel` as a normalized path relative to the root. Absolute paths (drive/UNC included) must
+/// lie under `root` or `root_canon`; a bare leading separator on Windows (`\x`, which is
+/// not absolute there) is read as root-relative.
+/// `..` pops a component lexically and may not climb above the root. Names that merely
+/// contain dots (`foo..bar.txt`) are ordinary components.
+fn relative_part(root: &Path, root_canon: &Path, rel: &str) -> Option<PathBuf> {
+    let p = Path::new(rel);
+    let rel_path = if has_windows_prefix(rel) || p.is_absolute() {
+        let p = simplify(p);
+        [simplify(root), simplify(root_canon)]
+            .into_iter()
+            .find_map(|r| p.strip_prefix(&r).ok().map(Path::to_path_buf))?
+    } else {
+        p.to_path_buf()
+    };
     let mut out = PathBuf::new();
-    for c in p.components() {
+    for c in rel_path.components() {
         match c {
-            std::path::Component::CurDir => {}
-            std::path::Component::ParentDir => {
-                out.pop();
+            Component::Normal(x) => out.push(x),
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
             }
-            std::path::Component::Normal(x) => out.push(x),
-            _ => {}
+            Component::CurDir | Component::RootDir => {}
+            Component::Prefix(_) => return None,
         }
     }
-    out
+    Some(out)
 }
 
-/// Resolve relative path under workspace root. Fails if path escapes root.
-/// Does not require target to exist (for write/exists).
-fn resolve(root: &str, rel: &str) -> Result<PathBuf, String> {
-    let root = Path::new(root);
+/// Canonical form of `path`, which need not exist: the deepest existing ancestor is
+/// canonicalized and the missing rest appended. A dangling symlink on the way is followed
+/// to its target, so creating the file later cannot land anywhere else.
+pub(crate) fn canonicalize_partial(path: &Path) -> std::io::Result<PathBuf> {
+    let mut path = path.to_path_buf();
+    'hops: for _ in 0..MAX_SYMLINK_HOPS {
+        let mut existing = path.clone();
+        // Missing components, innermost first; may include ".." from a link target.
+        let mut missing: Vec<std::ffi::OsString> = Vec::new();
+        loop {
+            match std::fs::symlink_metadata(&existing) {
+                Ok(meta) if meta.file_type().is_symlink() && std::fs::metadata(&existing).is_err() => {
+                    let target = std::fs::read_link(&existing)?;
+                    let mut next = existing.parent().unwrap_or(Path::new("")).join(target);
+                    next.extend(missing.iter().rev());
+                    path = next;
+                    continue 'hops;
+                }
+                Ok(_) => {
+                    let mut out = existing.canonicalize()?;
+                    for c in missing.iter().rev() {
+                        if c == ".." {
+                            out.pop();
+                        } else {
+                            out.push(c);
+                        }
+                    }
+                    return Ok(out);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    let Some(last) = existing.components().next_back() else {
+                        return Err(e);
+                    };
+                    missing.push(last.as_os_str().to_os_string());
+                    if !existing.pop() {
+                        return Err(e);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Err(std::io::Error::other("too many levels of symbolic links"))
+}
+
+/// Canonical form of an absolute workspace root; the one check every command starts from.
+pub(crate) fn canonical_root(workspace_root: &str) -> AppResult<PathBuf> {
+    let root = Path::new(workspace_root);
     if !root.is_absolute() {
-        return Err("workspace_root must be absolute".into());
+        return Err(AppError::invalid("workspace_root must be absolute")
+            .with("workspaceRoot", workspace_root));
     }
-    if rel.contains("..") {
-        return Err("path must not escape workspace".into());
+    root.canonicalize().at(root)
+}
+
+/// `security.allowExternalSymlinks` from the user settings. Commands read it once and pass
+/// it to every resolve() they make.
+pub(crate) fn allow_external_symlinks() -> bool {
+    crate::settings::load(None).security.allow_external_symlinks
+}
+
+/// Resolve relative path under workspace root. Fails if path escapes root, lexically or
+/// through a symlink (unless `allow_external_symlinks`, see the function of that name).
+/// Does not require target to exist (for write/exists). The returned path is the root's
+/// canonical form joined with the normalized relative path, so links inside are kept.
+pub(crate) fn resolve(root: &str, rel: &str, allow_external_symlinks: bool) -> AppResult<PathBuf> {
+    resolve_with(Path::new(root), rel, allow_external_symlinks)
+}
+
+fn resolve_with(root: &Path, rel: &str, allow_external_symlinks: bool) -> AppResult<PathBuf> {
+    if !root.is_absolute() {
+        return Err(AppError::invalid("workspace_root must be absolute").with("workspaceRoot", root));
     }
-    let rel = normalize_rel(rel);
-    let root_canon = root.canonicalize().map_err(|e| e.to_string())?;
-    let full = root_canon.join(&rel);
-    if !full.starts_with(&root_canon) {
-        return Err("path escapes workspace root".into());
+    let escapes = || AppError::new(ErrorKind::EscapesWorkspace, "path escapes workspace root").with("path", rel);
+    let root_canon = root.canonicalize().at(root)?;
+    let rel_norm = relative_part(root, &root_canon, rel).ok_or_else(escapes)?;
+    let full = root_canon.join(&rel_norm);
+    if allow_external_symlinks {
+        return Ok(full);
+    }
+    let target = canonicalize_partial(&full).at(&full)?;
+    if !target.starts_with(&root_canon) {
+        return Err(escapes().with("target", target.to_string_lossy().replace('\\', "/")));
     }
     Ok(full)
 }
 
 #[tauri::command]
-pub fn workspace_read_dir(workspace_root: String, path: String) -> Result<Vec<DirEntry>, String> {
-    let full = resolve(&workspace_root, &path)?;
+pub fn workspace_read_dir(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<DirEntry>> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
     if !full.is_dir() {
-        return Err("not a directory".into());
+        return Err(AppError::not_found(format!("{} is not a directory", path)).with("path", &path));
     }
     let mut out = Vec::new();
-    for e in std::fs::read_dir(&full).map_err(|e| e.to_string())? {
-        let e = e.map_err(|e| e.to_string())?;
+    for e in std::fs::read_dir(&full).at(&full)? {
+        let e = e.at(&full)?;
         let name = e.file_name().to_string_lossy().into_owned();
         let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
         out.push(DirEntry { name, is_dir });
@@ -63,68 +178,260 @@ pub struct DirEntry {
     pub is_dir: bool,
 }
 
+/// Read a text file, decoding BOM/UTF-16/Latin-1 as needed. Fails on binary files.
+#[tauri::command]
+pub fn workspace_read_file(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<String> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
+    let bytes = std::fs::read(&full).at(&full)?;
+    textfile::decode(&bytes).text.ok_or_else(|| {
+        AppError::new(ErrorKind::Unsupported, format!("{} is a binary file", path)).with("path", &path)
+    })
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadFileResult {
+    /// Decoded text; None when is_binary.
+    pub content: Option<String>,
+    pub is_binary: bool,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub has_trailing_newline: bool,
+    /// Size of the whole file, not of the returned range.
+    pub size_bytes: u64,
+    /// Byte span of `content` within the file (end exclusive).
+    pub byte_start: u64,
+    pub byte_end: u64,
+    /// True when max_bytes cut the requested range short.
+    pub truncated: bool,
+}
+
+/// Range and size limits for workspace_read_file_detailed. Byte and line ranges are
+/// mutually exclusive; lines are 1-based and inclusive. Without options the whole file is read.
+#[derive(Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadFileOptions {
+    pub byte_start: Option<u64>,
+    pub byte_end: Option<u64>,
+    pub line_start: Option<u64>,
+    pub line_end: Option<u64>,
+    pub max_bytes: Option<u64>,
+    /// Keep the head (default) or the tail when max_bytes is exceeded.
+    #[serde(default)]
+    pub mode: CutMode,
+}
+
+/// Read a file with encoding, line-ending and binary detection instead of failing on non-UTF-8.
+/// Optionally reads only a byte/line range and caps the result at max_bytes.
 #[tauri::command]
-pub fn workspace_read_file(workspace_root: String, path: String) -> Result<String, String> {
-    let full = resolve(&workspace_root, &path)?;
-    std::fs::read_to_string(&full).map_err(|e| e.to_string())
+pub fn workspace_read_file_detailed(
+    workspace_root: String,
+    path: String,
+    options: Option<ReadFileOptions>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<ReadFileResult> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
+    let opts = options.unwrap_or_default();
+    let has_bytes = opts.byte_start.is_some() || opts.byte_end.is_some();
+    let has_lines = opts.line_start.is_some() || opts.line_end.is_some();
+    let range = match (has_bytes, has_lines) {
+        (true, true) => return Err(AppError::invalid("byte range and line range cannot be combined")),
+        (true, false) => ReadRange::Bytes(opts.byte_start.unwrap_or(0), opts.byte_end),
+        (false, true) => ReadRange::Lines(opts.line_start.unwrap_or(1), opts.line_end),
+        (false, false) => ReadRange::Whole,
+    };
+    let size_bytes = std::fs::metadata(&full).at(&full)?.len();
+    let w = textfile::read_window(&full, range, opts.max_bytes, opts.mode).at(&full)?;
+    Ok(ReadFileResult {
+        is_binary: w.decoded.text.is_none(),
+        content: w.decoded.text,
+        encoding: w.decoded.encoding,
+        line_ending: w.decoded.line_ending,
+        has_trailing_newline: w.decoded.trailing_newline,
+        size_bytes,
+        byte_start: w.byte_start,
+        byte_end: w.byte_end,
+        truncated: w.truncated,
+    })
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatsResult {
+    pub size_bytes: u64,
+    pub line_count: u64,
+    pub is_binary: bool,
+    pub encoding: TextEncoding,
+}
+
+/// Size and line count without decoding, so callers can budget context before reading.
+#[tauri::command]
+pub fn workspace_file_stats(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<FileStatsResult> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
+    let st = textfile::stats(&full).at(&full)?;
+    Ok(FileStatsResult {
+        size_bytes: st.size_bytes,
+        line_count: st.line_count,
+        is_binary: st.is_binary,
+        encoding: st.encoding,
+    })
+}
+
+/// Overrides for workspace_write_file. Unset fields keep the existing file's style
+/// (new files: UTF-8, content's own line endings and trailing newline).
+#[derive(Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteFileOptions {
+    pub encoding: Option<TextEncoding>,
+    pub line_ending: Option<LineEnding>,
+    pub trailing_newline: Option<bool>,
+    /// Write content as UTF-8 exactly as given, skipping all preservation.
+    #[serde(default)]
+    pub raw: bool,
+}
+
+/// Apply the target file's encoding, line endings and trailing newline (or the explicit
+/// overrides) to content.
+fn encode_for_write(full: &Path, content: &str, opts: &WriteFileOptions) -> AppResult<Vec<u8>> {
+    if opts.raw {
+        return Ok(content.as_bytes().to_vec());
+    }
+    let existing = match std::fs::read(full) {
+        Ok(bytes) => Some(textfile::decode(&bytes)).filter(|d| d.text.is_some()),
+        Err(_) => None,
+    };
+    let encoding = opts
+        .encoding
+        .or(existing.as_ref().map(|d| d.encoding))
+        .unwrap_or(TextEncoding::Utf8);
+    let line_ending = opts
+        .line_ending
+        .or(existing.as_ref().map(|d| d.line_ending))
+        .unwrap_or(LineEnding::Mixed);
+    // An empty existing file has no trailing-newline style to keep.
+    let trailing_newline = opts.trailing_newline.or(existing
+        .as_ref()
+        .filter(|d| d.text.as_deref().is_some_and(|t| !t.is_empty()))
+        .map(|d| d.trailing_newline));
+    let mut text = textfile::convert_line_endings(content, line_ending);
+    if let Some(want) = trailing_newline.filter(|_| !text.is_empty()) {
+        text = textfile::set_trailing_newline(&text, want, line_ending);
+    }
+    textfile::encode(&text, encoding).map_err(|e| AppError::new(ErrorKind::Unsupported, e))
+}
+
+/// Write a text file. By default keeps the existing file's encoding, line-ending style and
+/// trailing newline.
 #[tauri::command]
 pub fn workspace_write_file(
     workspace_root: String,
     path: String,
     content: String,
-) -> Result<(), String> {
-    let full = resolve(&workspace_root, &path)?;
+    options: Option<WriteFileOptions>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<()> {
+    write_file(&registry, &workspace_root, &path, &content, options)
+}
+
+fn write_file(
+    registry: &Mutex<WorkspaceRegistry>,
+    workspace_root: &str,
+    path: &str,
+    content: &str,
+    options: Option<WriteFileOptions>,
+) -> AppResult<()> {
+    registry::check_read(registry, workspace_root)?;
+    let full = resolve(workspace_root, path, allow_external_symlinks())?;
+    registry::check_write(registry, workspace_root, &full)?;
+    let bytes = encode_for_write(&full, content, &options.unwrap_or_default())?;
     if let Some(p) = full.parent() {
-        std::fs::create_dir_all(p).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(p).at(p)?;
     }
-    std::fs::write(&full, content.as_bytes()).map_err(|e| e.to_string())
+    std::fs::write(&full, bytes).at(&full)
 }
 
 /// Write a file under workspace root. Same as workspace_write_file; alias for file-editor use.
-/// Security: resolve() rejects ".." and ensures path stays under workspace_root.
+/// Security: resolve() keeps the path under workspace_root and the workspace policy applies.
 #[tauri::command]
 pub fn write_project_file(
     workspace_root: String,
     relative_path: String,
     content: String,
-) -> Result<(), String> {
-    workspace_write_file(workspace_root, relative_path, content)
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<()> {
+    write_file(&registry, &workspace_root, &relative_path, &content, None)
 }
 
 #[tauri::command]
-pub fn workspace_exists(workspace_root: String, path: String) -> Result<bool, String> {
-    let full = resolve(&workspace_root, &path)?;
+pub fn workspace_exists(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<bool> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
     Ok(full.try_exists().unwrap_or(false))
 }
 
 #[tauri::command]
-pub fn workspace_file_size(workspace_root: String, path: String) -> Result<u64, String> {
-    let full = resolve(&workspace_root, &path)?;
-    let meta = std::fs::metadata(&full).map_err(|e| e.to_string())?;
+pub fn workspace_file_size(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<u64> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
+    let meta = std::fs::metadata(&full).at(&full)?;
     Ok(meta.len())
 }
 
 #[tauri::command]
-pub fn workspace_mkdir_all(workspace_root: String, path: String) -> Result<(), String> {
-    let full = resolve(&workspace_root, &path)?;
-    std::fs::create_dir_all(&full).map_err(|e| e.to_string())
+pub fn workspace_mkdir_all(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<()> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
+    registry::check_write(&registry, &workspace_root, &full)?;
+    std::fs::create_dir_all(&full).at(&full)
 }
 
 /// Resolve relative path under workspace root; return absolute path as string.
 #[tauri::command]
-pub fn workspace_resolve_path(workspace_root: String, path: String) -> Result<String, String> {
-    let full = resolve(&workspace_root, &path)?;
+pub fn workspace_resolve_path(
+    workspace_root: String,
+    path: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<String> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
     Ok(full.to_string_lossy().replace('\\', "/"))
 }
 
-/// Create .devassistant/logs and return absolute path for llama-server.log.
+/// Create .devassistant/logs, send the app log (llama-server output included) there and
+/// return the absolute path of the active log file.
 #[tauri::command]
-pub fn workspace_ensure_log_dir(workspace_root: String) -> Result<String, String> {
-    let logs_dir = resolve(&workspace_root, ".devassistant/logs")?;
-    std::fs::create_dir_all(&logs_dir).map_err(|e| e.to_string())?;
-    let log_file = logs_dir.join("llama-server.log");
+pub fn workspace_ensure_log_dir(
+    workspace_root: String,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<String> {
+    registry::check_read(&registry, &workspace_root)?;
+    let logs_dir = resolve(&workspace_root, crate::logging::LOGS_REL, allow_external_symlinks())?;
+    registry::check_write(&registry, &workspace_root, &logs_dir)?;
+    std::fs::create_dir_all(&logs_dir).at(&logs_dir)?;
+    let log_file = crate::logging::attach(Path::new(&workspace_root))?;
     Ok(log_file.to_string_lossy().replace('\\', "/"))
 }
 
@@ -134,264 +441,334 @@ pub fn workspace_append_file(
     workspace_root: String,
     path: String,
     content: String,
-) -> Result<(), String> {
-    let full = resolve(&workspace_root, &path)?;
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<()> {
+    registry::check_read(&registry, &workspace_root)?;
+    let full = resolve(&workspace_root, &path, allow_external_symlinks())?;
+    registry::check_write(&registry, &workspace_root, &full)?;
     if let Some(p) = full.parent() {
-        std::fs::create_dir_all(p).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(p).at(p)?;
     }
     let mut f = OpenOptions::new()
         .create(true)
         .append(true)
         .write(true)
         .open(&full)
-        .map_err(|e| format!("append_file {}: {}", full.display(), e))?;
-    f.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
-    f.write_all(b"\n").map_err(|e| e.to_string())?;
-    f.flush().map_err(|e| e.to_string())?;
+        .at(&full)?;
+    f.write_all(content.as_bytes()).at(&full)?;
+    f.write_all(b"\n").at(&full)?;
+    f.flush().at(&full)?;
     Ok(())
 }
 
-/// Directories that must NEVER appear in search results (no descend, no files from under them).
-const SEARCH_IGNORED: &[&str] = &[
-    "node_modules",
-    "target",
-    "dist",
-    "build",
-    ".git",
-    ".devassistant",
-    "runtime",
-    "models",
-    "source", // excludes source/, source/DevAssistant, source/DevAssistant/_internal
-];
-/// Path prefixes to filter from results (defense in depth; use forward slash).
-const SEARCH_IGNORED_PREFIXES: &[&str] = &["source/", "runtime/", "models/"];
-const SEARCH_MAX_DEPTH: u32 = 8;
-const SEARCH_MAX_RESULTS: usize = 20;
-
-/// Search files by name under workspace root. Returns relative paths (max 20), sorted:
-/// exact filename > exact stem > partial, then fewer segments (root-near), then shorter path, then alphabetical.
+/// Fuzzy-search files by relative path under workspace root (fzf-style: "wrkspcsvc" finds
+/// WorkspaceService.ts, "core/patch" matches path fragments). Returns up to `limit`
+/// (default limits.nameSearchDefaultResults) relative paths: exact filename > exact stem >
+/// fuzzy score, then shorter path.
+/// The file list is cached per root; ignore rules come from the shared walker.
 #[tauri::command]
 pub fn workspace_search_files_by_name(
     workspace_root: String,
     file_name: String,
-) -> Result<Vec<String>, String> {
-    let root = Path::new(&workspace_root);
-    if !root.is_absolute() {
-        return Err("workspace_root must be absolute".into());
-    }
-    let root_canon = root.canonicalize().map_err(|e| e.to_string())?;
-    let search_lower = file_name.trim().to_lowercase();
-    if search_lower.is_empty() {
+    limit: Option<usize>,
+    cache: tauri::State<'_, Mutex<FileListCache>>,
+    registry: tauri::State<'_, Mutex<WorkspaceRegistry>>,
+) -> AppResult<Vec<String>> {
+    let root_canon = registry::check_read(&registry, &workspace_root)?;
+    if file_name.trim().is_empty() {
         return Ok(Vec::new());
     }
-    let mut matches: Vec<String> = Vec::new();
-    walk_for_name(
-        &root_canon,
-        PathBuf::new(),
-        0,
-        &search_lower,
-        &mut matches,
-    )?;
-    matches.retain(|p| {
-        let n = p.replace('\\', "/");
-        !SEARCH_IGNORED_PREFIXES.iter().any(|pref| n.starts_with(*pref))
-    });
-    sort_search_results(&mut matches, &search_lower);
-    if matches.len() > SEARCH_MAX_RESULTS {
-        matches.truncate(SEARCH_MAX_RESULTS);
-    }
-    Ok(matches)
+    let limits = crate::settings::load(Some(&root_canon)).limits;
+    let limit = limit
+        .unwrap_or(limits.name_search_default_results)
+        .min(limits.name_search_max_results);
+    let files = fuzzy::file_list(&cache, &root_canon)?;
+    Ok(fuzzy::rank(&files, &file_name, limit))
 }
 
-fn walk_for_name(
-    root: &Path,
-    rel: PathBuf,
-    depth: u32,
-    search_lower: &str,
-    out: &mut Vec<String>,
-) -> Result<(), String> {
-    if depth > SEARCH_MAX_DEPTH || out.len() >= SEARCH_MAX_RESULTS {
-        return Ok(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh directory holding `ws/` (the workspace root) and `outside/` next to it.
+    struct Fixture {
+        base: PathBuf,
+        root: PathBuf,
+        outside: PathBuf,
     }
-    let full = root.join(&rel);
-    let entries = match std::fs::read_dir(&full) {
-        Ok(e) => e,
-        Err(_) => return Ok(()),
-    };
-    for e in entries {
-        if out.len() >= SEARCH_MAX_RESULTS {
-            break;
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let base = std::env::temp_dir().join(format!("ws-resolve-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&base);
+            let root = base.join("ws");
+            let outside = base.join("outside");
+            std::fs::create_dir_all(root.join("src")).unwrap();
+            std::fs::create_dir_all(&outside).unwrap();
+            std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+            std::fs::write(outside.join("secret.txt"), "secret").unwrap();
+            Fixture { base, root, outside }
         }
-        let e = e.map_err(|e| e.to_string())?;
-        let name = e.file_name().to_string_lossy().into_owned();
-        let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
-        if is_dir {
-            if SEARCH_IGNORED.iter().any(|&d| d.eq_ignore_ascii_case(&name)) {
-                continue;
-            }
-            let next_rel = rel.join(&name);
-            walk_for_name(root, next_rel, depth + 1, search_lower, out)?;
-        } else {
-            let name_lower = name.to_lowercase();
-            let stem = Path::new(&name)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_lowercase();
-            let exact = name_lower == *search_lower || stem == *search_lower;
-            let fuzzy = name_lower.contains(search_lower) || stem.contains(search_lower);
-            if exact || fuzzy {
-                let rel_str = rel.join(&name).to_string_lossy().replace('\\', "/");
-                out.push(rel_str);
-            }
+
+        fn canon(&self) -> PathBuf {
+            self.root.canonicalize().unwrap()
+        }
+
+        fn resolve(&self, rel: &str) -> AppResult<PathBuf> {
+            resolve_with(&self.root, rel, false)
+        }
+
+        fn kind(&self, rel: &str) -> ErrorKind {
+            self.resolve(rel).map(|p| panic!("{:?} resolved to {:?}", rel, p)).unwrap_err().kind
         }
     }
-    Ok(())
-}
 
-fn sort_search_results(matches: &mut [String], search_lower: &str) {
-    fn rank(path: &str, search_lower: &str) -> (u8, usize, usize) {
-        let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
-        let stem = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
-        let tier = if name == search_lower {
-            0u8 // exact filename
-        } else if stem == search_lower {
-            1 // exact stem
-        } else {
-            2 // partial
-        };
-        let segments = path.matches(|c| c == '/' || c == '\\').count() + 1;
-        (tier, segments, path.len())
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.base);
+        }
     }
-    matches.sort_by(|a, b| {
-        let (a_tier, a_seg, a_len) = rank(a, search_lower);
-        let (b_tier, b_seg, b_len) = rank(b, search_lower);
-        a_tier.cmp(&b_tier)
-            .then(a_seg.cmp(&b_seg))
-            .then(a_len.cmp(&b_len))
-            .then(a.cmp(b))
-    });
-}
 
-// --- Snapshot walk ---
+    #[test]
+    fn plain_paths_resolve_under_canonical_root() {
+        let f = Fixture::new("plain");
+        assert_eq!(f.resolve("src/main.rs").unwrap(), f.canon().join("src/main.rs"));
+        assert_eq!(f.resolve("").unwrap(), f.canon());
+        assert_eq!(f.resolve(".").unwrap(), f.canon());
+        assert_eq!(f.resolve("./src/./main.rs").unwrap(), f.canon().join("src/main.rs"));
+        assert_eq!(f.resolve("src//main.rs").unwrap(), f.canon().join("src/main.rs"));
+    }
 
-const SNAPSHOT_IGNORED: &[&str] = &[
-    "node_modules", ".git", "dist", "build", ".next", "out", ".turbo", ".cache",
-    "coverage", "target", ".venv", "venv", "__pycache__", ".DS_Store", ".devassistant",
-];
-const SNAPSHOT_MAX_DEPTH: u32 = 25;
-const SNAPSHOT_MAX_FILES: usize = 2000;
-const SNAPSHOT_MAX_FILE_BYTES: u64 = 2 * 1024 * 1024; // 2MB
+    #[test]
+    fn missing_paths_are_allowed() {
+        let f = Fixture::new("missing");
+        assert_eq!(f.resolve("new/dir/file.txt").unwrap(), f.canon().join("new/dir/file.txt"));
+    }
 
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SnapshotFileEntry {
-    pub path: String,
-    pub size_bytes: u64,
-    pub modified_at: String,
-}
+    #[test]
+    fn names_containing_dots_are_accepted() {
+        let f = Fixture::new("dots");
+        for rel in ["foo..bar.txt", "..foo", "foo..", "src/...", "a/..b/c", ".hidden"] {
+            assert_eq!(f.resolve(rel).unwrap(), f.canon().join(rel), "{}", rel);
+        }
+    }
 
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WalkSnapshotResult {
-    pub total_files: u64,
-    pub total_dirs: u64,
-    pub files: Vec<SnapshotFileEntry>,
-    pub top_level: Vec<String>,
-}
+    #[test]
+    fn parent_components_stay_inside_or_fail() {
+        let f = Fixture::new("parent");
+        assert_eq!(f.resolve("src/../Cargo.toml").unwrap(), f.canon().join("Cargo.toml"));
+        assert_eq!(f.resolve("a/b/../../src").unwrap(), f.canon().join("src"));
+        for rel in ["..", "../outside/secret.txt", "src/../../outside", "a/../../ws/src", "./../x"] {
+            assert_eq!(f.kind(rel), ErrorKind::EscapesWorkspace, "{}", rel);
+        }
+    }
 
-#[tauri::command]
-pub fn workspace_walk_snapshot(
-    workspace_root: String,
-) -> Result<WalkSnapshotResult, String> {
-    let root = Path::new(&workspace_root);
-    if !root.is_absolute() {
-        return Err("workspace_root must be absolute".into());
+    #[test]
+    fn escape_error_reports_requested_path() {
+        let f = Fixture::new("details");
+        let e = f.resolve("../outside").unwrap_err();
+        assert_eq!(e.details.get("path").and_then(|v| v.as_str()), Some("../outside"));
     }
-    let root_canon = root.canonicalize().map_err(|e| e.to_string())?;
 
-    let mut total_files: u64 = 0;
-    let mut total_dirs: u64 = 0;
-    let mut files: Vec<SnapshotFileEntry> = Vec::new();
-    let mut top_level: Vec<String> = Vec::new();
+    #[test]
+    fn root_must_be_absolute_and_exist() {
+        let f = Fixture::new("root");
+        assert_eq!(
+            resolve_with(Path::new("relative/ws"), "x", false).unwrap_err().kind,
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            resolve_with(&f.base.join("nope"), "x", false).unwrap_err().kind,
+            ErrorKind::NotFound
+        );
+    }
 
-    let mut stack: Vec<(PathBuf, u32, String)> = vec![(root_canon.clone(), 0, String::new())];
+    #[test]
+    fn absolute_paths_under_root_are_made_relative() {
+        let f = Fixture::new("absolute");
+        let abs = f.root.join("src/main.rs");
+        assert_eq!(f.resolve(&abs.to_string_lossy()).unwrap(), f.canon().join("src/main.rs"));
+        let abs = f.canon().join("src/main.rs");
+        assert_eq!(f.resolve(&abs.to_string_lossy()).unwrap(), f.canon().join("src/main.rs"));
+    }
 
-    while let Some((dir, depth, rel_prefix)) = stack.pop() {
-        if depth > SNAPSHOT_MAX_DEPTH {
-            continue;
-        }
-        if files.len() >= SNAPSHOT_MAX_FILES {
-            break;
+    #[test]
+    fn absolute_paths_outside_root_escape() {
+        let f = Fixture::new("rooted");
+        assert_eq!(f.kind("/etc/passwd"), ErrorKind::EscapesWorkspace);
+        let outside = f.outside.join("secret.txt");
+        assert_eq!(f.kind(&outside.to_string_lossy()), ErrorKind::EscapesWorkspace);
+    }
+
+    #[test]
+    fn windows_prefixes_outside_root_escape() {
+        let f = Fixture::new("prefix");
+        for rel in [
+            "C:\\Windows\\System32\\drivers\\etc\\hosts",
+            "C:/Windows",
+            "c:foo.txt",
+            "\\\\server\\share\\file.txt",
+            "\\\\?\\C:\\Windows",
+            "\\\\?\\UNC\\server\\share\\x",
+            "\\\\.\\pipe\\name",
+        ] {
+            assert_eq!(f.kind(rel), ErrorKind::EscapesWorkspace, "{}", rel);
         }
+    }
 
-        let entries = match std::fs::read_dir(&dir) {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+    #[test]
+    fn windows_prefix_detection() {
+        assert!(has_windows_prefix("C:"));
+        assert!(has_windows_prefix("z:\\x"));
+        assert!(has_windows_prefix("\\\\server\\share"));
+        assert!(!has_windows_prefix("src/c:x"));
+        assert!(!has_windows_prefix("1:x"));
+        assert!(!has_windows_prefix("\\x"));
+        assert!(!has_windows_prefix(""));
+    }
 
-        for e in entries.flatten() {
-            let ft = e.file_type();
-            if ft.as_ref().map(|t| t.is_symlink()).unwrap_or(false) {
-                continue;
-            }
-            let name = e.file_name().to_string_lossy().into_owned();
-            let is_dir = ft.map(|t| t.is_dir()).unwrap_or(false);
-            let rel_path = if rel_prefix.is_empty() {
-                name.clone()
-            } else {
-                format!("{}/{}", rel_prefix, name)
-            };
-
-            if is_dir {
-                if SNAPSHOT_IGNORED.iter().any(|&d| d.eq_ignore_ascii_case(&name)) {
-                    continue;
-                }
-                total_dirs += 1;
-                if depth == 0 {
-                    top_level.push(name.clone());
-                }
-                let full = dir.join(&name);
-                stack.push((full, depth + 1, rel_path));
-            } else {
-                total_files += 1;
-                if depth == 0 {
-                    top_level.push(name.clone());
-                }
-                let full = dir.join(&name);
-                let size = std::fs::metadata(&full).map(|m| m.len()).unwrap_or(0);
-                let modified = std::fs::metadata(&full)
-                    .and_then(|m| m.modified())
-                    .ok();
-                let modified_iso = modified
-                    .and_then(|t| {
-                        t.duration_since(std::time::UNIX_EPOCH)
-                            .ok()
-                            .and_then(|d| {
-                                Utc.timestamp_opt(d.as_secs() as i64, 0)
-                                    .single()
-                                    .map(|dt| dt.to_rfc3339())
-                            })
-                    })
-                    .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string());
-
-                if size <= SNAPSHOT_MAX_FILE_BYTES && files.len() < SNAPSHOT_MAX_FILES {
-                    files.push(SnapshotFileEntry {
-                        path: rel_path.replace('\\', "/"),
-                        size_bytes: size,
-                        modified_at: modified_iso,
-                    });
-                }
+    #[test]
+    fn verbatim_prefixes_are_simplified() {
+        assert_eq!(simplify(Path::new("\\\\?\\C:\\ws")), PathBuf::from("C:\\ws"));
+        assert_eq!(
+            simplify(Path::new("\\\\?\\UNC\\server\\share\\ws")),
+            PathBuf::from("\\\\server\\share\\ws")
+        );
+        assert_eq!(simplify(Path::new("\\\\?\\Volume{x}\\ws")), PathBuf::from("\\\\?\\Volume{x}\\ws"));
+        assert_eq!(simplify(Path::new("/home/ws")), PathBuf::from("/home/ws"));
+    }
+
+    #[cfg(unix)]
+    mod symlinks {
+        use super::*;
+        use std::os::unix::fs::symlink;
+
+        #[test]
+        fn link_to_outside_directory_escapes() {
+            let f = Fixture::new("link-dir");
+            symlink(&f.outside, f.root.join("out")).unwrap();
+            for rel in ["out", "out/secret.txt", "out/new.txt", "out/a/b/c.txt"] {
+                assert_eq!(f.kind(rel), ErrorKind::EscapesWorkspace, "{}", rel);
             }
         }
-    }
 
-    top_level.sort();
+        #[test]
+        fn link_to_system_directory_escapes() {
+            let f = Fixture::new("link-etc");
+            symlink("/etc", f.root.join("etc")).unwrap();
+            assert_eq!(f.kind("etc/passwd"), ErrorKind::EscapesWorkspace);
+            assert_eq!(f.kind("/etc/passwd"), ErrorKind::EscapesWorkspace);
+        }
 
-    Ok(WalkSnapshotResult {
-        total_files,
-        total_dirs,
-        files,
-        top_level,
-    })
+        #[test]
+        fn link_to_outside_file_escapes() {
+            let f = Fixture::new("link-file");
+            symlink(f.outside.join("secret.txt"), f.root.join("secret.txt")).unwrap();
+            assert_eq!(f.kind("secret.txt"), ErrorKind::EscapesWorkspace);
+            let e = f.resolve("secret.txt").unwrap_err();
+            assert!(e.details.contains_key("target"));
+        }
+
+        #[test]
+        fn relative_link_climbing_out_escapes() {
+            let f = Fixture::new("link-rel");
+            symlink("../../outside", f.root.join("src/up")).unwrap();
+            assert_eq!(f.kind("src/up/secret.txt"), ErrorKind::EscapesWorkspace);
+        }
+
+        #[test]
+        fn link_to_root_parent_escapes() {
+            let f = Fixture::new("link-parent");
+            symlink("..", f.root.join("parent")).unwrap();
+            assert_eq!(f.kind("parent/outside/secret.txt"), ErrorKind::EscapesWorkspace);
+            // Out through the link and back in lands inside the root, which is fine.
+            assert_eq!(
+                f.resolve("parent/ws/src/main.rs").unwrap(),
+                f.canon().join("parent/ws/src/main.rs")
+            );
+        }
+
+        #[test]
+        fn dangling_link_to_outside_escapes() {
+            let f = Fixture::new("dangling-out");
+            symlink(f.outside.join("created-later.txt"), f.root.join("later.txt")).unwrap();
+            symlink(f.outside.join("no/such/dir"), f.root.join("later-dir")).unwrap();
+            assert_eq!(f.kind("later.txt"), ErrorKind::EscapesWorkspace);
+            assert_eq!(f.kind("later-dir/x.txt"), ErrorKind::EscapesWorkspace);
+        }
+
+        #[test]
+        fn dangling_relative_link_with_parent_components_escapes() {
+            let f = Fixture::new("dangling-rel");
+            symlink("../missing/../../outside/new.txt", f.root.join("d")).unwrap();
+            assert_eq!(f.kind("d"), ErrorKind::EscapesWorkspace);
+        }
+
+        #[test]
+        fn links_inside_root_are_kept() {
+            let f = Fixture::new("link-inside");
+            symlink(f.root.join("src"), f.root.join("alias")).unwrap();
+            symlink("main.rs", f.root.join("src/entry.rs")).unwrap();
+            symlink("src/not-yet.rs", f.root.join("pending.rs")).unwrap();
+            assert_eq!(f.resolve("alias/main.rs").unwrap(), f.canon().join("alias/main.rs"));
+            assert_eq!(f.resolve("src/entry.rs").unwrap(), f.canon().join("src/entry.rs"));
+            assert_eq!(f.resolve("pending.rs").unwrap(), f.canon().join("pending.rs"));
+        }
+
+        #[test]
+        fn link_chain_ending_outside_escapes() {
+            let f = Fixture::new("chain");
+            symlink(&f.outside, f.root.join("hop2")).unwrap();
+            symlink("hop2", f.root.join("hop1")).unwrap();
+            assert_eq!(f.kind("hop1/secret.txt"), ErrorKind::EscapesWorkspace);
+        }
+
+        #[test]
+        fn link_loop_is_an_error() {
+            let f = Fixture::new("loop");
+            symlink("b", f.root.join("a")).unwrap();
+            symlink("a", f.root.join("b")).unwrap();
+            assert!(f.resolve("a/x.txt").is_err());
+            assert!(f.resolve("a").is_err());
+        }
+
+        #[test]
+        fn root_behind_a_link_resolves() {
+            let f = Fixture::new("root-link");
+            let link = f.base.join("ws-link");
+            symlink(&f.root, &link).unwrap();
+            let got = resolve_with(&link, "src/main.rs", false).unwrap();
+            assert_eq!(got, f.canon().join("src/main.rs"));
+            let abs = link.join("src/main.rs");
+            let got = resolve_with(&link, &abs.to_string_lossy(), false).unwrap();
+            assert_eq!(got, f.canon().join("src/main.rs"));
+        }
+
+        #[test]
+        fn external_links_allowed_when_configured() {
+            let f = Fixture::new("allowed");
+            symlink(&f.outside, f.root.join("out")).unwrap();
+            let got = resolve_with(&f.root, "out/secret.txt", true).unwrap();
+            assert_eq!(got, f.canon().join("out/secret.txt"));
+            assert_eq!(std::fs::read_to_string(got).unwrap(), "secret");
+            // Lexical escapes stay refused.
+            assert_eq!(
+                resolve_with(&f.root, "../outside", true).unwrap_err().kind,
+                ErrorKind::EscapesWorkspace
+            );
+        }
+
+        #[test]
+        fn writes_through_escaping_links_are_refused() {
+            let f = Fixture::new("commands");
+            symlink(&f.outside, f.root.join("out")).unwrap();
+            let registry = Mutex::new(WorkspaceRegistry::default());
+            registry.lock().unwrap().insert(&f.root, false).unwrap();
+            let root = f.root.to_string_lossy().into_owned();
+            let e = write_file(&registry, &root, "out/pwned.txt", "x", None).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::EscapesWorkspace);
+            assert!(!f.outside.join("pwned.txt").exists());
+            write_file(&registry, &root, "src/ok.rs", "x", None).unwrap();
+        }
+    }
 }
@@ -10,6 +10,7 @@ import {
   writeProjectSnapshot,
   readWorkspaceSettings,
   writeWorkspaceSettings,
+  logsAttach,
   findToolRoot,
   scanModelsForGGUF,
   toolRootExists,
@@ -41,8 +42,11 @@ import {
   generateSnapshotData,
   writeProjectSnapshotFile,
   getSnapshotOutputPath,
+  describeError,
+  errorMessage,
 } from "./core";
 import type {
+  CheckFinishedEvent,
   FileTreeNode,
   PlanAndPatch,
   PlannerOutput,
@@ -100,6 +104,7 @@ interface Message {
 
 export default function App() {
   const [workspacePath, setWorkspacePath] = useState<string | null>(null);
+  const [readOnly, setReadOnly] = useState(false);
   const [fileTree, setFileTree] = useState<FileTreeNode[]>([]);
   const [manifest, setManifest] = useState<import("./core/types").ProjectManifest | null>(null);
   const [selectedPaths, setSelectedPaths] = useState<string[]>([]);
@@ -245,19 +250,35 @@ export default function App() {
     }
   }, []);
 
+  const toggleReadOnly = useCallback(async () => {
+    try {
+      const policy = await workspace.setReadOnly(!(workspace.policy?.readOnly ?? false));
+      setReadOnly(policy.readOnly);
+    } catch (e) {
+      setStatusLine(`Read-only unchanged: ${describeError(e)}`);
+    }
+  }, []);
+
   const openWorkspace = useCallback(async () => {
     const path = await workspace.openWorkspace();
     if (!path) return;
     setWorkspacePath(path);
+    setReadOnly(workspace.policy?.readOnly ?? false);
     setProjectSnapshot(null);
     setEnabledPacks([]);
     setToolRoot(null);
     setStatusLine("Scanning workspace…");
     try {
-      const root = workspace.root ?? path;
+      let root = workspace.root ?? path;
 
+      const wider = await workspace.openEnclosingWorkspace();
+      if (wider) {
+        root = wider;
+        setWorkspacePath(root);
+      }
       const projectRootResult = await detectProjectRoot(root);
       console.log("[init] project root:", projectRootResult.rootPath, "type:", projectRootResult.detectedType, "signals:", projectRootResult.signalsFound);
+      await logsAttach(root).catch((e) => console.warn("[init] log attach failed:", e));
 
       const inspector = new ProjectInspector(workspace);
       const m = await inspector.buildManifest();
@@ -298,7 +319,8 @@ export default function App() {
             importantFiles: detected.importantFiles,
             detectedCommands: detected.detectedCommands,
             enabledPacks: enabled,
-          }
+          },
+          true
         );
         await writeProjectSnapshotFile(root, fullSnapshot);
       } else {
@@ -398,7 +420,7 @@ export default function App() {
             console.error("sendChatMessage file choice", e);
             setMessages((prev) => [
               ...prev,
-              { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${String(e)}` },
+              { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${describeError(e)}` },
             ]);
           }
           return;
@@ -499,7 +521,7 @@ export default function App() {
             console.error("sendChatMessage patch", e);
             setMessages((prev) => [
               ...prev,
-              { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${String(e)}` },
+              { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${describeError(e)}` },
             ]);
           } finally {
             setStatusLine(null);
@@ -574,7 +596,7 @@ export default function App() {
         console.error("sendChatMessage", e);
         setMessages((prev) => [
           ...prev,
-          { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${String(e)}` },
+          { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${describeError(e)}` },
         ]);
       } finally {
         setStatusLine(null);
@@ -637,7 +659,7 @@ export default function App() {
         console.error("proposePatch", e);
         setMessages((prev) => [
           ...prev,
-          { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${String(e)}` },
+          { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${describeError(e)}` },
         ]);
       } finally {
         setStatusLine(null);
@@ -717,7 +739,7 @@ export default function App() {
         console.error("executePipeline", e);
         setMessages((prev) => [
           ...prev,
-          { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${String(e)}` },
+          { id: `a-${Date.now()}`, role: "assistant", text: `Error: ${describeError(e)}` },
         ]);
       } finally {
         setStatusLine(null);
@@ -774,7 +796,11 @@ export default function App() {
       const engine = new PatchEngine(workspace.root, (p) =>
         workspace.readFile(p)
       );
-      const result = await engine.apply(planAndPatch.patch);
+      const { checkpoints } = await readWorkspaceSettings(workspace.root);
+      const result =
+        checkpoints === false
+          ? await engine.apply(planAndPatch.patch)
+          : await engine.applyCheckpointed(planAndPatch.patch, currentProposedSessionId);
       setLastBeforeSnapshots(result.beforeSnapshots);
       const store = new MemoryStore(workspace.root);
       await store.updateSessionToApplied(currentProposedSessionId, result.beforeSnapshots);
@@ -822,7 +848,8 @@ export default function App() {
         const engine = new PatchEngine(workspace.root, (p) =>
           workspace.readFile(p)
         );
-        await engine.revert(lastBeforeSnapshots);
+        if (lastAppliedSessionId) await engine.revertCheckpointed(lastAppliedSessionId, lastBeforeSnapshots);
+        else await engine.revert(lastBeforeSnapshots);
         setLastBeforeSnapshots(null);
         setLastAppliedSessionId(null);
         setPlanAndPatch(null);
@@ -916,7 +943,7 @@ export default function App() {
     } catch (e) {
       console.error("handleFileEditSave", e);
       setFileEditState((prev) =>
-        prev ? { ...prev, lastSaveStatus: "error", saveError: String(e) } : null
+        prev ? { ...prev, lastSaveStatus: "error", saveError: errorMessage(e) } : null
       );
     }
   }, [fileEditState]);
@@ -953,11 +980,63 @@ export default function App() {
     );
   }, [fileEditState]);
 
-  const runChecks = useCallback(() => {
-    setStatusLine("Running checks…");
-    setTimeout(() => setStatusLine(null), 800);
-    /* TODO: TaskRunner; Sprint 3 */
-  }, []);
+  /** Run the detected checks one by one (stopping at the first failure), recording them into the last applied session. */
+  const runChecks = useCallback(async () => {
+    const root = workspace.root;
+    if (!root) return;
+    const checks = await workspace.listChecks().catch((e) => {
+      console.error("listChecks", e);
+      return [];
+    });
+    if (checks.length === 0) {
+      setStatusLine("No checks detected for this project.");
+      setTimeout(() => setStatusLine(null), 1500);
+      return;
+    }
+    const finished = new Map<string, CheckFinishedEvent>();
+    const waiters = new Map<string, (e: CheckFinishedEvent) => void>();
+    const unlisten = await workspace.onCheckEvents(
+      () => {},
+      (e) => {
+        const runId = e.record.runId;
+        const waiter = runId ? waiters.get(runId) : undefined;
+        if (waiter) waiter(e);
+        else if (runId) finished.set(runId, e);
+      }
+    );
+    const sessionId = lastAppliedSessionId ?? undefined;
+    let failed: CheckFinishedEvent | null = null;
+    try {
+      for (const check of checks) {
+        setStatusLine(`Running ${check.command}${check.cwd ? ` (${check.cwd})` : ""}…`);
+        const started = await workspace.runCheck(check.id, { sessionId });
+        const result =
+          finished.get(started.runId) ??
+          (await new Promise<CheckFinishedEvent>((resolve) => waiters.set(started.runId, resolve)));
+        if (sessionId) await new MemoryStore(root).appendCheck(sessionId, result.record);
+        if (result.record.exitCode !== 0) {
+          failed = result;
+          break;
+        }
+      }
+    } catch (e) {
+      console.error("runChecks", e);
+    } finally {
+      unlisten();
+    }
+    if (sessionId) await fetchSessionsAndResume();
+    if (failed) {
+      const { record, diagnostics } = failed;
+      const first = diagnostics.find((d) => d.severity === "error" && d.file);
+      const where = first ? ` — ${first.file}:${first.line ?? "?"}: ${first.message.split("\n")[0]}` : ` — see ${record.outputPathRef}`;
+      setStatusLine(
+        `Check failed: ${record.command} (${record.timedOut ? "timed out" : `exit ${record.exitCode}`}, ${record.errors ?? 0} errors)${where}`
+      );
+    } else {
+      setStatusLine("Checks passed.");
+    }
+    setTimeout(() => setStatusLine(null), 3000);
+  }, [lastAppliedSessionId, fetchSessionsAndResume]);
 
   const viewSession = useCallback(
     async (s: SessionRecord) => {
@@ -988,7 +1067,9 @@ export default function App() {
         const engine = new PatchEngine(workspace.root, (p) =>
           workspace.readFile(p)
         );
-        const result = await engine.apply(s.patch);
+        const { checkpoints } = await readWorkspaceSettings(workspace.root);
+        const result =
+          checkpoints === false ? await engine.apply(s.patch) : await engine.applyCheckpointed(s.patch, s.id);
         const store = new MemoryStore(workspace.root);
         await store.updateSessionToApplied(s.id, result.beforeSnapshots);
         if (viewingSessionId === s.id) {
@@ -1018,7 +1099,7 @@ export default function App() {
         const engine = new PatchEngine(workspace.root, (p) =>
           workspace.readFile(p)
         );
-        await engine.revert(snapshots as FileSnapshot[]);
+        await engine.revertCheckpointed(s.id, snapshots as FileSnapshot[]);
         const store = new MemoryStore(workspace.root);
         await store.updateSessionStatus(s.id, "reverted");
         if (viewingSessionId === s.id) {
@@ -1044,6 +1125,34 @@ export default function App() {
     [viewingSessionId, fetchSessionsAndResume]
   );
 
+  /** Commit an applied session's files with the generated message, optionally on a new branch. */
+  const commitSession = useCallback(async (s: SessionRecord) => {
+    if (!workspace.root || s.status !== "applied") return;
+    try {
+      const plan = await workspace.sessionCommitPlan(s.id);
+      let allowUnrelatedStaged = false;
+      if (plan.unrelatedStaged.length > 0) {
+        allowUnrelatedStaged = window.confirm(
+          `These staged changes are not part of the session:\n${plan.unrelatedStaged.join("\n")}\n\nCommit them too?`
+        );
+        if (!allowUnrelatedStaged) return;
+      }
+      const branch = window.prompt(
+        `Commit ${plan.files.length} file(s):\n\n${plan.message}\nNew branch (leave empty to commit on ${plan.currentBranch ?? "detached HEAD"}):`,
+        plan.branchSuggestion
+      );
+      if (branch === null) return;
+      const result = await workspace.commitSession(s.id, {
+        branch: branch.trim() || undefined,
+        allowUnrelatedStaged,
+      });
+      setStatusLine(`Committed ${result.shortId}${result.branch ? ` on ${result.branch}` : ""}.`);
+    } catch (e) {
+      setStatusLine(`Commit failed: ${errorMessage(e)}`);
+    }
+    setTimeout(() => setStatusLine(null), 3000);
+  }, []);
+
   const selectFilesForContext = useCallback(async () => {
     const paths = await workspace.pickContextFiles();
     if (paths.length)
@@ -1061,7 +1170,12 @@ export default function App() {
 
   return (
     <div className="app app-single-flow">
-      <TopBar workspacePath={workspacePath} onOpenWorkspace={openWorkspace} />
+      <TopBar
+        workspacePath={workspacePath}
+        onOpenWorkspace={openWorkspace}
+        readOnly={readOnly}
+        onToggleReadOnly={toggleReadOnly}
+      />
       <div className="main-two-pane">
         <ConversationPane
           messages={messages}
@@ -1114,6 +1228,7 @@ export default function App() {
           onViewSession={viewSession}
           onApplySession={applySession}
           onRevertSession={revertSession}
+          onCommitSession={commitSession}
           showDiffPanel={showDiffPanel}
           patch={planAndPatch?.patch ?? null}
           previewMap={previewMap}
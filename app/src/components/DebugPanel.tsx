@@ -1,3 +1,7 @@
+import { useEffect, useState } from "react";
+import { logsQuery, logsTail } from "../core";
+import type { LogRecord } from "../core";
+
 interface DebugPanelProps {
   workspaceRoot: string | null;
   selectedFilesCount: number;
@@ -5,12 +9,40 @@ interface DebugPanelProps {
   lastSessionId: string | null;
 }
 
+const LOG_POLL_MS = 2000;
+const LOG_LINES = 100;
+
+function formatLog(r: LogRecord): string {
+  const time = r.ts.slice(11, 23);
+  const instance = r.instance ? ` [${r.instance}]` : "";
+  return `${time} ${r.level.padEnd(5)} ${r.target}${instance}: ${r.message}`;
+}
+
 export function DebugPanel({
   workspaceRoot,
   selectedFilesCount,
   hasProposedPatch,
   lastSessionId,
 }: DebugPanelProps) {
+  const [logs, setLogs] = useState<LogRecord[]>([]);
+  const [errorsOnly, setErrorsOnly] = useState(false);
+  const [fromFiles, setFromFiles] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    const query = { limit: LOG_LINES, minLevel: errorsOnly ? ("WARN" as const) : undefined };
+    const load = () =>
+      (fromFiles && workspaceRoot ? logsTail(workspaceRoot, query) : logsQuery(query))
+        .then((r) => !cancelled && setLogs(r))
+        .catch(() => {});
+    load();
+    const t = setInterval(load, LOG_POLL_MS);
+    return () => {
+      cancelled = true;
+      clearInterval(t);
+    };
+  }, [workspaceRoot, errorsOnly, fromFiles]);
+
   return (
     <div className="debug-panel">
       <h4>Debug</h4>
@@ -20,6 +52,23 @@ export function DebugPanel({
         {"\n"}hasProposedPatch: {String(hasProposedPatch)}
         {"\n"}lastSessionId: {lastSessionId ?? "(null)"}
       </pre>
+      <h4>
+        Log{" "}
+        <label>
+          <input type="checkbox" checked={errorsOnly} onChange={(e) => setErrorsOnly(e.target.checked)} />
+          warnings and errors
+        </label>{" "}
+        <label>
+          <input
+            type="checkbox"
+            checked={fromFiles}
+            disabled={!workspaceRoot}
+            onChange={(e) => setFromFiles(e.target.checked)}
+          />
+          include earlier sessions
+        </label>
+      </h4>
+      <pre className="debug-logs">{logs.length ? logs.map(formatLog).join("\n") : "(no log lines)"}</pre>
     </div>
   );
 }
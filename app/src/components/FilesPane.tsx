@@ -17,6 +17,7 @@ interface FilesPaneProps {
   onViewSession: (s: SessionRecord) => void;
   onApplySession: (s: SessionRecord) => void;
   onRevertSession: (s: SessionRecord) => void;
+  onCommitSession?: (s: SessionRecord) => void;
   /** When View Diff clicked: show diff panel. */
   showDiffPanel: boolean;
   patch: string | null;
@@ -43,6 +44,7 @@ export function FilesPane({
   onViewSession,
   onApplySession,
   onRevertSession,
+  onCommitSession,
   showDiffPanel,
   patch,
   previewMap,
@@ -163,6 +165,7 @@ export function FilesPane({
           onView={onViewSession}
           onApply={onApplySession}
           onRevert={onRevertSession}
+          onCommit={onCommitSession}
           applyInProgress={applyInProgress}
         />
       </div>
@@ -6,6 +6,7 @@ import {
   type StartLocalModelStatus,
 } from "../core/runtime/runtimeConfig";
 import { runtimeStatus } from "../core/runtime/runtimeApi";
+import { describeError, errorMessage } from "../core/errors";
 
 type ServerStatus = "—" | "starting" | "running" | "error";
 type PanelSize = "small" | "medium" | "large";
@@ -113,14 +114,15 @@ export function RuntimeStatusPanel({ workspaceRoot }: RuntimeStatusPanelProps) {
       }
     } catch (e) {
       setServerStatus("error");
-      setLastError(String(e));
-      setLastResult(`error | ${String(e)}`);
+      setLastError(describeError(e));
+      setLastResult(`error | ${errorMessage(e)}`);
     } finally {
       await detect();
     }
   }, [workspaceRoot, detect]);
 
-  const logRoot = paths.toolRoot ?? paths.workspaceRoot;
+  // Logs live in the opened workspace; the tool root is outside it.
+  const logRoot = paths.workspaceRoot;
 
   const handleCreateLogDir = useCallback(async () => {
     if (!logRoot) {
@@ -7,6 +7,8 @@ interface SessionsPanelProps {
   onView: (s: SessionRecord) => void;
   onApply: (s: SessionRecord) => void;
   onRevert: (s: SessionRecord) => void;
+  /** Commit an applied session's files to git. */
+  onCommit?: (s: SessionRecord) => void;
   applyInProgress: boolean;
 }
 
@@ -33,6 +35,7 @@ export function SessionsPanel({
   onView,
   onApply,
   onRevert,
+  onCommit,
   applyInProgress,
 }: SessionsPanelProps) {
   const [collapsed, setCollapsed] = useState(false);
@@ -94,6 +97,16 @@ export function SessionsPanel({
                     Revert
                   </button>
                 )}
+                {s.status === "applied" && onCommit && (
+                  <button
+                    type="button"
+                    className="btn secondary small"
+                    disabled={applyInProgress}
+                    onClick={() => onCommit(s)}
+                  >
+                    Commit
+                  </button>
+                )}
               </div>
             </div>
           ))}
@@ -3,10 +3,19 @@ import type { ReactNode } from "react";
 interface TopBarProps {
   workspacePath: string | null;
   onOpenWorkspace: () => void;
+  /** Backend policy for the open workspace; writing again needs native confirmation. */
+  readOnly?: boolean;
+  onToggleReadOnly?: () => void;
   children?: ReactNode;
 }
 
-export function TopBar({ workspacePath, onOpenWorkspace, children }: TopBarProps) {
+export function TopBar({
+  workspacePath,
+  onOpenWorkspace,
+  readOnly = false,
+  onToggleReadOnly,
+  children,
+}: TopBarProps) {
   return (
     <div className="topbar">
       <button type="button" className="btn primary" onClick={onOpenWorkspace}>
@@ -15,6 +24,12 @@ export function TopBar({ workspacePath, onOpenWorkspace, children }: TopBarProps
       <span className="workspace-path" title={workspacePath ?? ""}>
         {workspacePath ? workspacePath : "No workspace open"}
       </span>
+      {workspacePath && onToggleReadOnly && (
+        <label className="readonly-toggle" title="Block changes to project files">
+          <input type="checkbox" checked={readOnly} onChange={onToggleReadOnly} />
+          Read-only
+        </label>
+      )}
       {children}
     </div>
   );
@@ -3,13 +3,27 @@
  * Optionally include retrieved knowledge chunks (role-based limits).
  */
 
-import type { ModelContext, ProjectManifest, KnowledgeChunkRef, ProjectSnapshot } from "../types";
+import type {
+  ContextManifestEntry,
+  ModelContext,
+  ProjectManifest,
+  KnowledgeChunkRef,
+  ProjectSnapshot,
+} from "../types";
 import type { WorkspaceService } from "../workspace/WorkspaceService";
 import type { KnowledgeStore } from "../knowledge/KnowledgeStore";
 
 const KNOWLEDGE_LIMIT_PLANNER = 5;
 const KNOWLEDGE_LIMIT_CODER = 8;
 const KNOWLEDGE_LIMIT_REVIEWER = 5;
+/** Per-file cap so one huge selection (e.g. a log) can't freeze the app or overflow context. */
+const MAX_FILE_BYTES = 128 * 1024;
+const DEFAULT_REPO_MAP_TOKENS = 1024;
+/** RECENT CHANGES section: uncommitted files and latest commits listed. */
+const RECENT_CHANGES_MAX_FILES = 30;
+const RECENT_CHANGES_COMMITS = 5;
+/** Tokens kept free for the model's answer when packing context. */
+const DEFAULT_RESERVE_TOKENS = 1024;
 
 export type AgentRoleForKnowledge = "planner" | "coder" | "reviewer";
 
@@ -22,6 +36,12 @@ export interface BuildOptions {
   projectSnapshot?: ProjectSnapshot | null;
   /** Packs to filter knowledge retrieval (chunk tags). */
   enabledPacks?: string[];
+  /** Token budget for the REPO MAP section (ranked outline of files + signatures). 0 disables it. */
+  repoMapTokens?: number;
+  /** Token budget for packed files + knowledge. Default: runtime ctx size minus reserve. */
+  tokenBudget?: number;
+  /** Add a RECENT CHANGES section (branch, uncommitted files, latest commits) from git. Default true. */
+  recentChanges?: boolean;
 }
 
 export class ContextBuilder {
@@ -41,26 +61,68 @@ export class ContextBuilder {
         ? { suggestedPaths: suggestedPathsOrOptions }
         : suggestedPathsOrOptions ?? {};
     const { suggestedPaths, useKnowledge, knowledgeStore, agentRole, projectSnapshot, enabledPacks } = options;
+    const repoMapTokens = options.repoMapTokens ?? DEFAULT_REPO_MAP_TOKENS;
+
+    let knowledgeChunks: KnowledgeChunkRef[] | undefined;
+    if (useKnowledge && knowledgeStore && enabledPacks && enabledPacks.length > 0) {
+      const limit =
+        agentRole === "planner"
+          ? KNOWLEDGE_LIMIT_PLANNER
+          : agentRole === "reviewer"
+            ? KNOWLEDGE_LIMIT_REVIEWER
+            : KNOWLEDGE_LIMIT_CODER;
+      await knowledgeStore.ingestIfNeeded();
+      const retrieved = await knowledgeStore.retrieve(prompt, {
+        limit,
+        enabledPacks,
+      });
+      knowledgeChunks = retrieved.map((c) => ({
+        title: c.title,
+        sourcePath: c.sourcePath,
+        chunkText: c.chunkText,
+      }));
+    }
 
     const read = async (path: string) => {
       try {
-        const content = await this.workspace.readFile(path);
+        const r = await this.workspace.readFileDetailed(path, { maxBytes: MAX_FILE_BYTES });
+        if (r.isBinary || r.content == null) return null;
+        const content = r.truncated
+          ? `${r.content}\n... [truncated: showing first ${r.byteEnd} of ${r.sizeBytes} bytes]`
+          : r.content;
         return { path: this.workspace.normalizeRel(path), content };
       } catch {
         return null;
       }
     };
 
-    const selected = (
-      await Promise.all(selectedPaths.map((p) => read(p)))
-    ).filter(Boolean) as { path: string; content: string }[];
-
+    let selected: { path: string; content: string }[];
     let suggested: { path: string; content: string }[] | undefined;
+    let contextManifest: ContextManifestEntry[] | undefined;
     const suggestedList = suggestedPaths ?? options.suggestedPaths;
-    if (suggestedList?.length) {
-      suggested = (
-        await Promise.all(suggestedList.map((p) => read(p)))
+    try {
+      // Budgeted packing in the backend: whole files, symbol slices or head/tail, with a manifest.
+      const packed = await this.workspace.packContext({
+        prompt,
+        selectedPaths,
+        candidatePaths: suggestedList ?? [],
+        knowledgeChunks: knowledgeChunks ?? [],
+        tokenBudget: options.tokenBudget,
+        reserveTokens: DEFAULT_RESERVE_TOKENS + repoMapTokens,
+      });
+      selected = packed.selectedFiles.map(({ path, content }) => ({ path, content }));
+      suggested = packed.suggestedFiles.map(({ path, content }) => ({ path, content }));
+      knowledgeChunks = packed.knowledgeChunks;
+      contextManifest = packed.manifest;
+    } catch {
+      selected = (
+        await Promise.all(selectedPaths.map((p) => read(p)))
       ).filter(Boolean) as { path: string; content: string }[];
+      if (suggestedList?.length) {
+        suggested = (
+          await Promise.all(suggestedList.map((p) => read(p)))
+        ).filter(Boolean) as { path: string; content: string }[];
+      }
     }
 
     let manifestSummary: string | undefined;
@@ -90,24 +152,21 @@ export class ContextBuilder {
         : snapshotLines;
     }
 
-    let knowledgeChunks: KnowledgeChunkRef[] | undefined;
-    if (useKnowledge && knowledgeStore && enabledPacks && enabledPacks.length > 0) {
-      const limit =
-        agentRole === "planner"
-          ? KNOWLEDGE_LIMIT_PLANNER
-          : agentRole === "reviewer"
-            ? KNOWLEDGE_LIMIT_REVIEWER
-            : KNOWLEDGE_LIMIT_CODER;
-      await knowledgeStore.ingestIfNeeded();
-      const retrieved = await knowledgeStore.retrieve(prompt, {
-        limit,
-        enabledPacks,
-      });
-      knowledgeChunks = retrieved.map((c) => ({
-        title: c.title,
-        sourcePath: c.sourcePath,
-        chunkText: c.chunkText,
-      }));
+    if (repoMapTokens > 0) {
+      try {
+        const repoMap = await this.workspace.repoMap(repoMapTokens, selectedPaths);
+        if (repoMap.map) {
+          const section = `=== REPO MAP ===\n${repoMap.map.trimEnd()}`;
+          manifestSummary = manifestSummary ? `${manifestSummary}\n\n${section}` : section;
+        }
+      } catch {
+        /* repo map is optional context */
+      }
+    }
+
+    if (options.recentChanges ?? true) {
+      const section = await this.recentChanges();
+      if (section) manifestSummary = manifestSummary ? `${manifestSummary}\n\n${section}` : section;
     }
 
     return {
@@ -116,6 +175,41 @@ export class ContextBuilder {
       suggestedFiles: suggested?.length ? suggested : undefined,
       manifestSummary,
       knowledgeChunks: knowledgeChunks?.length ? knowledgeChunks : undefined,
+      contextManifest,
     };
   }
+
+  /** What the user has uncommitted and the latest commits; null outside git. */
+  private async recentChanges(): Promise<string | null> {
+    try {
+      const status = await this.workspace.gitStatus();
+      if (!status.isRepo) return null;
+      const head = status.head;
+      const lines = ["=== RECENT CHANGES ==="];
+      if (head) {
+        const branch = head.branch ?? `detached at ${head.commit?.slice(0, 7) ?? "?"}`;
+        const tracking = head.upstream ? ` (${head.upstream}: ahead ${head.ahead}, behind ${head.behind})` : "";
+        lines.push(`Branch: ${branch}${tracking}`);
+      }
+      if (status.files.length === 0) {
+        lines.push("Uncommitted: (none)");
+      } else {
+        lines.push(`Uncommitted (${status.files.length}):`);
+        for (const f of status.files.slice(0, RECENT_CHANGES_MAX_FILES)) {
+          const kind = [f.staged && `staged ${f.staged}`, f.unstaged].filter(Boolean).join(", ");
+          const from = f.oldPath ? ` (from ${f.oldPath})` : "";
+          lines.push(`  ${f.path}${from}: ${kind}`);
+        }
+        if (status.files.length > RECENT_CHANGES_MAX_FILES) lines.push("  …");
+      }
+      const commits = await this.workspace.gitLog(RECENT_CHANGES_COMMITS);
+      if (commits.length > 0) {
+        lines.push("Recent commits:");
+        for (const c of commits) lines.push(`  ${c.shortId} ${c.summary} (${c.author}, ${c.time.slice(0, 10)})`);
+      }
+      return lines.join("\n");
+    } catch {
+      return null;
+    }
+  }
 }
@@ -0,0 +1,51 @@
+import type { AppError, AppErrorKind } from "./types";
+
+/** True for the structured errors Rust commands reject with. */
+export function isAppError(e: unknown): e is AppError {
+  return (
+    typeof e === "object" &&
+    e !== null &&
+    typeof (e as { kind?: unknown }).kind === "string" &&
+    typeof (e as { message?: unknown }).message === "string"
+  );
+}
+
+/** Kind of a rejected invoke; "Other" for plain strings and JS errors. */
+export function errorKind(e: unknown): AppErrorKind {
+  return isAppError(e) ? e.kind : "Other";
+}
+
+/** A string detail (path, endpoint, ...) of an AppError, if present. */
+export function errorDetail(e: unknown, key: string): string | undefined {
+  if (!isAppError(e)) return undefined;
+  const v = e.details?.[key];
+  return typeof v === "string" ? v : undefined;
+}
+
+const REMEDIATION: Partial<Record<AppErrorKind, string>> = {
+  RuntimeNotStarted: "Start the local model from the Runtime panel.",
+  RuntimeMissing: "Place llama-server under <toolRoot>/runtime/llama.",
+  ModelMissing: "Put a .gguf model in <toolRoot>/models or set runtime.modelPath.",
+  ServerUnreachable: "Check that llama-server is running and the port is correct.",
+  EscapesWorkspace: "Use a path inside the opened workspace.",
+  PermissionDenied: "Check file permissions.",
+  Timeout: "The server may still be loading the model; try again.",
+};
+
+/** What the user can do about an error, when there is a known fix. */
+export function errorHint(e: unknown): string | undefined {
+  return REMEDIATION[errorKind(e)];
+}
+
+/** Message for display: AppError message, Error message or String(e). */
+export function errorMessage(e: unknown): string {
+  if (isAppError(e)) return e.message;
+  if (e instanceof Error) return e.message;
+  return String(e);
+}
+
+/** errorMessage plus the remediation hint, for chat and status lines. */
+export function describeError(e: unknown): string {
+  const hint = errorHint(e);
+  return hint ? `${errorMessage(e)} ${hint}` : errorMessage(e);
+}
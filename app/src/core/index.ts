@@ -1,14 +1,23 @@
 export * from "./types";
+export { isAppError, errorKind, errorDetail, errorHint, errorMessage, describeError } from "./errors";
 export { WorkspaceService } from "./workspace/WorkspaceService";
 export { getRequestedFileHint, readProjectFile, extractFileMentions, hasEditIntent, hasDiffRequest, routeMessage, hasFileEditIntent } from "./workspace/readProjectFile";
 export { routeUserMessage, classifyFileActionIntent, applySimpleEdit } from "./intent";
 export type { ReadProjectFileResult } from "./workspace/readProjectFile";
 export { ProjectInspector } from "./inspect/ProjectInspector";
-export { ProjectDetector } from "./project/ProjectDetector";
+export { ProjectDetector, pickCommands } from "./project/ProjectDetector";
 export type { ProjectDetectorResult } from "./project/ProjectDetector";
 export { readProjectSnapshot, writeProjectSnapshot } from "./project/projectSnapshot";
-export { detectProjectRoot, inferDetectedType } from "./project/projectRoot";
-export type { ProjectRootResult, DetectedType } from "./project/projectRoot";
+export { detectProjectRoot, inferDetectedType, scopeOptions } from "./project/projectRoot";
+export type {
+  ProjectRootResult,
+  DetectedType,
+  EcosystemMatch,
+  WorkspaceKind,
+  WorkspaceMember,
+  ProjectScope,
+  ProjectScopeKind,
+} from "./project/projectRoot";
 export { getDefaultEnabledPackIds } from "./knowledge/autoEnablePacks";
 export type { ProjectInfo } from "./knowledge/autoEnablePacks";
 export {
@@ -18,7 +27,13 @@ export {
   shouldIgnorePathSegment,
 } from "./project/snapshot";
 export type { ProjectSnapshotJson, SnapshotFileEntry } from "./project/snapshot";
-export { readWorkspaceSettings, writeWorkspaceSettings } from "./project/workspaceSettings";
+export {
+  readWorkspaceSettings,
+  writeWorkspaceSettings,
+  getSettings,
+  updateSettings,
+  watchSettings,
+} from "./project/workspaceSettings";
 export { ContextBuilder } from "./context/ContextBuilder";
 export {
   MockModelProvider,
@@ -49,6 +64,7 @@ export {
   runtimeStart,
   runtimeStatus,
   runtimeStop,
+  runtimeDiscover,
   runtimeGenerate,
   ensureLocalRuntime,
   DEFAULT_LOCAL_SETTINGS,
@@ -58,8 +74,10 @@ export type {
   LocalModelSettings,
   RuntimeStartResult,
   RuntimeStatusResult,
+  RuntimeDiscovery,
   GenerateOptions,
 } from "./runtime/runtimeApi";
+export { logsAttach, logsQuery, logsTail } from "./runtime/logsApi";
 export { PatchEngine } from "./patch/PatchEngine";
 export type { ApplyResult, FileSnapshot } from "./patch/PatchEngine";
 export { MemoryStore } from "./memory/MemoryStore";
@@ -39,6 +39,7 @@ export function getDefaultEnabledPackIds(
 
   switch (detectedType) {
     case "node":
+    case "deno":
       add("typescript");
       add("javascript");
       add("node");
@@ -55,6 +56,13 @@ export function getDefaultEnabledPackIds(
     case "php":
       add("php");
       break;
+    case "dart":
+      add("flutter");
+      break;
+    case "cmake":
+    case "meson":
+      add("cpp");
+      break;
     case "unknown":
       if (
         signalsLower.includes("package.json") ||
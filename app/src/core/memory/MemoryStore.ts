@@ -1,14 +1,18 @@
 /**
- * MemoryStore: per-workspace .devassistant/sessions.json.
+ * MemoryStore: per-workspace .devassistant/sessions.json. The only writer of that file;
+ * read-modify-write updates are queued per workspace so concurrent ones are not lost.
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { SessionRecord } from "../types";
+import type { CheckRecord, SessionRecord } from "../types";
 import type { FileSnapshot } from "../patch/PatchEngine";
 import { pathsFromPatch } from "../patch/PatchEngine";
 
 const SESSIONS_FILE = ".devassistant/sessions.json";
 
+/** Tail of the pending updates per workspace root. */
+const updateQueues = new Map<string, Promise<unknown>>();
+
 function hash(s: string): string {
   let h = 0;
   for (let i = 0; i < s.length; i++) {
@@ -56,6 +60,15 @@ export class MemoryStore {
     });
   }
 
+  /** Run one read-modify-write of sessions.json after the previous ones for this root. */
+  private exclusive<T>(update: () => Promise<T>): Promise<T> {
+    const root = this._ensureRoot();
+    const prev = updateQueues.get(root) ?? Promise.resolve();
+    const next = prev.catch(() => {}).then(update);
+    updateQueues.set(root, next);
+    return next;
+  }
+
   private async ensureDir(): Promise<void> {
     try {
       const workspaceRoot = this._ensureRoot();
@@ -77,32 +90,34 @@ export class MemoryStore {
     patch: string,
     beforeSnapshots: FileSnapshot[]
   ): Promise<SessionRecord> {
-    await this.ensureDir();
-    const sessions = await this.readSessions();
-    const filesChanged = beforeSnapshots.map((s) => ({
-      path: s.path,
-      beforeHash: hash(s.content),
-      afterHash: undefined as string | undefined,
-    }));
-    const id = `s${Date.now()}`;
-    const now = new Date().toISOString();
-    const record: SessionRecord = {
-      id,
-      timestamp: now,
-      createdAt: now,
-      status: "applied",
-      userPrompt,
-      selectedContextFiles,
-      manifestHash,
-      explanation,
-      patch,
-      filesChanged,
-      beforeSnapshots: [...beforeSnapshots],
-      checks: [],
-    };
-    sessions.push(record);
-    await this.writeSessions(sessions);
-    return record;
+    return this.exclusive(async () => {
+      await this.ensureDir();
+      const sessions = await this.readSessions();
+      const filesChanged = beforeSnapshots.map((s) => ({
+        path: s.path,
+        beforeHash: hash(s.content),
+        afterHash: undefined as string | undefined,
+      }));
+      const id = `s${Date.now()}`;
+      const now = new Date().toISOString();
+      const record: SessionRecord = {
+        id,
+        timestamp: now,
+        createdAt: now,
+        status: "applied",
+        userPrompt,
+        selectedContextFiles,
+        manifestHash,
+        explanation,
+        patch,
+        filesChanged,
+        beforeSnapshots: [...beforeSnapshots],
+        checks: [],
+      };
+      sessions.push(record);
+      await this.writeSessions(sessions);
+      return record;
+    });
   }
 
   /** Proposed session: patch proposed, not yet saved or applied. */
@@ -112,31 +127,33 @@ export class MemoryStore {
     explanation: string,
     patch: string
   ): Promise<SessionRecord> {
-    await this.ensureDir();
-    const sessions = await this.readSessions();
-    const paths = pathsFromPatch(patch);
-    const filesChanged = paths.map((path) => ({
-      path,
-      beforeHash: undefined as string | undefined,
-      afterHash: undefined as string | undefined,
-    }));
-    const id = `s${Date.now()}`;
-    const now = new Date().toISOString();
-    const record: SessionRecord = {
-      id,
-      timestamp: now,
-      createdAt: now,
-      status: "proposed",
-      userPrompt,
-      selectedContextFiles,
-      explanation,
-      patch,
-      filesChanged,
-      checks: [],
-    };
-    sessions.push(record);
-    await this.writeSessions(sessions);
-    return record;
+    return this.exclusive(async () => {
+      await this.ensureDir();
+      const sessions = await this.readSessions();
+      const paths = pathsFromPatch(patch);
+      const filesChanged = paths.map((path) => ({
+        path,
+        beforeHash: undefined as string | undefined,
+        afterHash: undefined as string | undefined,
+      }));
+      const id = `s${Date.now()}`;
+      const now = new Date().toISOString();
+      const record: SessionRecord = {
+        id,
+        timestamp: now,
+        createdAt: now,
+        status: "proposed",
+        userPrompt,
+        selectedContextFiles,
+        explanation,
+        patch,
+        filesChanged,
+        checks: [],
+      };
+      sessions.push(record);
+      await this.writeSessions(sessions);
+      return record;
+    });
   }
 
   /** Pending session: Save / Run later. No file writes (no patch apply). */
@@ -147,32 +164,34 @@ export class MemoryStore {
     explanation: string,
     patch: string
   ): Promise<SessionRecord> {
-    await this.ensureDir();
-    const sessions = await this.readSessions();
-    const paths = pathsFromPatch(patch);
-    const filesChanged = paths.map((path) => ({
-      path,
-      beforeHash: undefined as string | undefined,
-      afterHash: undefined as string | undefined,
-    }));
-    const id = `s${Date.now()}`;
-    const now = new Date().toISOString();
-    const record: SessionRecord = {
-      id,
-      timestamp: now,
-      createdAt: now,
-      status: "pending",
-      userPrompt,
-      selectedContextFiles,
-      manifestHash,
-      explanation,
-      patch,
-      filesChanged,
-      checks: [],
-    };
-    sessions.push(record);
-    await this.writeSessions(sessions);
-    return record;
+    return this.exclusive(async () => {
+      await this.ensureDir();
+      const sessions = await this.readSessions();
+      const paths = pathsFromPatch(patch);
+      const filesChanged = paths.map((path) => ({
+        path,
+        beforeHash: undefined as string | undefined,
+        afterHash: undefined as string | undefined,
+      }));
+      const id = `s${Date.now()}`;
+      const now = new Date().toISOString();
+      const record: SessionRecord = {
+        id,
+        timestamp: now,
+        createdAt: now,
+        status: "pending",
+        userPrompt,
+        selectedContextFiles,
+        manifestHash,
+        explanation,
+        patch,
+        filesChanged,
+        checks: [],
+      };
+      sessions.push(record);
+      await this.writeSessions(sessions);
+      return record;
+    });
   }
 
   async getLastSession(): Promise<SessionRecord | null> {
@@ -192,11 +211,13 @@ export class MemoryStore {
   }
 
   async updateSessionStatus(id: string, status: SessionRecord["status"]): Promise<void> {
-    const sessions = await this.readSessions();
-    const i = sessions.findIndex((s) => s.id === id);
-    if (i < 0) return;
-    sessions[i] = { ...sessions[i]!, status };
-    await this.writeSessions(sessions);
+    return this.exclusive(async () => {
+      const sessions = await this.readSessions();
+      const i = sessions.findIndex((s) => s.id === id);
+      if (i < 0) return;
+      sessions[i] = { ...sessions[i]!, status };
+      await this.writeSessions(sessions);
+    });
   }
 
   /** Mark a pending session as applied and store beforeSnapshots (timeline Apply). */
@@ -204,21 +225,35 @@ export class MemoryStore {
     id: string,
     beforeSnapshots: FileSnapshot[]
   ): Promise<void> {
-    const sessions = await this.readSessions();
-    const i = sessions.findIndex((s) => s.id === id);
-    if (i < 0) return;
-    const r = sessions[i]!;
-    const filesChanged = beforeSnapshots.map((s) => ({
-      path: s.path,
-      beforeHash: hash(s.content),
-      afterHash: undefined as string | undefined,
-    }));
-    sessions[i] = {
-      ...r,
-      status: "applied",
-      filesChanged,
-      beforeSnapshots: [...beforeSnapshots],
-    };
-    await this.writeSessions(sessions);
+    return this.exclusive(async () => {
+      const sessions = await this.readSessions();
+      const i = sessions.findIndex((s) => s.id === id);
+      if (i < 0) return;
+      const r = sessions[i]!;
+      const filesChanged = beforeSnapshots.map((s) => ({
+        path: s.path,
+        beforeHash: hash(s.content),
+        afterHash: undefined as string | undefined,
+      }));
+      sessions[i] = {
+        ...r,
+        status: "applied",
+        filesChanged,
+        beforeSnapshots: [...beforeSnapshots],
+      };
+      await this.writeSessions(sessions);
+    });
+  }
+
+  /** Append a finished check (checks://finished) to its session's checks. */
+  async appendCheck(sessionId: string, record: CheckRecord): Promise<void> {
+    return this.exclusive(async () => {
+      const sessions = await this.readSessions();
+      const i = sessions.findIndex((s) => s.id === sessionId);
+      if (i < 0) return;
+      const r = sessions[i]!;
+      sessions[i] = { ...r, checks: [...(r.checks ?? []), record] };
+      await this.writeSessions(sessions);
+    });
   }
 }
@@ -13,6 +13,7 @@ import {
   type GenerateOptions,
 } from "../runtime/runtimeApi";
 import { extractUnifiedDiff, extractExplanation } from "../runtime/parseCoderOutput";
+import { errorDetail, errorHint, errorKind, errorMessage } from "../errors";
 
 function buildCoderPrompt(ctx: ModelContext): string {
   const parts: string[] = [];
@@ -131,12 +132,14 @@ export class LocalModelProvider implements IModelProvider {
       });
       return (raw || "").trim() || "No response.";
     } catch (e) {
-      const msg = String(e);
-      const lines = msg.split("\n");
-      const first = lines[0]?.trim() || msg;
-      const rest = lines.slice(1).filter(Boolean).join("\n");
-      const second = rest ? rest : "Endpoint: n/a";
-      return `LOCAL_MODEL_ERROR: ${first}\n${second}`;
+      const endpoint = errorDetail(e, "endpoint") ?? "n/a";
+      const hint = errorHint(e);
+      const lines = [
+        `LOCAL_MODEL_ERROR: [${errorKind(e)}] ${errorMessage(e)}`,
+        `Endpoint: ${endpoint}`,
+      ];
+      if (hint) lines.push(hint);
+      return lines.join("\n");
     }
   }
 }
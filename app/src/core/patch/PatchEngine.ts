@@ -4,6 +4,7 @@
 
 import * as diff from "diff";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../errors";
 
 export interface FileSnapshot {
   path: string;
@@ -14,6 +15,8 @@ export interface ApplyResult {
   applied: string[];
   failed: { path: string; error: string }[];
   beforeSnapshots: FileSnapshot[];
+  /** Git checkpoints were recorded (applyCheckpointed) or restored (revertCheckpointed). */
+  checkpointed?: boolean;
 }
 
 function validatePath(_root: string, path: string): boolean {
@@ -102,12 +105,41 @@ export class PatchEngine {
         });
         applied.push(path);
       } catch (e) {
-        failed.push({ path, error: String(e) });
+        failed.push({ path, error: errorMessage(e) });
       }
     }
     return { applied, failed, beforeSnapshots };
   }
 
+  /**
+   * apply() bracketed by "before"/"after" git checkpoints on the session's private ref
+   * (refs/devassistant/checkpoints/<session>). Outside git the patch is applied without them.
+   */
+  async applyCheckpointed(patch: string, sessionId: string): Promise<ApplyResult> {
+    const paths = pathsFromPatch(patch).filter((p) => validatePath(this.workspaceRoot, p));
+    const checkpoint = (label: string) =>
+      invoke("git_checkpoint_create", { workspaceRoot: this.workspaceRoot, sessionId, label, paths });
+    const checkpointed = paths.length > 0 && (await checkpoint("before").then(() => true, () => false));
+    const result = await this.apply(patch);
+    if (checkpointed) await checkpoint("after").catch(() => {});
+    return { ...result, checkpointed };
+  }
+
+  /** Restore the session's "before" checkpoint; falls back to snapshots when it has none. */
+  async revertCheckpointed(sessionId: string, snapshots: FileSnapshot[]): Promise<ApplyResult> {
+    try {
+      const r = await invoke<{ restored: string[]; removed: string[] }>("git_checkpoint_restore", {
+        workspaceRoot: this.workspaceRoot,
+        sessionId,
+        checkpoint: "before",
+        paths: null,
+      });
+      return { applied: [...r.restored, ...r.removed], failed: [], beforeSnapshots: [], checkpointed: true };
+    } catch {
+      return this.revert(snapshots);
+    }
+  }
+
   async revert(snapshots: FileSnapshot[]): Promise<ApplyResult> {
     const applied: string[] = [];
     const failed: { path: string; error: string }[] = [];
@@ -124,7 +156,7 @@ export class PatchEngine {
         });
         applied.push(path);
       } catch (e) {
-        failed.push({ path, error: String(e) });
+        failed.push({ path, error: errorMessage(e) });
       }
     }
     return { applied, failed, beforeSnapshots: [] };
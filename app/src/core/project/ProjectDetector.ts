@@ -1,31 +1,20 @@
 /**
- * ProjectDetector: scan workspace for project type(s), recommended packs,
- * important files, and inferred commands (build/test/lint/dev).
+ * ProjectDetector: project type(s), recommended packs, important files and
+ * commands (build/test/lint/dev), from the manifests parsed by project_detect.
  */
 
 import type { WorkspaceService } from "../workspace/WorkspaceService";
-import type { FileTreeNode } from "../types";
-import type { DetectedCommands } from "../types";
+import type { DetectedCommands, ProjectCommand, ProjectInfo } from "../types";
 
 export interface ProjectDetectorResult {
   detectedTypes: string[];
   recommendedPacks: string[];
   importantFiles: string[];
   detectedCommands: DetectedCommands;
+  /** Full detection: languages, frameworks, entry points and every command with its source. */
+  project: ProjectInfo;
 }
 
-/** Signature files per project type. Paths are relative to workspace root. */
-const SIGNATURES: Record<string, (string | { pattern: string })[]> = {
-  "Node/TS": ["package.json", "tsconfig.json"],
-  Python: ["pyproject.toml", "requirements.txt", "poetry.lock"],
-  Flutter: ["pubspec.yaml"],
-  "C/C++": ["CMakeLists.txt", "Makefile", { pattern: "*.cpp" }, { pattern: "*.h" }],
-  PowerShell: [{ pattern: "*.ps1" }, { pattern: "*.psm1" }, { pattern: "*.psd1" }],
-  Tauri: ["src-tauri/tauri.conf.json"],
-  Firebase: ["firebase.json"],
-  "Next.js": ["next.config.js", "next.config.mjs", "next.config.ts", "next.config.mts"],
-};
-
 /** Map detected type -> knowledge pack identifiers (match knowledge/ folder tags). */
 const TYPE_TO_PACKS: Record<string, string[]> = {
   "Node/TS": ["node", "typescript"],
@@ -36,73 +25,48 @@ const TYPE_TO_PACKS: Record<string, string[]> = {
   Tauri: ["tauri"],
   Firebase: ["firebase"],
   "Next.js": ["nextjs"],
+  Rust: ["rust"],
+  Go: ["go"],
 };
 
-/** Default commands when no package.json scripts (by type). */
+/** Defaults for types with no manifest to read commands from. */
 const DEFAULT_COMMANDS: Record<string, Partial<DetectedCommands>> = {
-  "Node/TS": { build: "npm run build", test: "npm test", lint: "npm run lint", dev: "npm run dev" },
-  Python: { build: "", test: "pytest", lint: "ruff check .", dev: "" },
-  Flutter: { build: "flutter build", test: "flutter test", lint: "flutter analyze", dev: "flutter run" },
-  "C/C++": { build: "make", test: "make test", lint: "", dev: "" },
-  PowerShell: { build: "", test: "Invoke-Pester", lint: "Invoke-ScriptAnalyzer", dev: "" },
-  Tauri: { build: "npm run tauri build", test: "npm test", lint: "npm run lint", dev: "npm run tauri dev" },
-  Firebase: { build: "", test: "", lint: "", dev: "firebase emulators:start" },
-  "Next.js": { build: "npm run build", test: "npm test", lint: "npm run lint", dev: "npm run dev" },
+  PowerShell: { test: "Invoke-Pester", lint: "Invoke-ScriptAnalyzer" },
+  Firebase: { dev: "firebase emulators:start" },
 };
 
-function flattenPaths(nodes: FileTreeNode[]): string[] {
-  const out: string[] = [];
-  function walk(n: FileTreeNode[]) {
-    for (const node of n) {
-      if (!node.isDir) out.push(node.path);
-      if (node.children?.length) walk(node.children);
-    }
-  }
-  walk(nodes);
-  return out;
+function commandLine(c: ProjectCommand): string {
+  return c.cwd ? `cd ${c.cwd} && ${c.command}` : c.command;
 }
 
-function pathMatches(path: string, sig: string | { pattern: string }): boolean {
-  const name = path.split("/").pop() ?? "";
-  const norm = path.replace(/\\/g, "/");
-  if (typeof sig === "string") {
-    return norm === sig || norm.endsWith("/" + sig);
+/**
+ * Pure: first command of each kind (root-level commands come first). Unit-testable.
+ */
+export function pickCommands(project: ProjectInfo): DetectedCommands {
+  const out: DetectedCommands = {};
+  for (const key of ["build", "test", "lint", "dev"] as const) {
+    const c = project.commands.find((cmd) => cmd.kind === key);
+    if (c) out[key] = commandLine(c);
   }
-  const ext = sig.pattern.startsWith("*") ? sig.pattern.slice(1) : sig.pattern; // *.cpp -> .cpp
-  return name.endsWith(ext) || name.toLowerCase().endsWith(ext.toLowerCase());
-}
-
-function hasSignature(fileList: string[], type: string): boolean {
-  const sigs = SIGNATURES[type];
-  if (!sigs) return false;
-  if (type === "Next.js") {
-    return (
-      sigs.some((sig) => fileList.some((p) => pathMatches(p, sig))) ||
-      fileList.some((p) => p.startsWith("app/") || p.startsWith("pages/"))
-    );
+  for (const type of project.detectedTypes) {
+    const defaults = DEFAULT_COMMANDS[type] ?? {};
+    for (const key of ["build", "test", "lint", "dev"] as const) {
+      if (!out[key] && defaults[key]) out[key] = defaults[key];
+    }
   }
-  return sigs.some((sig) =>
-    fileList.some((p) => pathMatches(p, sig))
-  );
+  return out;
 }
 
 export class ProjectDetector {
   constructor(private workspace: WorkspaceService) {}
 
   async detect(): Promise<ProjectDetectorResult> {
-    const tree = await this.workspace.readFileTree();
-    const fileList = flattenPaths(tree);
-
-    const detectedTypes: string[] = [];
-    for (const type of Object.keys(SIGNATURES)) {
-      if (hasSignature(fileList, type)) detectedTypes.push(type);
-    }
+    const project = await this.workspace.detectProject();
 
     const recommendedPacks: string[] = [];
     const seen = new Set<string>();
-    for (const type of detectedTypes) {
-      const packs = TYPE_TO_PACKS[type] ?? [];
-      for (const p of packs) {
+    for (const type of project.detectedTypes) {
+      for (const p of TYPE_TO_PACKS[type] ?? []) {
         if (!seen.has(p)) {
           seen.add(p);
           recommendedPacks.push(p);
@@ -110,72 +74,17 @@ export class ProjectDetector {
       }
     }
 
-    const importantFiles: string[] = [];
-    const configNames = [
-      "package.json", "tsconfig.json", "pyproject.toml", "requirements.txt", "poetry.lock",
-      "pubspec.yaml", "CMakeLists.txt", "Makefile", "tauri.conf.json", "firebase.json",
-      "next.config.js", "next.config.mjs", "next.config.ts", "next.config.mts",
+    const importantFiles = [
+      ...project.importantFiles,
+      ...project.entryPoints.filter((e) => e.kind !== "lib").map((e) => e.path),
     ];
-    for (const p of fileList) {
-      const name = p.split("/").pop() ?? "";
-      if (configNames.includes(name) || p === "src-tauri/tauri.conf.json") {
-        importantFiles.push(p);
-      }
-    }
-    // Add first entry point if Node (main from package.json) - done below after parsing package.json
-    const detectedCommands = await this.inferCommands(fileList, detectedTypes);
-
-    // If we have package.json and it has main/scripts, add main to importantFiles
-    try {
-      const pkgRaw = await this.workspace.readFile("package.json");
-      const pkg = JSON.parse(pkgRaw) as { main?: string; bin?: string | Record<string, string> };
-      if (typeof pkg.main === "string" && !importantFiles.includes(pkg.main)) {
-        importantFiles.push(pkg.main);
-      }
-    } catch {
-      /* no package.json or invalid */
-    }
 
     return {
-      detectedTypes,
+      detectedTypes: project.detectedTypes,
       recommendedPacks,
       importantFiles: [...new Set(importantFiles)],
-      detectedCommands,
+      detectedCommands: pickCommands(project),
+      project,
     };
   }
-
-  private async inferCommands(
-    fileList: string[],
-    detectedTypes: string[]
-  ): Promise<DetectedCommands> {
-    const out: DetectedCommands = {};
-    const hasPackageJson = fileList.some((p) => p === "package.json" || p.endsWith("/package.json"));
-
-    if (hasPackageJson) {
-      try {
-        const pkgRaw = await this.workspace.readFile("package.json");
-        const pkg = JSON.parse(pkgRaw) as { scripts?: Record<string, string> };
-        const scripts = pkg.scripts ?? {};
-        if (scripts.build) out.build = scripts.build;
-        if (scripts.test) out.test = scripts.test;
-        if (scripts.lint) out.lint = scripts.lint;
-        out.dev = scripts.dev ?? scripts.start ?? out.dev;
-      } catch {
-        /* ignore */
-      }
-    }
-
-    const defaults = detectedTypes.length
-      ? DEFAULT_COMMANDS[detectedTypes[0]] ?? {}
-      : {};
-    if (!out.build && defaults.build) out.build = defaults.build;
-    if (!out.test && defaults.test) out.test = defaults.test;
-    if (!out.lint && defaults.lint) out.lint = defaults.lint;
-    if (!out.dev && defaults.dev) out.dev = defaults.dev;
-    if (detectedTypes.includes("C/C++") && fileList.some((p) => p.includes("CMakeLists"))) {
-      if (!out.build) out.build = "cmake --build .";
-    }
-
-    return out;
-  }
 }
@@ -5,22 +5,95 @@
 
 import { invoke } from "@tauri-apps/api/core";
 
+/** Ecosystem ids from the Rust ECOSYSTEMS table, plus "unknown". */
 export type DetectedType =
   | "node"
+  | "deno"
   | "rust"
   | "python"
   | "go"
   | "php"
+  | "dart"
+  | "gradle"
+  | "maven"
+  | "dotnet"
+  | "elixir"
+  | "ruby"
+  | "bazel"
+  | "meson"
+  | "cmake"
+  | "powershell"
+  | "firebase"
+  | "make"
   | "unknown";
 
+export interface EcosystemMatch {
+  id: DetectedType;
+  label: string;
+  /** Sum of the weights of the markers found. */
+  score: number;
+  /** Breaks score ties. */
+  priority: number;
+  markers: string[];
+}
+
+export type WorkspaceKind = "cargo" | "npm" | "pnpm" | "go";
+
+export interface WorkspaceMember {
+  /** Absolute, forward slashes. */
+  path: string;
+  /** Relative to the workspace root ("" for the root package). */
+  relPath: string;
+  name: string | null;
+}
+
 export interface ProjectRootResult {
+  /** Nearest directory with a root signal (VCS, manifest, Bazel workspace, CMake project()); per-directory build files alone do not count. */
   rootPath: string;
   detectedType: DetectedType;
   signalsFound: string[];
+  /** Nearest directory with a package manifest. */
+  packageRoot: string | null;
+  /** Enclosing Cargo/npm/pnpm/go workspace that contains the package. */
+  workspaceRoot: string | null;
+  workspaceKind: WorkspaceKind | null;
+  /** Nearest .git/.hg/.svn root. */
+  vcsRoot: string | null;
+  members: WorkspaceMember[];
+  /** Winning ecosystem at rootPath. */
+  ecosystem: EcosystemMatch | null;
+  /** Why it won, e.g. "Deno: deno.json (10) scored 10; tied with Node.js and won on priority 85 over 70". */
+  ecosystemReason: string | null;
+  /** Every ecosystem matched at rootPath, best first. */
+  ecosystems: EcosystemMatch[];
+}
+
+export type ProjectScopeKind = "package" | "workspace" | "vcs";
+
+export interface ProjectScope {
+  kind: ProjectScopeKind;
+  path: string;
 }
 
 /**
- * Pure: infer detected type from signals. Unit-testable.
+ * Pure: distinct scopes the user can open, narrowest first. Unit-testable.
+ */
+export function scopeOptions(result: ProjectRootResult): ProjectScope[] {
+  const candidates: [ProjectScopeKind, string | null][] = [
+    ["package", result.packageRoot],
+    ["workspace", result.workspaceRoot],
+    ["vcs", result.vcsRoot],
+  ];
+  const out: ProjectScope[] = [];
+  for (const [kind, path] of candidates) {
+    if (path && !out.some((s) => s.path === path)) out.push({ kind, path });
+  }
+  return out;
+}
+
+/**
+ * Pure: infer detected type from signals. Unit-testable. Fallback for results without
+ * an ecosystem (e.g. only .git found).
  */
 export function inferDetectedType(signalsFound: string[]): DetectedType {
   const lower = signalsFound.map((s) => s.toLowerCase());
@@ -46,19 +119,18 @@ export function inferDetectedType(signalsFound: string[]): DetectedType {
 export async function detectProjectRoot(
   startPath?: string
 ): Promise<ProjectRootResult> {
-  const result = await invoke<{ rootPath: string; signalsFound: string[] }>(
+  const result = await invoke<Omit<ProjectRootResult, "detectedType">>(
     "detect_project_root",
     { startPath: startPath ?? "" }
   );
-  const detectedType = inferDetectedType(result.signalsFound);
+  const detectedType = result.ecosystem?.id ?? inferDetectedType(result.signalsFound);
   console.log("[projectRoot] detected:", {
     rootPath: result.rootPath,
     detectedType,
     signalsFound: result.signalsFound,
+    ecosystemReason: result.ecosystemReason,
+    workspaceRoot: result.workspaceRoot,
+    vcsRoot: result.vcsRoot,
   });
-  return {
-    rootPath: result.rootPath,
-    detectedType,
-    signalsFound: result.signalsFound,
-  };
+  return { ...result, detectedType };
 }
@@ -44,6 +44,12 @@ export interface ProjectSnapshotJson {
   detectedType: DetectedType;
   signalsFound: string[];
   counts: { totalFiles: number; totalDirs: number };
+  /** Backend snapshot id; pass to workspace_snapshot_changes for incremental refresh. */
+  snapshotId?: string;
+  /** files[] was capped; counts still cover everything walked. */
+  truncated?: boolean;
+  skippedLarge?: number;
+  skippedUnreadable?: number;
   files: SnapshotFileEntry[];
   topLevel: string[];
   /** UI backward compat */
@@ -57,7 +63,8 @@ export interface ProjectSnapshotJson {
 const SNAPSHOT_VERSION = 1;
 
 /**
- * Generate snapshot data by calling Tauri workspace_walk_snapshot.
+ * Generate snapshot data by calling Tauri workspace_walk_snapshot. With save the walk is
+ * also persisted, and its snapshotId can be passed to getSnapshotChanges later.
  */
 export async function generateSnapshotData(
   rootPath: string,
@@ -69,15 +76,21 @@ export async function generateSnapshotData(
     enabledPacks: string[];
     importantFiles: string[];
     detectedCommands: DetectedCommands;
-  }
+  },
+  save = false
 ): Promise<ProjectSnapshotJson> {
   const raw = await invoke<{
+    snapshotId: string | null;
     totalFiles: number;
     totalDirs: number;
     files: SnapshotFileEntry[];
     topLevel: string[];
+    truncated: boolean;
+    skippedLarge: number;
+    skippedUnreadable: number;
   }>("workspace_walk_snapshot", {
     workspaceRoot: rootPath,
+    save,
   });
 
   const base: ProjectSnapshotJson = {
@@ -90,6 +103,10 @@ export async function generateSnapshotData(
       totalFiles: raw.totalFiles,
       totalDirs: raw.totalDirs,
     },
+    snapshotId: raw.snapshotId ?? undefined,
+    truncated: raw.truncated,
+    skippedLarge: raw.skippedLarge,
+    skippedUnreadable: raw.skippedUnreadable,
     files: raw.files,
     topLevel: raw.topLevel,
   };
@@ -103,6 +120,32 @@ export async function generateSnapshotData(
   return base;
 }
 
+export interface SnapshotChanges {
+  fromId: string;
+  /** Id of the new snapshot; null unless save was requested. */
+  toId: string | null;
+  added: string[];
+  modified: string[];
+  deleted: string[];
+  truncated: boolean;
+}
+
+/**
+ * Files added/modified/deleted since a previous snapshot id. With save, also persists a
+ * new snapshot whose id is returned as toId; the sinceId baseline is kept either way.
+ */
+export async function getSnapshotChanges(
+  rootPath: string,
+  sinceId: string,
+  save = false
+): Promise<SnapshotChanges> {
+  return invoke<SnapshotChanges>("workspace_snapshot_changes", {
+    workspaceRoot: rootPath,
+    sinceId,
+    save,
+  });
+}
+
 /**
  * Resolve output path: .devassistant/project_snapshot.json under project root.
  * (App data dir not implemented - use project root.)
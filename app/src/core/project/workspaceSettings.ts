@@ -1,48 +1,88 @@
 /**
- * Read/write workspace settings at .devassistant/settings.json
+ * Settings via settings_get/settings_set: defaults, user config, workspace
+ * .devassistant/settings.json and DEVASSISTANT_* env, resolved in that order.
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { WorkspaceSettings } from "../types";
-
-const SETTINGS_PATH = ".devassistant/settings.json";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  ResolvedSettings,
+  SettingsChangedEvent,
+  SettingsPatch,
+  WorkspaceSettings,
+} from "../types";
 
 const DEFAULT_SETTINGS: WorkspaceSettings = {
   autoPacksEnabled: true,
   enabledPacks: [],
 };
 
+export function getSettings(workspaceRoot?: string): Promise<ResolvedSettings> {
+  return invoke<ResolvedSettings>("settings_get", { workspaceRoot: workspaceRoot ?? null });
+}
+
+/** Merge patch into the user or workspace layer; rejected if unknown or out of range. */
+export function updateSettings(
+  scope: "user" | "workspace",
+  patch: SettingsPatch,
+  workspaceRoot?: string
+): Promise<ResolvedSettings> {
+  return invoke<ResolvedSettings>("settings_set", {
+    workspaceRoot: workspaceRoot ?? null,
+    scope,
+    patch,
+  });
+}
+
+/**
+ * Watch the user and workspace settings files; onChange gets the re-resolved settings
+ * (event.workspaceRoot tells which watch fired). Returned function stops both.
+ */
+export async function watchSettings(
+  onChange: (event: SettingsChangedEvent) => void,
+  workspaceRoot?: string
+): Promise<UnlistenFn> {
+  const root = workspaceRoot ?? null;
+  const unlisten = await listen<SettingsChangedEvent>("settings://changed", (e) => onChange(e.payload));
+  await invoke("settings_watch", { workspaceRoot: root });
+  return () => {
+    unlisten();
+    invoke("settings_unwatch", { workspaceRoot: root }).catch(() => {});
+  };
+}
+
 export async function readWorkspaceSettings(
   workspaceRoot: string
 ): Promise<WorkspaceSettings> {
   try {
-    const raw = await invoke<string>("workspace_read_file", {
-      workspaceRoot,
-      path: SETTINGS_PATH,
-    });
-    const data = JSON.parse(raw) as Partial<WorkspaceSettings>;
+    const { settings } = await getSettings(workspaceRoot);
     return {
-      autoPacksEnabled: data.autoPacksEnabled ?? DEFAULT_SETTINGS.autoPacksEnabled,
-      enabledPacks: Array.isArray(data.enabledPacks) ? data.enabledPacks : DEFAULT_SETTINGS.enabledPacks,
-      modelPath: typeof data.modelPath === "string" && data.modelPath.trim() ? data.modelPath.trim() : undefined,
-      port: typeof data.port === "number" && data.port > 0 ? data.port : undefined,
+      autoPacksEnabled: settings.autoPacksEnabled,
+      enabledPacks: settings.enabledPacks,
+      modelPath: settings.runtime.modelPath?.trim() || undefined,
+      port: settings.runtime.port,
+      extraIgnore: settings.extraIgnore,
+      checkpoints: settings.checkpoints,
     };
   } catch {
     return { ...DEFAULT_SETTINGS };
   }
 }
 
+/** Write the given fields to the workspace layer; fields left undefined keep their stored value. */
 export async function writeWorkspaceSettings(
   workspaceRoot: string,
-  settings: WorkspaceSettings
+  settings: Partial<WorkspaceSettings>
 ): Promise<void> {
-  await invoke("workspace_mkdir_all", {
-    workspaceRoot,
-    path: ".devassistant",
-  });
-  await invoke("workspace_write_file", {
-    workspaceRoot,
-    path: SETTINGS_PATH,
-    content: JSON.stringify(settings, null, 2),
-  });
+  const patch: SettingsPatch = {};
+  if (settings.autoPacksEnabled !== undefined) patch.autoPacksEnabled = settings.autoPacksEnabled;
+  if (settings.enabledPacks !== undefined) patch.enabledPacks = settings.enabledPacks;
+  if (settings.extraIgnore !== undefined) patch.extraIgnore = settings.extraIgnore;
+  if (settings.checkpoints !== undefined) patch.checkpoints = settings.checkpoints;
+  if (settings.port !== undefined || settings.modelPath !== undefined) {
+    patch.runtime = {};
+    if (settings.port !== undefined) patch.runtime.port = settings.port;
+    if (settings.modelPath !== undefined) patch.runtime.modelPath = settings.modelPath || null;
+  }
+  await updateSettings("workspace", patch, workspaceRoot);
 }
@@ -0,0 +1,22 @@
+/**
+ * App log: recent records from memory (this session) or from the workspace's rolling
+ * log files under .devassistant/logs (earlier sessions too).
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { LogQuery, LogRecord } from "../types";
+
+/** Write the app log under workspaceRoot/.devassistant/logs. Returns the active log file. */
+export function logsAttach(workspaceRoot: string): Promise<string> {
+  return invoke<string>("logs_attach", { workspaceRoot });
+}
+
+/** Matching records held in memory, oldest first. */
+export function logsQuery(query?: LogQuery): Promise<LogRecord[]> {
+  return invoke<LogRecord[]>("logs_query", { query: query ?? null });
+}
+
+/** Last matching records from the workspace's log files, oldest first. */
+export function logsTail(workspaceRoot: string, query?: LogQuery): Promise<LogRecord[]> {
+  return invoke<LogRecord[]>("logs_tail", { workspaceRoot, query: query ?? null });
+}
@@ -65,7 +65,52 @@ export async function runtimeChat(
   });
 }
 
-/** Walk up from workspace_root (up to 8 levels). First dir with runtime/llama + models/ is toolRoot. */
+export interface TokenizeResult {
+  count: number;
+  /** Token ids; only when counted by llama-server. */
+  tokens: number[] | null;
+  pieces: string[] | null;
+  source: "server" | "heuristic";
+}
+
+export interface TokenCount {
+  /** Set for file items; null for text items. */
+  path: string | null;
+  tokens: number;
+  /** Counted by llama-server (false = heuristic estimate). */
+  exact: boolean;
+  error: string | null;
+}
+
+export interface CountTokensResult {
+  /** Texts first, then paths, each in input order. */
+  items: TokenCount[];
+  total: number;
+  exact: boolean;
+}
+
+/** Tokenize with the running model via /tokenize; estimates the count when no server is running. */
+export async function runtimeTokenize(text: string, withPieces?: boolean): Promise<TokenizeResult> {
+  return invoke<TokenizeResult>("runtime_tokenize", {
+    text,
+    withPieces: withPieces ?? undefined,
+  });
+}
+
+/** Batch token counts for texts and/or workspace files (relative paths). */
+export async function runtimeCountTokens(
+  texts: string[],
+  workspaceRoot?: string | null,
+  paths?: string[]
+): Promise<CountTokensResult> {
+  return invoke<CountTokensResult>("runtime_count_tokens", {
+    texts,
+    workspaceRoot: workspaceRoot || undefined,
+    paths: paths ?? undefined,
+  });
+}
+
+/** Walk up from workspace_root (limits.toolRootMaxLevels, default 8). First dir with runtime/llama + models/ is toolRoot. */
 export async function findToolRoot(workspaceRoot: string): Promise<string | null> {
   const result = await invoke<unknown>("find_tool_root", { workspaceRoot });
   return typeof result === "string" ? result : null;
@@ -82,6 +127,23 @@ export async function toolRootExists(toolRoot: string, relPath: string): Promise
   return invoke<boolean>("tool_root_exists", { toolRoot, relPath });
 }
 
+export interface RuntimeDiscovery {
+  workspaceRoot: string;
+  toolRoot: string | null;
+  /** llama-server for this platform (.exe on Windows), if present. */
+  serverPath: string | null;
+  /** settings runtime.modelPath when it exists, else the newest models/*.gguf. */
+  modelPath: string | null;
+  hadMultipleModels: boolean;
+  configPath: string;
+  logFilePath: string;
+}
+
+/** Tool root, llama-server and model runtime_autostart would use. Starts nothing. */
+export async function runtimeDiscover(workspaceRoot: string): Promise<RuntimeDiscovery> {
+  return invoke<RuntimeDiscovery>("runtime_discover", { workspaceRoot });
+}
+
 /** GET http://127.0.0.1:port/health; true if 200. */
 export async function runtimeHealthCheck(port: number): Promise<boolean> {
   return invoke<boolean>("runtime_health_check", { port });
@@ -98,14 +160,14 @@ export async function runtimeStart(
   toolRoot: string | null,
   params: RuntimeStartParams | null,
   portOverride?: number | null,
-  logFilePath?: string | null
+  workspaceRoot?: string | null
 ): Promise<RuntimeStartResult> {
   return invoke<RuntimeStartResult>("runtime_start", {
     ggufPath,
     toolRoot: toolRoot || undefined,
     params: params || undefined,
     portOverride: portOverride ?? undefined,
-    logFilePath: logFilePath || undefined,
+    workspaceRoot: workspaceRoot || undefined,
   });
 }
 
@@ -1,22 +1,13 @@
 /**
  * Runtime config: .devassistant/runtime_config.json (not committed).
- * Auto-detect llama-server + GGUF, persist config, startLocalModel entry.
+ * Discovery, persistence and start live in Rust (runtime_discover / runtime_autostart).
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import {
-  findToolRoot,
-  resolveModelPath,
-  toolRootExists,
-  runtimeStart,
-  runtimeHealthCheck,
-} from "./runtimeApi";
+import { runtimeDiscover } from "./runtimeApi";
+import { describeError, errorMessage } from "../errors";
 
 const CONFIG_PATH = ".devassistant/runtime_config.json";
-const LOG_REL = ".devassistant/logs/llama-server.log";
-const DEFAULT_PORT = 11435;
-const DEFAULT_CTX = 4096;
-const LLAMA_REL = "runtime/llama/llama-server.exe";
 
 export interface RuntimeConfig {
   llamaServerPath: string;
@@ -39,6 +30,10 @@ export type StartLocalModelStatus =
 export interface StartLocalModelResult {
   status: StartLocalModelStatus;
   details?: string;
+  /** Port the server is (or would be) on. */
+  port?: number;
+  /** Config written back to runtime_config.json. */
+  config?: RuntimeConfig;
 }
 
 export async function readRuntimeConfig(
@@ -96,18 +91,10 @@ export async function ensureLogDir(workspaceRoot: string): Promise<EnsureLogDirR
     const logFilePath = await invoke<string>("workspace_ensure_log_dir", { workspaceRoot: root });
     return { ok: true, logFilePath };
   } catch (e) {
-    return { ok: false, error: String(e), workspaceRoot: root };
+    return { ok: false, error: errorMessage(e), workspaceRoot: root };
   }
 }
 
-function appendRuntimeLogMarker(logRoot: string, line: string): Promise<void> {
-  return invoke("workspace_append_file", {
-    workspaceRoot: logRoot,
-    path: LOG_REL,
-    content: line,
-  });
-}
-
 export interface DetectRuntimeStatusResult {
   runtimeFound: boolean;
   modelFound: boolean;
@@ -115,7 +102,7 @@ export interface DetectRuntimeStatusResult {
   workspaceRoot: string;
   /** Absolute tool root (first dir with runtime/llama + models/), or null. */
   toolRoot: string | null;
-  /** Absolute path we intend to write for llama-server.log. */
+  /** Active app log (llama-server output included) under .devassistant/logs. */
   logFilePath: string;
   /** Absolute path for runtime_config.json. */
   runtimeConfigPath: string;
@@ -135,80 +122,26 @@ export async function detectRuntimeStatus(
   };
   if (!raw) return empty;
 
-  const workspaceRootAbs = await resolveWorkspaceRoot(raw);
-  const root = workspaceRootAbs || raw;
-  const toolRoot = await findToolRoot(root);
-  const projectRoot = toolRoot ?? root;
-  /* Log/config under workspace root (same as sessions.json / settings.json). */
-  let logFilePath = "";
-  let runtimeConfigPath = "";
   try {
-    logFilePath = await invoke<string>("workspace_resolve_path", {
-      workspaceRoot: root,
-      path: LOG_REL,
-    });
-    runtimeConfigPath = await invoke<string>("workspace_resolve_path", {
-      workspaceRoot: root,
-      path: CONFIG_PATH,
-    });
-  } catch {
-    /* leave empty if resolve fails */
-  }
-
-  const runtimeFound = await toolRootExists(projectRoot, LLAMA_REL);
-  const scan = await scanModelsByMtime(projectRoot);
-
-  return {
-    runtimeFound,
-    modelFound: !!scan,
-    workspaceRoot: root,
-    toolRoot,
-    logFilePath,
-    runtimeConfigPath,
-  };
-}
-
-/**
- * Resolve workspace root to a deterministic absolute path.
- * Use app workspace root (folder user opened). Do NOT rely on cwd.
- */
-async function resolveWorkspaceRoot(workspaceRoot: string): Promise<string> {
-  const n = workspaceRoot.replace(/\\/g, "/").replace(/\/+$/, "");
-  if (!n) return n;
-  try {
-    return await invoke<string>("workspace_resolve_path", {
-      workspaceRoot: n,
-      path: ".",
-    });
+    const d = await runtimeDiscover(raw);
+    return {
+      runtimeFound: !!d.serverPath,
+      modelFound: !!d.modelPath,
+      workspaceRoot: d.workspaceRoot,
+      toolRoot: d.toolRoot,
+      logFilePath: d.logFilePath,
+      runtimeConfigPath: d.configPath,
+    };
   } catch {
-    return n;
+    return { ...empty, workspaceRoot: raw };
   }
 }
 
-/** baseDir: same folder as .devassistant/sessions.json and .devassistant/settings.json (workspace root). */
-function baseDirFromWorkspaceRoot(workspaceRoot: string): Promise<string> {
-  const raw = workspaceRoot.replace(/\\/g, "/").replace(/\/+$/, "");
-  if (!raw) return Promise.resolve("");
-  return resolveWorkspaceRoot(raw).then((r) => r || raw);
-}
-
-function stubConfig(port: number): RuntimeConfig {
-  const iso = new Date().toISOString();
-  return {
-    llamaServerPath: "",
-    modelPath: "",
-    host: "127.0.0.1",
-    port,
-    ctx: DEFAULT_CTX,
-    gpuLayers: 0,
-    createdAt: iso,
-  };
-}
-
 /**
- * Single entry: auto-detect runtime + model, persist config, start llama-server.
- * baseDir = workspace root (same as sessions.json / settings.json). Always writes
- * .devassistant/runtime_config.json and appends marker to .devassistant/logs/llama-server.log.
+ * Single entry: reuse or discover runtime + model, persist config, start or attach to
+ * llama-server. Always writes .devassistant/runtime_config.json. The outcome and the
+ * server's stdout/stderr are logged through tracing into the workspace's app log,
+ * .devassistant/logs/devassistant.log; there is no separate llama-server log.
  */
 export async function startLocalModel(
   workspaceRoot: string
@@ -217,92 +150,15 @@ export async function startLocalModel(
   if (!raw) {
     return { status: "error", details: "No workspace root." };
   }
-
-  const baseDir = await baseDirFromWorkspaceRoot(raw);
-  const port = DEFAULT_PORT;
-  const iso = new Date().toISOString();
-
-  const ensured = await ensureLogDir(baseDir);
-  if (!ensured.ok) {
-    await writeRuntimeConfig(baseDir, stubConfig(port)).catch(() => {});
-    return {
-      status: "error",
-      details: `ensureLogDir failed: ${ensured.error}. workspaceRoot=${ensured.workspaceRoot}.`,
-    };
-  }
-  const logPath = ensured.logFilePath;
-
-  const toolRoot = await findToolRoot(baseDir || raw);
-  const projectRoot = toolRoot ?? baseDir ?? raw;
-
-  const runtimeOk = await toolRootExists(projectRoot, LLAMA_REL);
-  if (!runtimeOk) {
-    const stub = stubConfig(port);
-    await writeRuntimeConfig(baseDir, stub).catch(() => {});
-    const marker = `[runtime] startLocalModel ${iso} status=missing_runtime model=none port=${port}`;
-    await appendRuntimeLogMarker(baseDir, marker).catch(() => {});
-    return { status: "missing_runtime", details: "runtime/llama/llama-server.exe not found." };
-  }
-
-  const scan = await scanModelsByMtime(projectRoot);
-  if (!scan) {
-    const stub = stubConfig(port);
-    await writeRuntimeConfig(baseDir, stub).catch(() => {});
-    const marker = `[runtime] startLocalModel ${iso} status=missing_model model=none port=${port}`;
-    await appendRuntimeLogMarker(baseDir, marker).catch(() => {});
-    return { status: "missing_model", details: "No .gguf in models/." };
-  }
-
-  if (scan.hadMultiple) {
-    console.warn("[startLocalModel] Multiple .gguf in models/; using most recently modified.");
-  }
-
-  const modelAbs = resolveModelPath(projectRoot, scan.path);
-  const llamaAbs = resolveModelPath(projectRoot, LLAMA_REL);
-
-  let healthy = false;
-  try {
-    healthy = await runtimeHealthCheck(port);
-  } catch {
-    healthy = false;
-  }
-
-  if (healthy) {
-    const cfg: RuntimeConfig = {
-      llamaServerPath: llamaAbs,
-      modelPath: modelAbs,
-      host: "127.0.0.1",
-      port,
-      ctx: DEFAULT_CTX,
-      gpuLayers: 0,
-      createdAt: iso,
-    };
-    await writeRuntimeConfig(baseDir, cfg).catch(() => {});
-    const marker = `[runtime] startLocalModel ${iso} status=already_running model=${modelAbs} port=${port}`;
-    await appendRuntimeLogMarker(baseDir, marker).catch(() => {});
-    return { status: "already_running", details: `Port ${port} in use (health OK).` };
-  }
-
-  const marker = `[runtime] startLocalModel ${iso} status=starting model=${modelAbs} port=${port}`;
-  await appendRuntimeLogMarker(baseDir, marker).catch(() => {});
-
-  const cfg: RuntimeConfig = {
-    llamaServerPath: llamaAbs,
-    modelPath: modelAbs,
-    host: "127.0.0.1",
-    port,
-    ctx: DEFAULT_CTX,
-    gpuLayers: 0,
-    createdAt: iso,
-  };
-  await writeRuntimeConfig(baseDir, cfg).catch(() => {});
-
   try {
-    await runtimeStart(modelAbs, toolRoot ?? projectRoot, {
-      context_length: DEFAULT_CTX,
-    }, port, logPath);
-    return { status: "started" };
+    const result = await invoke<{
+      status: StartLocalModelStatus;
+      details: string | null;
+      port: number;
+      config: RuntimeConfig;
+    }>("runtime_autostart", { workspaceRoot: raw });
+    return { ...result, details: result.details ?? undefined };
   } catch (e) {
-    return { status: "error", details: String(e) };
+    return { status: "error", details: describeError(e) };
   }
 }
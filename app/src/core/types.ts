@@ -5,6 +5,133 @@ export interface DirEntry {
   is_dir: boolean;
 }
 
+export type TextEncoding =
+  | "utf-8"
+  | "utf-8-bom"
+  | "utf-16le"
+  | "utf-16be"
+  | "utf-16le-nobom"
+  | "utf-16be-nobom"
+  | "latin-1";
+export type LineEnding = "lf" | "crlf" | "cr" | "mixed" | "none";
+
+/** Result of workspace_read_file_detailed. content is null for binary files. */
+export interface ReadFileResult {
+  content: string | null;
+  isBinary: boolean;
+  encoding: TextEncoding;
+  lineEnding: LineEnding;
+  hasTrailingNewline: boolean;
+  /** Size of the whole file, not of the returned range. */
+  sizeBytes: number;
+  byteStart: number;
+  byteEnd: number;
+  /** True when maxBytes cut the requested range short. */
+  truncated: boolean;
+}
+
+/** Range/size limits for workspace_read_file_detailed. Lines are 1-based, inclusive. */
+export interface ReadFileOptions {
+  byteStart?: number;
+  byteEnd?: number;
+  lineStart?: number;
+  lineEnd?: number;
+  maxBytes?: number;
+  mode?: "head" | "tail";
+}
+
+export interface FileStats {
+  sizeBytes: number;
+  lineCount: number;
+  isBinary: boolean;
+  encoding: TextEncoding;
+}
+
+/** Payload of the backend "workspace://changed" event. Paths are root-relative. */
+export interface WorkspaceChangedEvent {
+  workspaceRoot: string;
+  created: string[];
+  modified: string[];
+  removed: string[];
+  renamed: { from: string; to: string }[];
+}
+
+export interface ContentSearchOptions {
+  /** Treat the query as a regex (default: literal). */
+  regex?: boolean;
+  case?: "smart" | "sensitive" | "insensitive";
+  wholeWord?: boolean;
+  include?: string[];
+  exclude?: string[];
+  contextLines?: number;
+  maxMatchesPerFile?: number;
+  maxResults?: number;
+  timeoutMs?: number;
+  /** When set, batches are also emitted as "workspace://search-results". */
+  searchId?: string;
+}
+
+export interface ContentMatch {
+  path: string;
+  line: number;
+  column: number;
+  preview: string;
+  contextBefore: string[];
+  contextAfter: string[];
+}
+
+export interface ContentSearchResult {
+  matches: ContentMatch[];
+  filesSearched: number;
+  filesWithMatches: number;
+  truncated: boolean;
+  timedOut: boolean;
+  elapsedMs: number;
+}
+
+export type SymbolKind =
+  | "function"
+  | "method"
+  | "class"
+  | "struct"
+  | "enum"
+  | "trait"
+  | "interface"
+  | "impl"
+  | "module"
+  | "type"
+  | "constant";
+
+/** A declaration from the tree-sitter symbol index. Lines are 1-based, inclusive. */
+export interface CodeSymbol {
+  name: string;
+  kind: SymbolKind;
+  path: string;
+  startLine: number;
+  endLine: number;
+  container: string | null;
+  exported: boolean;
+  signature: string;
+}
+
+export interface RepoMapFile {
+  path: string;
+  score: number;
+  /** Symbols written for this file (0 when only the path fit). */
+  symbols: number;
+}
+
+/** Ranked outline of the workspace (paths + key signatures) trimmed to a token budget. */
+export interface RepoMap {
+  map: string;
+  estimatedTokens: number;
+  tokenBudget: number;
+  files: RepoMapFile[];
+  totalFiles: number;
+  ranking: "references" | "recency";
+  truncated: boolean;
+}
+
 export interface FileTreeNode {
   name: string;
   path: string;
@@ -20,6 +147,63 @@ export interface ProjectManifest {
   dependencyIndicators: Record<string, string[]>;
 }
 
+/** Something detected in the project and where it was found ("app/package.json#dependencies.react"). */
+export interface DetectedItem {
+  name: string;
+  source: string;
+}
+
+export interface ProjectEntryPoint {
+  /** Workspace-relative. */
+  path: string;
+  kind: "main" | "bin" | "lib" | "module" | "script";
+  source: string;
+}
+
+export type ProjectCommandKind =
+  | "build"
+  | "test"
+  | "lint"
+  | "typecheck"
+  | "format"
+  | "dev"
+  | "run"
+  | "other";
+
+export interface ProjectCommand {
+  /** Script or target name. */
+  name: string;
+  kind: ProjectCommandKind;
+  command: string;
+  /** Workspace-relative directory ("" for the root). */
+  cwd: string;
+  /** e.g. "app/package.json#scripts.build" or "Makefile:12". */
+  source: string;
+}
+
+export interface ProjectPackage {
+  manifest: string;
+  ecosystem: "node" | "rust" | "python" | "go" | "dart";
+  name: string | null;
+  version: string | null;
+  /** Cargo features. */
+  features: string[];
+}
+
+/** Parsed from the manifests by project_detect. */
+export interface ProjectInfo {
+  detectedTypes: string[];
+  /** Most files first. */
+  languages: DetectedItem[];
+  frameworks: DetectedItem[];
+  entryPoints: ProjectEntryPoint[];
+  /** Root-level commands first. */
+  commands: ProjectCommand[];
+  packages: ProjectPackage[];
+  importantFiles: string[];
+  truncated: boolean;
+}
+
 /** Commands inferred from package.json scripts or common tooling defaults. */
 export interface DetectedCommands {
   build?: string;
@@ -47,6 +231,118 @@ export interface WorkspaceSettings {
   modelPath?: string;
   /** Port for llama-server (default 11435). */
   port?: number;
+  /** Extra gitignore-style patterns skipped by search, snapshot and indexing. */
+  extraIgnore?: string[];
+  /** Record git checkpoints before/after applying patches and revert from them (default true). */
+  checkpoints?: boolean;
+}
+
+export interface RuntimeSettings {
+  /** Loopback address llama-server binds to; user settings only. */
+  host: string;
+  port: number;
+  modelPath: string | null;
+  ctx: number;
+  /** -1 offloads all layers. */
+  gpuLayers: number;
+  threads: number | null;
+}
+
+export interface SettingsLimits {
+  /** Levels walked up from the workspace looking for runtime/llama + models/. */
+  toolRootMaxLevels: number;
+  /** Content search results when a search sets no maxResults. */
+  searchMaxResults: number;
+  /** Files listed by workspace_walk_snapshot. */
+  snapshotMaxFiles: number;
+  /** Files returned by a search by name that sets no limit. */
+  nameSearchDefaultResults: number;
+  /** Cap on a search by name limit. */
+  nameSearchMaxResults: number;
+}
+
+export interface LoggingSettings {
+  /** tracing filter, e.g. "info" or "info,llama_server=warn". RUST_LOG overrides it. */
+  level: string;
+  /** Active log file rotates past this size and at the start of each day. */
+  maxFileMb: number;
+  /** Log files kept per workspace, the active one included. */
+  maxFiles: number;
+  retentionDays: number;
+}
+
+/** Read only from the user settings file and environment; settings_set cannot change them. */
+export interface SecuritySettings {
+  /** Follow symlinks to targets outside the workspace root. */
+  allowExternalSymlinks: boolean;
+  /** Globs workspace commands refuse to write; no slash matches the file name at any depth. */
+  protectedPaths: string[];
+}
+
+/** Effective settings: defaults < user config < workspace .devassistant/settings.json < DEVASSISTANT_* env. */
+export interface AppSettings {
+  version: number;
+  autoPacksEnabled: boolean;
+  enabledPacks: string[];
+  extraIgnore: string[];
+  checkpoints: boolean;
+  runtime: RuntimeSettings;
+  limits: SettingsLimits;
+  logging: LoggingSettings;
+  security: SecuritySettings;
+}
+
+export type SettingsLayer = "default" | "user" | "workspace" | "env";
+
+/** Partial settings for settings_set; null removes a key from that layer's file. */
+export type SettingsPatch = {
+  [K in keyof AppSettings]?: AppSettings[K] extends object
+    ? AppSettings[K] extends unknown[]
+      ? AppSettings[K] | null
+      : { [P in keyof AppSettings[K]]?: AppSettings[K][P] | null } | null
+    : AppSettings[K] | null;
+};
+
+export interface ResolvedSettings {
+  settings: AppSettings;
+  /** Dotted key ("runtime.port") -> layer that set it. */
+  sources: Record<string, SettingsLayer>;
+  /** Skipped layers, unknown keys and pending migrations. */
+  warnings: string[];
+  userPath: string | null;
+  workspacePath: string | null;
+}
+
+/** One app log line (.devassistant/logs/devassistant.log is JSON lines of these). */
+export interface LogRecord {
+  /** RFC 3339 UTC with milliseconds. */
+  ts: string;
+  level: "TRACE" | "DEBUG" | "INFO" | "WARN" | "ERROR";
+  /** e.g. "app", "runtime", "llama_server". */
+  target: string;
+  /** Process the line belongs to, e.g. "llama-server:11435:4242". */
+  instance?: string;
+  message: string;
+  fields?: Record<string, string>;
+}
+
+export interface LogQuery {
+  minLevel?: LogRecord["level"];
+  /** Target prefix. */
+  target?: string;
+  instance?: string;
+  /** Case-insensitive substring of the message. */
+  contains?: string;
+  /** RFC 3339; records at or after this time. */
+  since?: string;
+  /** Most recent N matches (default 200). */
+  limit?: number;
+}
+
+/** Payload of settings://changed. */
+export interface SettingsChangedEvent {
+  workspaceRoot: string | null;
+  resolved: ResolvedSettings;
 }
 
 export interface ModelContext {
@@ -59,6 +355,50 @@ export interface ModelContext {
   targetFiles?: string[];
   /** Retrieved knowledge chunks (title + sourcePath + chunkText). */
   knowledgeChunks?: KnowledgeChunkRef[];
+  /** What context packing included, trimmed or dropped, and why. */
+  contextManifest?: ContextManifestEntry[];
+}
+
+export type PackMode = "whole" | "symbols" | "headTail";
+
+export interface ContextManifestEntry {
+  /** File path, "title (sourcePath)" for knowledge chunks, or "prompt". */
+  id: string;
+  /** "prompt" appears only when the prompt alone does not fit the context window. */
+  role: "prompt" | "selected" | "candidate" | "knowledge";
+  decision: "included" | "trimmed" | "dropped";
+  mode: PackMode | null;
+  tokens: number;
+  originalTokens: number;
+  reason: string | null;
+}
+
+export interface PackContextRequest {
+  prompt: string;
+  selectedPaths: string[];
+  candidatePaths?: string[];
+  knowledgeChunks?: KnowledgeChunkRef[];
+  /** Default: runtime ctx size minus reserve and overhead. */
+  tokenBudget?: number;
+  reserveTokens?: number;
+}
+
+export interface PackedFile {
+  path: string;
+  content: string;
+  mode: PackMode;
+  tokens: number;
+}
+
+export interface PackContextResult {
+  selectedFiles: PackedFile[];
+  suggestedFiles: PackedFile[];
+  knowledgeChunks: KnowledgeChunkRef[];
+  manifest: ContextManifestEntry[];
+  budgetTokens: number;
+  usedTokens: number;
+  promptTokens: number;
+  exact: boolean;
 }
 
 /** One knowledge chunk reference for context (and UI display). */
@@ -89,12 +429,193 @@ export type AgentMode = "Coder" | "Planner" | "Reviewer";
 
 export type SessionStatus = "proposed" | "pending" | "applied" | "reverted";
 
-/** Check step record (Step 3 will populate). */
+/** Check step record from checks://finished; MemoryStore.appendCheck adds it to a session. */
 export interface CheckRecord {
   step: string;
   command: string;
+  /** -1 when killed (timeout/cancel) or ended by a signal. */
   exitCode: number;
   outputPathRef?: string;
+  runId?: string;
+  checkId?: string;
+  durationMs?: number;
+  timedOut?: boolean;
+  cancelled?: boolean;
+  /** Parsed diagnostics by severity. */
+  errors?: number;
+  warnings?: number;
+  finishedAt?: string;
+}
+
+export type GitChangeKind =
+  | "added"
+  | "modified"
+  | "deleted"
+  | "renamed"
+  | "copied"
+  | "typechange"
+  | "untracked"
+  | "conflicted";
+
+export interface GitHead {
+  /** null when detached. */
+  branch: string | null;
+  /** null before the first commit. */
+  commit: string | null;
+  summary: string | null;
+  detached: boolean;
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+}
+
+export interface GitFileStatus {
+  /** Workspace-relative. */
+  path: string;
+  staged: GitChangeKind | null;
+  unstaged: GitChangeKind | null;
+  oldPath: string | null;
+}
+
+export interface GitStatus {
+  isRepo: boolean;
+  repoRoot: string | null;
+  head: GitHead | null;
+  files: GitFileStatus[];
+}
+
+export interface GitFileDiff {
+  path: string;
+  oldPath: string | null;
+  status: GitChangeKind;
+  additions: number;
+  deletions: number;
+  binary: boolean;
+  /** Unified diff; headers use repository paths. */
+  patch: string;
+  truncated: boolean;
+}
+
+export interface GitDiff {
+  staged: boolean;
+  files: GitFileDiff[];
+  truncated: boolean;
+}
+
+export interface GitCommit {
+  id: string;
+  shortId: string;
+  summary: string;
+  author: string;
+  email: string;
+  /** RFC 3339. */
+  time: string;
+}
+
+export interface GitBlameLine {
+  line: number;
+  /** null for lines changed since HEAD. */
+  commit: string | null;
+  shortId: string | null;
+  author: string | null;
+  time: string | null;
+  summary: string | null;
+  content: string;
+}
+
+export interface Checkpoint {
+  commit: string;
+  shortId: string;
+  /** "before" | "after" for applied patches. */
+  label: string;
+  time: string;
+  /** Workspace-relative files captured (empty = whole workspace). */
+  paths: string[];
+}
+
+export interface SessionCheckpoints {
+  sessionId: string;
+  refName: string;
+  /** Newest first. */
+  checkpoints: Checkpoint[];
+}
+
+export interface CheckpointRestoreResult {
+  commit: string;
+  restored: string[];
+  removed: string[];
+}
+
+export interface SessionCommitPlan {
+  sessionId: string;
+  /** Generated from the session's prompt and explanation. */
+  message: string;
+  /** Workspace-relative files that would be committed. */
+  files: string[];
+  /** Repository paths already staged that are not part of the session (block the commit). */
+  unrelatedStaged: string[];
+  branchSuggestion: string;
+  currentBranch: string | null;
+}
+
+export interface SessionCommitResult {
+  commit: string;
+  shortId: string;
+  branch: string | null;
+  createdBranch: boolean;
+  message: string;
+  files: string[];
+}
+
+export type DiagnosticSeverity = "error" | "warning" | "info";
+
+export type DiagnosticFormat = "cargo" | "tsc" | "eslint" | "pytest" | "ruff" | "auto";
+
+/** A compiler/linter/test failure location parsed from tool output. */
+export interface Diagnostic {
+  /** Workspace-relative; null when outside the workspace or unknown. */
+  file: string | null;
+  line: number | null;
+  column: number | null;
+  severity: DiagnosticSeverity;
+  /** E0308, TS2322, no-unused-vars, F401, AssertionError… */
+  code: string | null;
+  message: string;
+  source: Exclude<DiagnosticFormat, "auto">;
+}
+
+/** A check detected from the project's manifests (checks_list). Only these can run. */
+export interface CheckSpec {
+  id: string;
+  step: "build" | "test" | "lint" | "typecheck";
+  /** Root-relative working directory ("" for the root). */
+  cwd: string;
+  program: string;
+  args: string[];
+  command: string;
+  /** Parser applied to the output when the check finishes. */
+  format: DiagnosticFormat;
+}
+
+export interface CheckRunStarted {
+  runId: string;
+  check: CheckSpec;
+  outputPathRef: string;
+}
+
+/** Payload of "checks://output". */
+export interface CheckOutputEvent {
+  runId: string;
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
+/** Payload of "checks://finished". */
+export interface CheckFinishedEvent {
+  workspaceRoot: string;
+  sessionId: string | null;
+  record: CheckRecord;
+  diagnostics: Diagnostic[];
 }
 
 export interface TouchedFileRecord {
@@ -146,3 +667,44 @@ export interface RetrievedChunk {
   chunkText: string;
   score: number;
 }
+
+/** Stable error categories returned by Rust commands (error.rs ErrorKind). */
+export type AppErrorKind =
+  | "InvalidInput"
+  | "NotFound"
+  | "EscapesWorkspace"
+  | "PermissionDenied"
+  | "Conflict"
+  | "Unsupported"
+  | "RuntimeNotStarted"
+  | "RuntimeMissing"
+  | "ModelMissing"
+  | "ServerUnreachable"
+  | "ServerHttp"
+  | "Timeout"
+  | "Io"
+  | "Parse"
+  | "Other";
+
+/** Error payload rejected by Rust commands. details holds e.g. path, endpoint, body. */
+export type AppError =
+  | { kind: "ServerHttp"; status: number; message: string; details?: Record<string, unknown> }
+  | {
+      kind: Exclude<AppErrorKind, "ServerHttp">;
+      message: string;
+      details?: Record<string, unknown>;
+    };
+
+/** What workspace commands may do in an opened workspace (registry.rs). */
+export interface WorkspacePolicy {
+  /** Project files cannot be changed; .devassistant/ app data still can. */
+  readOnly: boolean;
+  /** Globs never written; from the user setting security.protectedPaths. */
+  protected: string[];
+}
+
+/** A workspace registered through the native folder dialog. */
+export interface OpenedWorkspace {
+  root: string;
+  policy: WorkspacePolicy;
+}
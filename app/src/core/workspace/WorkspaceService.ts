@@ -5,7 +5,41 @@
 
 import { open } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
-import type { DirEntry, FileTreeNode } from "../types";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  Checkpoint,
+  CheckpointRestoreResult,
+  CheckFinishedEvent,
+  CheckOutputEvent,
+  CheckRunStarted,
+  CheckSpec,
+  CodeSymbol,
+  Diagnostic,
+  DiagnosticFormat,
+  ContentSearchOptions,
+  ContentSearchResult,
+  DirEntry,
+  FileStats,
+  FileTreeNode,
+  GitBlameLine,
+  GitCommit,
+  GitDiff,
+  GitHead,
+  GitStatus,
+  SessionCheckpoints,
+  SessionCommitPlan,
+  SessionCommitResult,
+  PackContextRequest,
+  ProjectInfo,
+  PackContextResult,
+  ReadFileOptions,
+  ReadFileResult,
+  RepoMap,
+  SymbolKind,
+  OpenedWorkspace,
+  WorkspaceChangedEvent,
+  WorkspacePolicy,
+} from "../types";
 
 const HARD_IGNORES = new Set([
   "node_modules", "dist", "build", "out", ".git", ".next", ".nuxt",
@@ -17,12 +51,18 @@ const NO_WORKSPACE = "Open a workspace first.";
 
 export class WorkspaceService {
   private _root: string | null = null;
+  private _policy: WorkspacePolicy | null = null;
   private _gitignorePatterns: string[] = [];
 
   get root(): string | null {
     return this._root;
   }
 
+  /** Policy the backend enforces for the open root. */
+  get policy(): WorkspacePolicy | null {
+    return this._policy;
+  }
+
   /** Throws if no root; use before any workspace invoke. */
   private _ensureRoot(): string {
     if (this._root == null || this._root === "") {
@@ -32,16 +72,45 @@ export class WorkspaceService {
     return this._root;
   }
 
-  async openWorkspace(): Promise<string | null> {
-    const selected = await open({
-      directory: true,
-      multiple: false,
-      title: "Open workspace folder",
-    });
-    if (typeof selected !== "string") return null;
-    this._root = selected;
+  /**
+   * Pick a folder with the native dialog. The backend registers it; workspace commands
+   * refuse any root that was not opened this way.
+   */
+  async openWorkspace(readOnly = false): Promise<string | null> {
+    const opened = await invoke<OpenedWorkspace | null>("workspace_open", { readOnly });
+    if (!opened) return null;
+    await this._switchTo(opened);
+    return opened.root;
+  }
+
+  /**
+   * If the open root belongs to a larger project workspace, the backend asks the user
+   * (native dialog) whether to open that instead. Returns the new root, or null.
+   */
+  async openEnclosingWorkspace(): Promise<string | null> {
+    const workspaceRoot = this._ensureRoot();
+    const opened = await invoke<OpenedWorkspace | null>("workspace_open_enclosing", { workspaceRoot });
+    if (!opened) return null;
+    await this._switchTo(opened);
+    return opened.root;
+  }
+
+  /** Making the workspace writable again asks for native confirmation and may reject. */
+  async setReadOnly(readOnly: boolean): Promise<WorkspacePolicy> {
+    const workspaceRoot = this._ensureRoot();
+    const opened = await invoke<OpenedWorkspace>("workspace_set_read_only", { workspaceRoot, readOnly });
+    this._policy = opened.policy;
+    return opened.policy;
+  }
+
+  private async _switchTo(opened: OpenedWorkspace): Promise<void> {
+    const previous = this._root;
+    this._root = opened.root;
+    this._policy = opened.policy;
+    if (previous && previous !== opened.root) {
+      await invoke("workspace_close", { workspaceRoot: previous }).catch(() => {});
+    }
     await this._loadGitignore();
-    return selected;
   }
 
   private async _loadGitignore(): Promise<void> {
@@ -110,6 +179,25 @@ export class WorkspaceService {
     });
   }
 
+  /** Read with encoding/line-ending/binary detection; never fails on non-UTF-8. */
+  async readFileDetailed(relPath: string, options?: ReadFileOptions): Promise<ReadFileResult> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<ReadFileResult>("workspace_read_file_detailed", {
+      workspaceRoot,
+      path: relPath,
+      options: options ?? null,
+    });
+  }
+
+  /** Size and line count without reading the file into the webview. */
+  async fileStats(relPath: string): Promise<FileStats> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<FileStats>("workspace_file_stats", {
+      workspaceRoot,
+      path: relPath,
+    });
+  }
+
   async writeFile(workspaceRoot: string, relPath: string, content: string): Promise<void> {
     await invoke("write_project_file", {
       workspaceRoot,
@@ -134,15 +222,75 @@ export class WorkspaceService {
     });
   }
 
-  /** Search files by name under workspace root. Returns relative paths (max 20). */
-  async searchFilesByName(workspaceRoot: string, fileName: string): Promise<string[]> {
+  /**
+   * Fuzzy-search files by relative path under workspace root (e.g. "wrkspcsvc", "core/patch").
+   * Returns best-first relative paths (default limits.nameSearchDefaultResults).
+   */
+  async searchFilesByName(workspaceRoot: string, fileName: string, limit?: number): Promise<string[]> {
     const result = await invoke<string[]>("workspace_search_files_by_name", {
       workspaceRoot,
       fileName: fileName.trim(),
+      limit: limit ?? null,
     });
     return Array.isArray(result) ? result : [];
   }
 
+  /** Find symbols by name across the workspace (exact, then prefix, then substring). */
+  async findSymbol(query: string, kind?: SymbolKind, limit?: number): Promise<CodeSymbol[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<CodeSymbol[]>("workspace_find_symbol", {
+      workspaceRoot,
+      query,
+      kind: kind ?? null,
+      limit: limit ?? null,
+    });
+  }
+
+  /** Outline of one file in source order (empty for unsupported languages). */
+  async symbolsInFile(relPath: string): Promise<CodeSymbol[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<CodeSymbol[]>("workspace_symbols_in_file", {
+      workspaceRoot,
+      path: relPath,
+    });
+  }
+
+  /** Innermost symbol containing a 1-based line, e.g. to quote a whole function. */
+  async enclosingSymbol(relPath: string, line: number): Promise<CodeSymbol | null> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<CodeSymbol | null>("workspace_enclosing_symbol", {
+      workspaceRoot,
+      path: relPath,
+      line,
+    });
+  }
+
+  /** Fit files and knowledge into the token budget; returns packed content plus a manifest. */
+  async packContext(request: PackContextRequest): Promise<PackContextResult> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<PackContextResult>("context_pack", { workspaceRoot, request });
+  }
+
+  /** Ranked outline of the workspace within a token budget; focus paths rank first. */
+  async repoMap(tokenBudget?: number, focusPaths?: string[]): Promise<RepoMap> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<RepoMap>("workspace_repo_map", {
+      workspaceRoot,
+      tokenBudget: tokenBudget ?? null,
+      focusPaths: focusPaths ?? null,
+    });
+  }
+
+  /** Full-text search over file contents (literal by default). */
+  async searchContent(query: string, options?: ContentSearchOptions): Promise<ContentSearchResult> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<ContentSearchResult>("workspace_search_content", {
+      workspaceRoot,
+      query,
+      options: options ?? null,
+    });
+  }
+
   async exists(relPath: string): Promise<boolean> {
     if (this._root == null || this._root === "") {
       console.warn("[WorkspaceService] workspace_exists blocked: no workspace root.");
@@ -158,6 +306,176 @@ export class WorkspaceService {
     }
   }
 
+  /**
+   * Start the backend watcher for the open root and subscribe to batched change events.
+   * Returns a function that unsubscribes and stops the watcher.
+   */
+  async watch(onChange: (e: WorkspaceChangedEvent) => void): Promise<UnlistenFn> {
+    const workspaceRoot = this._ensureRoot();
+    const unlisten = await listen<WorkspaceChangedEvent>("workspace://changed", (e) => onChange(e.payload));
+    await invoke("workspace_watch_start", { workspaceRoot });
+    return async () => {
+      unlisten();
+      await invoke("workspace_watch_stop", { workspaceRoot }).catch(() => {});
+    };
+  }
+
+  /** Checks (build/test/lint/typecheck) detected from the project's manifests. */
+  async listChecks(): Promise<CheckSpec[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<CheckSpec[]>("checks_list", { workspaceRoot });
+  }
+
+  /**
+   * Start a detected check by id. Output arrives via onCheckEvents; sessionId is echoed in
+   * the finished event so the caller can record it with MemoryStore.appendCheck.
+   */
+  async runCheck(checkId: string, opts?: { sessionId?: string; timeoutMs?: number }): Promise<CheckRunStarted> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<CheckRunStarted>("checks_run", {
+      workspaceRoot,
+      checkId,
+      sessionId: opts?.sessionId ?? null,
+      timeoutMs: opts?.timeoutMs ?? null,
+    });
+  }
+
+  /** Cancel a running check. Returns false if it already finished. */
+  async cancelCheck(runId: string): Promise<boolean> {
+    return invoke<boolean>("checks_cancel", { runId });
+  }
+
+  /** Subscribe to check output lines and completions. Returns a function that unsubscribes. */
+  async onCheckEvents(
+    onOutput: (e: CheckOutputEvent) => void,
+    onFinished: (e: CheckFinishedEvent) => void
+  ): Promise<UnlistenFn> {
+    const unOutput = await listen<CheckOutputEvent>("checks://output", (e) => onOutput(e.payload));
+    const unFinished = await listen<CheckFinishedEvent>("checks://finished", (e) => onFinished(e.payload));
+    return () => {
+      unOutput();
+      unFinished();
+    };
+  }
+
+  /**
+   * Parse build/lint/test output (inline text or a workspace-relative log, e.g. a check's
+   * outputPathRef) into diagnostics. cwd is the root-relative directory the tool ran in.
+   */
+  async parseDiagnostics(
+    source: { output: string } | { logPath: string },
+    opts?: { format?: DiagnosticFormat; cwd?: string }
+  ): Promise<Diagnostic[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<Diagnostic[]>("diagnostics_parse", {
+      workspaceRoot,
+      output: "output" in source ? source.output : null,
+      logPath: "logPath" in source ? source.logPath : null,
+      format: opts?.format ?? null,
+      cwd: opts?.cwd ?? null,
+    });
+  }
+
+  /** Languages, frameworks, entry points and commands parsed from the workspace's manifests. */
+  async detectProject(): Promise<ProjectInfo> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<ProjectInfo>("project_detect", { workspaceRoot });
+  }
+
+  /** Git working-tree status (isRepo false when the workspace is not under git). */
+  async gitStatus(): Promise<GitStatus> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<GitStatus>("git_status", { workspaceRoot });
+  }
+
+  /** Current branch, HEAD commit and upstream ahead/behind. */
+  async gitHead(): Promise<GitHead> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<GitHead>("git_head", { workspaceRoot });
+  }
+
+  /** Unstaged (default, untracked included) or staged diff, optionally for one path. */
+  async gitDiff(opts?: { staged?: boolean; path?: string; contextLines?: number }): Promise<GitDiff> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<GitDiff>("git_diff", {
+      workspaceRoot,
+      staged: opts?.staged ?? false,
+      path: opts?.path ?? null,
+      contextLines: opts?.contextLines ?? null,
+    });
+  }
+
+  /** Recent commits from HEAD, newest first; with path, only those touching it. */
+  async gitLog(limit?: number, path?: string): Promise<GitCommit[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<GitCommit[]>("git_log", { workspaceRoot, limit: limit ?? null, path: path ?? null });
+  }
+
+  /** Blame for lines startLine..endLine (1-based, inclusive) of the working-tree file. */
+  async gitBlame(path: string, startLine: number, endLine: number): Promise<GitBlameLine[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<GitBlameLine[]>("git_blame", { workspaceRoot, path, startLine, endLine });
+  }
+
+  /** Record the current content of paths (or the whole workspace) as a session checkpoint. */
+  async createCheckpoint(sessionId: string, label: string, paths?: string[]): Promise<Checkpoint> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<Checkpoint>("git_checkpoint_create", { workspaceRoot, sessionId, label, paths: paths ?? null });
+  }
+
+  /** Sessions with git checkpoints, most recent first. */
+  async listCheckpoints(): Promise<SessionCheckpoints[]> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<SessionCheckpoints[]>("git_checkpoint_list", { workspaceRoot });
+  }
+
+  /** Diff between two checkpoints (label or commit; to may be "worktree"). Default: oldest to newest. */
+  async diffCheckpoints(sessionId: string, from?: string, to?: string): Promise<GitDiff> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<GitDiff>("git_checkpoint_diff", {
+      workspaceRoot,
+      sessionId,
+      from: from ?? null,
+      to: to ?? null,
+      contextLines: null,
+    });
+  }
+
+  /** Write files back to a checkpoint (label or commit); index and HEAD are untouched. */
+  async restoreCheckpoint(sessionId: string, checkpoint: string, paths?: string[]): Promise<CheckpointRestoreResult> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<CheckpointRestoreResult>("git_checkpoint_restore", {
+      workspaceRoot,
+      sessionId,
+      checkpoint,
+      paths: paths ?? null,
+    });
+  }
+
+  /** Generated message, files and blocking staged changes for committing an applied session. */
+  async sessionCommitPlan(sessionId: string): Promise<SessionCommitPlan> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<SessionCommitPlan>("git_session_commit_plan", { workspaceRoot, sessionId });
+  }
+
+  /**
+   * Stage exactly the session's files and commit them, optionally on a new branch.
+   * Rejects when unrelated changes are staged unless allowUnrelatedStaged.
+   */
+  async commitSession(
+    sessionId: string,
+    opts?: { message?: string; branch?: string; allowUnrelatedStaged?: boolean }
+  ): Promise<SessionCommitResult> {
+    const workspaceRoot = this._ensureRoot();
+    return invoke<SessionCommitResult>("git_session_commit", {
+      workspaceRoot,
+      sessionId,
+      message: opts?.message ?? null,
+      branch: opts?.branch ?? null,
+      allowUnrelatedStaged: opts?.allowUnrelatedStaged ?? null,
+    });
+  }
+
   normalizeRel(p: string): string {
     return p.replace(/\\/g, "/").replace(/^\/+/, "");
   }